@@ -0,0 +1,63 @@
+//! Throughput benchmark for `ReleaseParser::parse`.
+//!
+//! Run with `cargo bench`. Prints releases parsed per second for a single
+//! thread and for several threads sharing the compiled rule set.
+
+use scene_release::ReleaseParser;
+use std::hint::black_box;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const RELEASES: &[(&str, &str)] = &[
+    ("tv", "24.S02E02.9.00.Uhr.bis.10.00.Uhr.German.DL.TV.Dubbed.DVDRip.SVCD.READ.NFO-c0nFuSed"),
+    ("movie", "The.Matrix.1999.1080p.BluRay.x264-GROUP"),
+    ("movie", "12.12 The Day (2023) {tmdb-919207} [Remux-1080p][TrueHD 5.1][AVC]-HBO"),
+    ("tv", "Arrow (2012) - S05E04 - Penance [Bluray-1080p Remux][DTS-HD MA 5.1][AVC]-EPSiLON"),
+    ("tv", "Seinfeld (1989) {tvdb-79169} - S01E01 - The Seinfeld Chronicles [Bluray-2160p Remux Proper][DV HDR10][DTS-HD MA 5.1][HEVC]-NEWMAN"),
+    ("movie", "Sharks.of.the.Corn.2021.1080p.AMZN.WEB-DL.DDP2.0.H.264-SQS"),
+    ("tv", "[GM-Team][国漫][仙逆][Renegade Immortal][2023][119][AVC][GB][1080P]"),
+    ("tv", "Digimon Beatbreak (2025) S01E11 (1080p CR WEB-DL H264 AAC 2.0) [AnoZu]"),
+    ("tv", "Running.Man.E780.This.is.the.Romance.of.It.Continues.1080p.VIU.WEB-DL.H264.AAC-MMR"),
+    ("movie", "Doctor (2012) 1080p WAVVE WEB-DL AAC H.264-GNom"),
+];
+
+const ROUNDS: usize = 200;
+const THREADS: usize = 4;
+
+fn parse_all(rounds: usize) -> usize {
+    let tv = ReleaseParser::new("tv");
+    let movie = ReleaseParser::new("movie");
+    let mut parsed = 0;
+    for _ in 0..rounds {
+        for (release_type, release) in RELEASES {
+            let parser = if *release_type == "tv" { &tv } else { &movie };
+            black_box(parser.parse(black_box(release)));
+            parsed += 1;
+        }
+    }
+    parsed
+}
+
+fn report(label: &str, parsed: usize, elapsed: Duration) {
+    let per_second = parsed as f64 / elapsed.as_secs_f64();
+    let per_release = elapsed / parsed as u32;
+    println!("{:<24} {:>8} releases in {:>10.2?}  ({:>10.0} releases/s, {:>8.2?}/release)", label, parsed, elapsed, per_second, per_release);
+}
+
+fn main() {
+    // Warm up: the first parse compiles the shared rule set
+    let start = Instant::now();
+    parse_all(1);
+    report("first parse (cold)", RELEASES.len(), start.elapsed());
+
+    let start = Instant::now();
+    let parsed = parse_all(ROUNDS);
+    report("single thread", parsed, start.elapsed());
+
+    let start = Instant::now();
+    let parsed: usize = thread::scope(|scope| {
+        let handles: Vec<_> = (0..THREADS).map(|_| scope.spawn(|| parse_all(ROUNDS))).collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).sum()
+    });
+    report(&format!("{} threads", THREADS), parsed, start.elapsed());
+}
@@ -0,0 +1,472 @@
+//! `scene-release`: parse release names and library paths from the shell.
+//!
+//! Exit codes: 0 when everything parsed, 1 when a name or path could not be
+//! parsed or a scanned file was left unmatched (the others are still
+//! printed), 2 for usage errors and 3 for I/O errors.
+
+use scene_release::classify::RELEASE_TYPES;
+use scene_release::{ParseError, ParsedRelease, PathInfo, ReleaseParser};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: scene-release [OPTIONS] <COMMAND>
+
+Commands:
+  parse <NAME>...   Parse release names
+  batch [FILE]      Parse one name per line of FILE, or of stdin without FILE or with -
+  path <FILE>...    Parse library paths: series, season and movie folders
+  scan <DIR>        List the series and movies of a library
+  explain <NAME>    Show which part of a name gave which field
+  serve             Answer parse requests over HTTP (with the server feature)
+
+Options:
+  -t, --type <TYPE>      tv, movie, music, game, software or auto [default: auto]
+      --strict           Fail names without a title or group
+      --json             Print JSON instead of text
+  -f, --format <FORMAT>  Output of batch: ndjson or csv [default: ndjson]
+      --plain            Explain without colors
+      --address <ADDR>   Address to serve on [default: 127.0.0.1:8080]
+  -h, --help             Print this help
+  -V, --version          Print the version";
+
+/// A run that stopped early, with its exit code.
+#[derive(Debug, PartialEq, Eq)]
+enum Failure {
+    Usage(String),
+    Io(String),
+}
+
+/// Whether every input parsed.
+type Outcome = Result<bool, Failure>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BatchFormat {
+    Ndjson,
+    Csv,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    release_type: String,
+    strict: bool,
+    json: bool,
+    format: BatchFormat,
+    plain: bool,
+    address: Option<String>,
+    command: String,
+    arguments: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => return ExitCode::SUCCESS,
+        Err(message) => return fail(Failure::Usage(message)),
+    };
+    match run(&options, &mut io::stdout().lock()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(failure) => fail(failure),
+    }
+}
+
+fn fail(failure: Failure) -> ExitCode {
+    match failure {
+        Failure::Usage(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            ExitCode::from(2)
+        }
+        Failure::Io(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::from(3)
+        }
+    }
+}
+
+impl Options {
+    /// `None` when help or the version was printed.
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Self {
+            release_type: "auto".to_string(),
+            strict: false,
+            json: false,
+            format: BatchFormat::Ndjson,
+            plain: false,
+            address: None,
+            command: String::new(),
+            arguments: Vec::new(),
+        };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg.clone(), None),
+            };
+            let mut value = |name: &str| inline.clone().or_else(|| args.next()).ok_or_else(|| format!("{} needs a value", name));
+            match flag.as_str() {
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    return Ok(None);
+                }
+                "-V" | "--version" => {
+                    println!("scene-release {}", env!("CARGO_PKG_VERSION"));
+                    return Ok(None);
+                }
+                "-t" | "--type" => {
+                    let release_type = value("--type")?.to_lowercase();
+                    if release_type != "auto" && !RELEASE_TYPES.contains(&release_type.as_str()) {
+                        return Err(format!("unknown release type {:?}", release_type));
+                    }
+                    options.release_type = release_type;
+                }
+                "-f" | "--format" => {
+                    options.format = match value("--format")?.to_lowercase().as_str() {
+                        "ndjson" | "json" => BatchFormat::Ndjson,
+                        "csv" => BatchFormat::Csv,
+                        other => return Err(format!("unknown batch format {:?}", other)),
+                    }
+                }
+                "--strict" => options.strict = true,
+                "--json" => options.json = true,
+                "--plain" => options.plain = true,
+                "--address" => options.address = Some(value("--address")?),
+                "-" => options.arguments.push(arg),
+                _ if flag.starts_with('-') => return Err(format!("unknown option {:?}", arg)),
+                _ if options.command.is_empty() => options.command = arg,
+                _ => options.arguments.push(arg),
+            }
+        }
+
+        let arguments = options.arguments.len();
+        match options.command.as_str() {
+            "" => Err("no command given".to_string()),
+            "parse" | "path" if arguments == 0 => Err(format!("{} needs at least one argument", options.command)),
+            "scan" | "explain" if arguments != 1 => Err(format!("{} takes one argument", options.command)),
+            "batch" if arguments > 1 => Err("batch takes at most one file".to_string()),
+            "serve" if arguments > 0 => Err("serve takes no arguments".to_string()),
+            "serve" if cfg!(not(feature = "server")) => Err("serve needs scene-release built with the server feature".to_string()),
+            "parse" | "path" | "scan" | "explain" | "batch" | "serve" => Ok(Some(options)),
+            command => Err(format!("unknown command {:?}", command)),
+        }
+    }
+
+    fn parser(&self) -> ReleaseParser {
+        let parser = match self.release_type.as_str() {
+            "auto" => ReleaseParser::auto(),
+            release_type => ReleaseParser::new(release_type),
+        };
+        parser.strict(self.strict)
+    }
+}
+
+fn run(options: &Options, out: &mut impl Write) -> Outcome {
+    let parser = options.parser();
+    match options.command.as_str() {
+        "parse" => parse(options, &parser, out),
+        "batch" => batch(options, &parser, out),
+        "path" => path(options, &parser, out),
+        "scan" => scan(options, &parser, out),
+        "serve" => serve(options, out),
+        _ => explain(options, &parser, out),
+    }
+}
+
+fn parse(options: &Options, parser: &ReleaseParser, out: &mut impl Write) -> Outcome {
+    let mut parsed_all = true;
+    for (index, name) in options.arguments.iter().enumerate() {
+        match parser.try_parse(name) {
+            Ok(parsed) if options.json => print(out, &json(&parsed, true))?,
+            // A blank line between names
+            Ok(parsed) => print(out, &format!("{}{}", if index > 0 { "\n" } else { "" }, describe(&parsed, "")))?,
+            Err(error) => parsed_all &= report(name, &error),
+        }
+    }
+    Ok(parsed_all)
+}
+
+fn batch(options: &Options, parser: &ReleaseParser, out: &mut impl Write) -> Outcome {
+    let input: Box<dyn BufRead> = match options.arguments.first().map(String::as_str) {
+        None | Some("-") => Box::new(io::stdin().lock()),
+        Some(file) => Box::new(BufReader::new(File::open(file).map_err(|error| Failure::Io(format!("{}: {}", file, error)))?)),
+    };
+    if options.format == BatchFormat::Csv {
+        print(out, &csv_row(csv_header()))?;
+    }
+
+    let mut parsed_all = true;
+    for line in input.lines() {
+        let line = line.map_err(|error| Failure::Io(error.to_string()))?;
+        let name = line.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let result = parser.try_parse(name);
+        parsed_all &= result.is_ok();
+        let row = match (options.format, result) {
+            (BatchFormat::Ndjson, Ok(parsed)) => json(&parsed, false),
+            (BatchFormat::Ndjson, Err(error)) => serde_json::json!({ "release": name, "error": error.to_string() }).to_string(),
+            (BatchFormat::Csv, Ok(parsed)) => csv_row(csv_values(&parsed, "")),
+            (BatchFormat::Csv, Err(error)) => {
+                let parsed = ParsedRelease {
+                    release: name.to_string(),
+                    ..Default::default()
+                };
+                csv_row(csv_values(&parsed, &error.to_string()))
+            }
+        };
+        print(out, &row)?;
+    }
+    Ok(parsed_all)
+}
+
+fn path(options: &Options, parser: &ReleaseParser, out: &mut impl Write) -> Outcome {
+    let mut parsed_all = true;
+    for path in &options.arguments {
+        match parser.try_parse_path(path) {
+            Ok(path_info) if options.json => print(out, &json(&path_info, true))?,
+            Ok(path_info) => print(out, &describe_path(&path_info))?,
+            Err(error) => parsed_all &= report(path, &error),
+        }
+    }
+    Ok(parsed_all)
+}
+
+fn scan(options: &Options, parser: &ReleaseParser, out: &mut impl Write) -> Outcome {
+    let root = &options.arguments[0];
+    let inventory = parser.scan(root).map_err(|error| Failure::Io(format!("{}: {}", root, error)))?;
+    if options.json {
+        print(out, &json(&inventory, true))?;
+        return Ok(inventory.unmatched.is_empty());
+    }
+
+    let mut text = String::new();
+    for series in &inventory.series {
+        let _ = writeln!(text, "{}{}", series.info.title, year(series.info.year));
+        for season in &series.seasons {
+            let _ = writeln!(text, "  Season {}: {} files", season.season, season.episodes.len());
+        }
+        if !series.other.is_empty() {
+            let _ = writeln!(text, "  Other: {} files", series.other.len());
+        }
+    }
+    for movie in &inventory.movies {
+        let _ = writeln!(text, "{}{}: {} files", movie.info.title, year(movie.info.year), movie.versions.len());
+    }
+    for skipped in &inventory.unmatched {
+        let _ = writeln!(text, "unmatched: {} ({})", skipped.path.display(), skipped.reason);
+    }
+    print(out, text.trim_end())?;
+    Ok(inventory.unmatched.is_empty())
+}
+
+fn explain(options: &Options, parser: &ReleaseParser, out: &mut impl Write) -> Outcome {
+    let name = &options.arguments[0];
+    if let Err(error) = parser.try_parse(name) {
+        return Ok(report(name, &error));
+    }
+    let explanation = parser.explain(name);
+    if options.json {
+        print(out, &json(&explanation, true))?;
+    } else if options.plain || !io::stdout().is_terminal() {
+        print(out, &explanation.render_plain())?;
+    } else {
+        print(out, &explanation.render())?;
+    }
+    Ok(true)
+}
+
+#[cfg(feature = "server")]
+fn serve(options: &Options, out: &mut impl Write) -> Outcome {
+    let mut config = scene_release::ServerConfig::default();
+    if let Some(address) = &options.address {
+        config.address = address.clone();
+    }
+    print(out, &format!("listening on http://{}", config.address))?;
+    out.flush().map_err(|error| Failure::Io(error.to_string()))?;
+    scene_release::ParseService::new(config).serve().map_err(|error| Failure::Io(error.to_string()))?;
+    Ok(true)
+}
+
+#[cfg(not(feature = "server"))]
+fn serve(_: &Options, _: &mut impl Write) -> Outcome {
+    unreachable!("serve is rejected with the options")
+}
+
+/// Print why `input` failed and return `false`, for the exit code.
+fn report(input: &str, error: &ParseError) -> bool {
+    eprintln!("{}: {}", input, error);
+    false
+}
+
+fn print(out: &mut impl Write, text: &str) -> Result<(), Failure> {
+    writeln!(out, "{}", text).map_err(|error| Failure::Io(error.to_string()))
+}
+
+fn json(value: &impl serde::Serialize, pretty: bool) -> String {
+    let json = if pretty { serde_json::to_string_pretty(value) } else { serde_json::to_string(value) };
+    json.expect("parse results serialize to JSON")
+}
+
+fn year(year: Option<u16>) -> String {
+    year.map(|year| format!(" ({})", year)).unwrap_or_default()
+}
+
+/// The fields that were found, one per line, aligned.
+fn describe(parsed: &ParsedRelease, indent: &str) -> String {
+    let mut lines: Vec<(&str, String)> = ParsedRelease::FIELDS
+        .iter()
+        .filter(|field| **field != "flags")
+        .filter_map(|field| Some((*field, parsed.get(field).filter(|value| !value.is_empty())?)))
+        .collect();
+    if !parsed.flags.is_empty() {
+        lines.push(("flags", parsed.flags.join(", ")));
+    }
+    if !parsed.language.is_empty() {
+        let mut languages: Vec<String> = parsed.language.iter().map(|(code, name)| format!("{} ({})", name, code)).collect();
+        languages.sort();
+        lines.push(("language", languages.join(", ")));
+    }
+    let width = lines.iter().map(|(field, _)| field.len()).max().unwrap_or(0);
+    lines.iter().map(|(field, value)| format!("{}{:width$} {}", indent, format!("{}:", field), value, width = width + 1)).collect::<Vec<_>>().join("\n")
+}
+
+fn describe_path(path_info: &PathInfo) -> String {
+    let mut text = String::new();
+    if let Some(directory) = &path_info.directory {
+        let _ = writeln!(text, "directory:\n{}", describe(directory, "  "));
+    }
+    if let Some(season) = path_info.season {
+        let _ = writeln!(text, "season folder: {}", season);
+    }
+    let _ = write!(text, "file:\n{}", describe(&path_info.file, "  "));
+    text
+}
+
+fn csv_header() -> Vec<String> {
+    ParsedRelease::FIELDS.iter().chain(&["language", "error"]).map(|field| field.to_string()).collect()
+}
+
+fn csv_values(parsed: &ParsedRelease, error: &str) -> Vec<String> {
+    let mut languages: Vec<&str> = parsed.language.keys().map(String::as_str).collect();
+    languages.sort();
+    let mut values: Vec<String> = ParsedRelease::FIELDS.iter().map(|field| parsed.get(field).unwrap_or_default()).collect();
+    values.extend([languages.join(","), error.to_string()]);
+    values
+}
+
+/// Quote values holding a comma, quote or line break, as RFC 4180 does.
+fn csv_row(values: Vec<String>) -> String {
+    values
+        .into_iter()
+        .map(|value| if value.contains([',', '"', '\n', '\r']) { format!("\"{}\"", value.replace('"', "\"\"")) } else { value })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(args: &[&str]) -> Result<Option<Options>, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn output(args: &[&str]) -> (Outcome, String) {
+        let mut out = Vec::new();
+        let outcome = run(&options(args).unwrap().unwrap(), &mut out);
+        (outcome, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_options() {
+        let parsed = options(&["--type=tv", "parse", "--json", "Show.S01E01-GRP"]).unwrap().unwrap();
+        assert_eq!(parsed.release_type, "tv");
+        assert!(parsed.json);
+        assert_eq!(parsed.command, "parse");
+        assert_eq!(parsed.arguments, vec!["Show.S01E01-GRP"]);
+        assert_eq!(options(&["batch", "-f", "csv", "-"]).unwrap().unwrap().format, BatchFormat::Csv);
+
+        assert_eq!(options(&["-t", "tvshow", "parse", "x"]), Err("unknown release type \"tvshow\"".to_string()));
+        assert_eq!(options(&["parse"]), Err("parse needs at least one argument".to_string()));
+        assert_eq!(options(&["scan", "a", "b"]), Err("scan takes one argument".to_string()));
+        assert_eq!(options(&["frobnicate"]), Err("unknown command \"frobnicate\"".to_string()));
+        assert_eq!(options(&["parse", "--type"]), Err("--type needs a value".to_string()));
+    }
+
+    #[test]
+    fn test_parse() {
+        let (outcome, text) = output(&["parse", "Show.S01E02.720p.HDTV.x264-GRP"]);
+        assert_eq!(outcome, Ok(true));
+        let line = |field: &str| text.lines().find(|line| line.starts_with(field)).map(|line| line.split_whitespace().collect::<Vec<_>>());
+        assert_eq!(line("title:"), Some(vec!["title:", "Show"]));
+        assert_eq!(line("type:"), Some(vec!["type:", "tv"]));
+
+        let (outcome, text) = output(&["--type", "movie", "parse", "--json", "Movie.2020.1080p.BluRay.x264-GRP"]);
+        assert_eq!(outcome, Ok(true));
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["year"], 2020);
+
+        // Strict mode fails a name without a group
+        let (outcome, text) = output(&["--strict", "parse", "Movie.2020.1080p", "Movie.2020.1080p.BluRay.x264-GRP"]);
+        assert_eq!(outcome, Ok(false));
+        assert!(text.contains("group:"));
+    }
+
+    #[test]
+    fn test_batch_file() {
+        let file = std::env::temp_dir().join(format!("scene_release_cli_batch_{}.txt", std::process::id()));
+        std::fs::write(&file, "Show.S01E01.720p.HDTV.x264-GRP\n\nMovie, The.2020.1080p.BluRay.x264-GRP\n").unwrap();
+        let file_arg = file.to_str().unwrap();
+
+        let (outcome, text) = output(&["batch", file_arg]);
+        assert_eq!(outcome, Ok(true));
+        let lines: Vec<serde_json::Value> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["season"], 1);
+
+        let (_, text) = output(&["batch", "--format", "csv", file_arg]);
+        let rows: Vec<&str> = text.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].starts_with("release,title,"));
+        assert!(rows[2].starts_with("\"Movie, The.2020.1080p.BluRay.x264-GRP\","), "{}", rows[2]);
+        std::fs::remove_file(&file).unwrap();
+
+        let (outcome, _) = output(&["batch", "/nonexistent/scene_release_cli.txt"]);
+        assert!(matches!(outcome, Err(Failure::Io(_))));
+    }
+
+    #[test]
+    fn test_path() {
+        let (outcome, text) = output(&["path", "Show (2020)/Season 01/Show.S01E02.720p.HDTV.x264-GRP.mkv"]);
+        assert_eq!(outcome, Ok(true));
+        assert!(text.starts_with("directory:\n  "), "{}", text);
+        assert!(text.contains("season folder: 1\n"), "{}", text);
+
+        let (outcome, text) = output(&["path", "Season 01/Show.S01E02.mkv"]);
+        assert_eq!(outcome, Ok(false));
+        assert!(text.is_empty());
+    }
+
+    #[test]
+    fn test_scan() {
+        let root = std::env::temp_dir().join(format!("scene_release_cli_scan_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("Show.S01E01.720p.HDTV.x264-GRP.mkv"), "").unwrap();
+        let root_arg = root.to_str().unwrap();
+
+        let (outcome, text) = output(&["scan", root_arg]);
+        assert_eq!(outcome, Ok(true));
+        assert!(text.contains("Season 1: 1 files"), "{}", text);
+
+        // An unmatched file fails the run, in both outputs
+        std::fs::write(root.join("notes.txt"), "").unwrap();
+        let (outcome, text) = output(&["scan", root_arg]);
+        assert_eq!(outcome, Ok(false));
+        assert!(text.contains("unmatched: "), "{}", text);
+        assert_eq!(output(&["scan", "--json", root_arg]).0, Ok(false));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
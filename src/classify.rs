@@ -0,0 +1,172 @@
+//! Release type detection for [`ReleaseParser::auto`](crate::ReleaseParser::auto).
+//!
+//! Every token of the release name is checked against a small table of
+//! evidence rules. Each rule votes for one release type with a weight; the
+//! type with the highest total wins. Video markers (resolution, codec, video
+//! source) vote for TV when there is any TV evidence and for movies otherwise.
+
+use crate::date::find_token_dates;
+use crate::rules::regex;
+use crate::tokenizer::{tokenize, Separator};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Release types [`classify`] can detect.
+pub const RELEASE_TYPES: &[&str] = &["tv", "movie", "music", "game", "software"];
+
+/// Pseudo type of the evidence shared by TV and movies.
+const VIDEO: &str = "video";
+
+/// The detected release type of a release name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Classification {
+    #[serde(rename = "type")]
+    pub release_type: String,
+    /// From 0.0 (no evidence, fell back to "movie") to 1.0 (strong and undisputed)
+    pub confidence: f32,
+}
+
+/// Total weight at which a type counts as certain, before competing types are considered.
+const CERTAIN_SCORE: f32 = 4.0;
+
+/// Evidence rules as `(release type, weight, pattern)`.
+///
+/// Patterns are anchored and matched against each token, its dash-separated
+/// parts (`WEB-FLAC`) and the token joined with the next one by a space
+/// ("Season 1", "Episode 12").
+fn evidence_rules() -> [(&'static str, f32, &'static Regex); 22] {
+    [
+        // TV
+        ("tv", 3.0, regex!(r"(?i)^S\d{1,2}E\d{1,4}(?:-?E?\d{1,4})*$")),
+        ("tv", 2.5, regex!(r"(?i)^\d{1,2}x\d{2,3}$")),
+        ("tv", 2.0, regex!(r"(?i)^(?:S\d{1,2}|E\d{2,4})$")),
+        ("tv", 2.0, regex!(r"(?i)^(?:Season|Staffel|Saison|Episode) \d{1,4}$")),
+        ("tv", 2.0, regex!(r"(?i)^(?:Seasons? \d{1,2}-\d{1,2}|Complete Series)$")),
+        ("tv", 2.0, regex!(r"(?i)^(?:OVA|OAD|ONA|NCOP|NCED)\d{0,3}$")),
+        ("tv", 2.0, regex!(r"(?i)^tvdb(?:id)?-\d+$")),
+        ("tv", 1.0, regex!(r"(?i)^(?:HDTV|PDTV|SDTV|DSR)$")),
+        // Movies, and video in general
+        ("movie", 1.0, regex!(r"^(?:19|20)\d{2}$")),
+        ("movie", 2.0, regex!(r"(?i)^(?:tmdb(?:id)?-\d+|edition-.+)$")),
+        (VIDEO, 1.0, regex!(r"(?i)^(?:BluRay|BDRip|BRRip|DVDRip|Remux|WEB-?DL|WEBRip|HDRip|CAM|HDCAM|TS|TELESYNC|DVDScr)$")),
+        (VIDEO, 1.0, regex!(r"(?i)^(?:480|576|720|1080|2160)[pi]$")),
+        (VIDEO, 1.0, regex!(r"(?i)^(?:x26[45]|h\.?26[45]|HEVC|AVC|XviD|DivX)$")),
+        // Music
+        ("music", 2.0, regex!(r"(?i)^(?:FLAC|MP3|ALAC|V0|V2|CBR|VBR|\d{3}kbps|(?:16|24)BIT|\d{2}(?:\.\d)?kHz)$")),
+        ("music", 2.0, regex!(r"(?i)^(?:\d?CD|CDM|CDS|CDR|EP|LP|Vinyl|OST|Discography|Album|Single|Bootleg|VA)$")),
+        // Games
+        ("game", 3.0, regex!(r"(?i)^(?:XBOX|XBOX360|XBOXONE|X360|PS[2-5]|PSP|PSV|NSW|Switch|Wii|WiiU|NDS|3DS|GBA)$")),
+        ("game", 3.0, regex!(r"(?i)^(?:CODEX|PLAZA|SKIDROW|RELOADED|CPY|EMPRESS|TENOKE|DARKSiDERS|RUNE|FLT|HOODLUM|PROPHET|RAZOR1911|DOGE|TiNYiSO|GOG)$")),
+        ("game", 1.5, regex!(r"(?i)^(?:DLC|Update|Trainer|Crackfix|MULTi\d+)$")),
+        // Software
+        ("software", 2.0, regex!(r"(?i)^(?:Win(?:dows)?|WinXP|Win7|Win8|Win10|Win11|MacOS|MacOSX|OSX|Linux|Android|iOS)$")),
+        ("software", 2.0, regex!(r"(?i)^(?:x64|x86|x32|64bit|32bit|Multilingual|Incl|Keygen|Keymaker|Patch|Cracked|Portable|Regged|Activator)$")),
+        ("software", 2.0, regex!(r"(?i)^v\d+(?:\.\d+)+$")),
+        ("software", 1.0, regex!(r"(?i)^v\d+$")),
+    ]
+}
+
+/// Detect the release type of a release name from its tokens.
+///
+/// Returns `"movie"` with a confidence of 0.0 when no token gives any hint.
+pub fn classify(release_name: &str) -> Classification {
+    let tokens = tokenize(release_name);
+    let rules = evidence_rules();
+    let mut scores = [0.0f32; RELEASE_TYPES.len()];
+    let mut video = 0.0f32;
+    let mut vote = |release_type: &str, weight: f32| match RELEASE_TYPES.iter().position(|known| *known == release_type) {
+        Some(index) => scores[index] += weight,
+        None => video += weight,
+    };
+
+    for (i, token) in tokens.iter().enumerate() {
+        let pair = tokens.get(i + 1).map(|next| format!("{} {}", token.text, next.text));
+        let parts: Vec<&str> = if token.text.contains('-') { token.text.split('-').collect() } else { Vec::new() };
+        for (release_type, weight, re) in &rules {
+            let matched = re.is_match(&token.text)
+                || parts.iter().any(|part| re.is_match(part))
+                || pair.as_deref().is_some_and(|pair| re.is_match(pair));
+            if matched {
+                vote(release_type, *weight);
+            }
+        }
+        // Anime numbering: "Title - 01", "[119]"
+        let number = regex!(r"^\d{1,4}$").is_match(&token.text) && !regex!(r"^(?:19|20)\d{2}$").is_match(&token.text);
+        if number && token.separator == Separator::Dash {
+            vote("tv", 2.0);
+        } else if number && token.separator == Separator::Bracket && token.text.len() <= 3 {
+            vote("tv", 1.5);
+        }
+    }
+    // "Part.3.of.6" is a miniseries; a part alone may be a movie sequel
+    if regex!(r"(?i)\b(?:Part|Pt|Teil|Partie)[. _]?(?:\d{1,2}|[IVXL]{1,6})[. _](?:of|von|sur)[. _](?:\d{1,2}|[IVXL]{1,6})\b").is_match(release_name) {
+        vote("tv", 2.5);
+    }
+    // Air dates of daily shows span several tokens; the less certain the layout, the less they count
+    for (_, _, _, confidence) in find_token_dates(release_name, &tokens) {
+        vote("tv", 3.0 * confidence);
+    }
+
+    // RELEASE_TYPES starts with "tv" and "movie"
+    let video_type = if scores[0] > 0.0 { 0 } else { 1 };
+    scores[video_type] += video;
+
+    let total: f32 = scores.iter().sum();
+    let (best, score) = scores
+        .iter()
+        .enumerate()
+        .fold((1, 0.0f32), |best, (index, &score)| if score > best.1 { (index, score) } else { best });
+    if score == 0.0 {
+        return Classification {
+            release_type: "movie".to_string(),
+            confidence: 0.0,
+        };
+    }
+    Classification {
+        release_type: RELEASE_TYPES[best].to_string(),
+        confidence: (score / total) * (score.min(CERTAIN_SCORE) / CERTAIN_SCORE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_tv() {
+        let classification = classify("Breaking.Bad.S05E14.Ozymandias.1080p.WEB-DL.DD5.1.H.264-GROUP");
+        assert_eq!(classification.release_type, "tv");
+        assert!(classification.confidence > 0.5);
+
+        assert_eq!(classify("[SubsPlease] Frieren - 12 (1080p) [ABCD1234]").release_type, "tv");
+        assert_eq!(classify("The Show Season 2 Episode 5 720p").release_type, "tv");
+        assert_eq!(classify("Planet.Earth.Part.3.of.6.720p.BluRay.x264-GRP").release_type, "tv");
+        assert_eq!(classify("[Group] Frieren - OVA 02 [1080p]").release_type, "tv");
+        assert_eq!(classify("Show.Complete.Series.1080p.BluRay.x264-GRP").release_type, "tv");
+        assert_eq!(classify("The.Daily.Show.2023.10.30.Guest.Name.720p.WEB.h264-GRP").release_type, "tv");
+    }
+
+    #[test]
+    fn test_classify_movie() {
+        let classification = classify("The.Matrix.1999.1080p.BluRay.x264-GROUP");
+        assert_eq!(classification.release_type, "movie");
+        assert!(classification.confidence > 0.9);
+        // The number of an ID is no air date
+        assert_eq!(classify("Movie (2019) {tmdb-191231} [Bluray-1080p]-GRP").release_type, "movie");
+    }
+
+    #[test]
+    fn test_classify_music_game_software() {
+        assert_eq!(classify("Artist-Album_Title-(CAT123)-WEB-FLAC-2023-GRP").release_type, "music");
+        assert_eq!(classify("Some.Game.Deluxe.Edition-CODEX").release_type, "game");
+        assert_eq!(classify("Some.Game.NSW-VENOM").release_type, "game");
+        assert_eq!(classify("Adobe.Photoshop.v24.1.0.x64.Multilingual-GRP").release_type, "software");
+    }
+
+    #[test]
+    fn test_classify_without_evidence() {
+        let classification = classify("Just.A.Title-GRP");
+        assert_eq!(classification.release_type, "movie");
+        assert_eq!(classification.confidence, 0.0);
+    }
+}
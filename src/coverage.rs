@@ -0,0 +1,283 @@
+//! Missing, duplicate and overlapping episodes of a series.
+//!
+//! [`coverage`] groups parsed TV files by series and, per season, lists the
+//! episodes that are there and the ones missing between episode 1 and the
+//! last one found (or the episode count, when [known](SeriesCoverage::with_episode_counts)),
+//! up to episode [`MAX_MISSING_EPISODE`].
+//! Episodes found in more than one file are reported as duplicates, and
+//! multi-episode files that share episodes with another file as overlaps.
+//! Specials (season 0, OVAs and other extras) are listed apart, as their
+//! numbering has gaps by nature.
+
+use crate::scan::Series;
+use crate::types::ParsedRelease;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Last episode listed as missing, so a mistaken episode number or count
+/// doesn't list millions of episodes.
+pub const MAX_MISSING_EPISODE: u128 = 10_000;
+
+/// An episode found in more than one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DuplicateEpisode {
+    pub episode: u128,
+    pub releases: Vec<String>,
+}
+
+/// Two files sharing episodes, at least one of them a multi-episode file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Overlap {
+    /// Episodes in both files
+    pub episodes: Vec<u128>,
+    pub releases: [String; 2],
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeasonCoverage {
+    pub season: u128,
+    /// Episode count of the season, when known
+    pub expected: Option<u128>,
+    /// Episodes found, in order
+    pub present: Vec<u128>,
+    /// Episodes not found, up to the expected count or the last one found
+    pub missing: Vec<u128>,
+    pub duplicates: Vec<DuplicateEpisode>,
+    pub overlaps: Vec<Overlap>,
+}
+
+/// A special file: an episode of season 0, an OVA or another extra.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Special {
+    pub episodes: Vec<u128>,
+    pub release: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeriesCoverage {
+    /// Title of the first file of the series
+    pub title: String,
+    /// Seasons in order, without specials
+    pub seasons: Vec<SeasonCoverage>,
+    pub specials: Vec<Special>,
+    /// Files with no season or episode number
+    pub unnumbered: Vec<String>,
+}
+
+impl SeasonCoverage {
+    /// Whether no episode is missing. Without an expected count this only
+    /// says there are no gaps.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    fn new(season: u128, files: &[(&ParsedRelease, Vec<u128>)]) -> Self {
+        let mut releases_by_episode: BTreeMap<u128, Vec<String>> = BTreeMap::new();
+        for (parsed, episodes) in files {
+            for episode in episodes {
+                releases_by_episode.entry(*episode).or_default().push(parsed.release.clone());
+            }
+        }
+
+        let mut overlaps: Vec<Overlap> = Vec::new();
+        for (i, (parsed, episodes)) in files.iter().enumerate() {
+            for (other, other_episodes) in &files[i + 1..] {
+                if episodes.len() < 2 && other_episodes.len() < 2 {
+                    continue;
+                }
+                let shared: Vec<u128> = episodes.iter().filter(|episode| other_episodes.contains(episode)).copied().collect();
+                if !shared.is_empty() {
+                    overlaps.push(Overlap {
+                        episodes: shared,
+                        releases: [parsed.release.clone(), other.release.clone()],
+                    });
+                }
+            }
+        }
+
+        let mut coverage = Self {
+            season,
+            expected: None,
+            present: releases_by_episode.keys().copied().collect(),
+            missing: Vec::new(),
+            duplicates: releases_by_episode
+                .iter()
+                .filter(|(_, releases)| releases.len() > 1)
+                .map(|(episode, releases)| DuplicateEpisode {
+                    episode: *episode,
+                    releases: releases.clone(),
+                })
+                .collect(),
+            overlaps,
+        };
+        coverage.find_missing();
+        coverage
+    }
+
+    fn find_missing(&mut self) {
+        let last = self.expected.or_else(|| self.present.last().copied()).unwrap_or(0).min(MAX_MISSING_EPISODE);
+        // The gaps between the episodes found, which are in order
+        let mut missing = Vec::new();
+        let mut next = 1;
+        for &episode in self.present.iter().take_while(|episode| **episode <= last) {
+            missing.extend(next..episode);
+            next = episode + 1;
+        }
+        missing.extend(next..=last);
+        self.missing = missing;
+    }
+}
+
+impl SeriesCoverage {
+    /// Check the seasons against known episode counts, by season number.
+    /// Seasons with no file at all are added with every episode missing.
+    pub fn with_episode_counts(mut self, counts: &HashMap<u128, u128>) -> Self {
+        for &season in counts.keys().filter(|season| **season > 0) {
+            if !self.seasons.iter().any(|coverage| coverage.season == season) {
+                self.seasons.push(SeasonCoverage::new(season, &[]));
+            }
+        }
+        for coverage in &mut self.seasons {
+            coverage.expected = counts.get(&coverage.season).copied();
+            coverage.find_missing();
+        }
+        self.seasons.sort_by_key(|coverage| coverage.season);
+        self
+    }
+
+    /// Seasons with missing episodes.
+    pub fn incomplete_seasons(&self) -> impl Iterator<Item = &SeasonCoverage> {
+        self.seasons.iter().filter(|coverage| !coverage.is_complete())
+    }
+
+    fn new<'a>(releases: &[&'a ParsedRelease]) -> Self {
+        let mut seasons: BTreeMap<u128, Vec<(&'a ParsedRelease, Vec<u128>)>> = BTreeMap::new();
+        let mut specials: Vec<Special> = Vec::new();
+        let mut unnumbered: Vec<String> = Vec::new();
+        for parsed in releases {
+            let episodes: Vec<u128> = if parsed.episodes.is_empty() { parsed.episode.into_iter().collect() } else { parsed.episodes.clone() };
+            match parsed.season {
+                _ if parsed.season == Some(0) || parsed.special_kind.is_some() => specials.push(Special {
+                    episodes,
+                    release: parsed.release.clone(),
+                }),
+                Some(season) if !episodes.is_empty() => seasons.entry(season).or_default().push((parsed, episodes)),
+                _ => unnumbered.push(parsed.release.clone()),
+            }
+        }
+
+        Self {
+            title: releases.first().map(|parsed| parsed.title.clone()).unwrap_or_default(),
+            seasons: seasons.iter().map(|(season, files)| SeasonCoverage::new(*season, files)).collect(),
+            specials,
+            unnumbered,
+        }
+    }
+}
+
+impl Series {
+    /// Coverage of the episodes of this series. Files in a season folder
+    /// count for that season when their name has no season.
+    pub fn coverage(&self) -> SeriesCoverage {
+        let files: Vec<ParsedRelease> = self
+            .seasons
+            .iter()
+            .flat_map(|season| {
+                season.episodes.iter().map(|file| ParsedRelease {
+                    season: file.parsed.season.or(Some(season.season)),
+                    ..file.parsed.clone()
+                })
+            })
+            .chain(self.other.iter().map(|file| file.parsed.clone()))
+            .collect();
+        let mut coverage = SeriesCoverage::new(&files.iter().collect::<Vec<_>>());
+        coverage.title = self.info.title.clone();
+        coverage
+    }
+}
+
+/// Coverage of parsed TV files, grouped into series by title (ignoring
+/// case and punctuation) and year.
+pub fn coverage<'a>(releases: impl IntoIterator<Item = &'a ParsedRelease>) -> Vec<SeriesCoverage> {
+    let mut series: BTreeMap<(String, Option<u16>), Vec<&ParsedRelease>> = BTreeMap::new();
+    for parsed in releases {
+        let title: String = parsed.title.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect();
+        series.entry((title, parsed.year)).or_default().push(parsed);
+    }
+    series.values().map(|releases| SeriesCoverage::new(releases)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReleaseParser;
+
+    fn parse_all(releases: &[&str]) -> Vec<ParsedRelease> {
+        let parser = ReleaseParser::new("tv");
+        releases.iter().map(|release| parser.parse(release)).collect()
+    }
+
+    #[test]
+    fn test_missing_and_duplicates() {
+        let releases = parse_all(&[
+            "Show.S01E01.720p.HDTV.x264-AAA",
+            "Show.S01E01.1080p.WEB-DL.x264-BBB",
+            "Show.S01E02-E03.720p.HDTV.x264-AAA",
+            "Show.S01E03.720p.HDTV.x264-CCC",
+            "Show.S01E06.720p.HDTV.x264-AAA",
+            "Show.S02E02.720p.HDTV.x264-AAA",
+            "Other.Show.S01E01.720p.HDTV.x264-AAA",
+        ]);
+        let report = coverage(&releases);
+        assert_eq!(report.len(), 2);
+        let show = report.iter().find(|series| series.title == "Show").unwrap();
+
+        let season = &show.seasons[0];
+        assert_eq!(season.present, vec![1, 2, 3, 6]);
+        assert_eq!(season.missing, vec![4, 5]);
+        let duplicated: Vec<u128> = season.duplicates.iter().map(|duplicate| duplicate.episode).collect();
+        assert_eq!(duplicated, vec![1, 3]);
+        assert_eq!(season.overlaps.len(), 1);
+        assert_eq!(season.overlaps[0].episodes, vec![3]);
+        assert_eq!(show.seasons[1].missing, vec![1]);
+        assert_eq!(show.incomplete_seasons().count(), 2);
+    }
+
+    #[test]
+    fn test_episode_counts_and_specials() {
+        let releases = parse_all(&["Show.S01E01.720p.HDTV.x264-AAA", "Show.S01E02.720p.HDTV.x264-AAA", "Show.S00E05.720p.HDTV.x264-AAA", "Show.S01E03.OVA.720p.HDTV.x264-AAA"]);
+        let counts = HashMap::from([(1, 3), (2, 2)]);
+        let show = coverage(&releases).remove(0).with_episode_counts(&counts);
+
+        assert_eq!(show.seasons[0].expected, Some(3));
+        assert_eq!(show.seasons[0].missing, vec![3]);
+        assert_eq!(show.seasons[1].missing, vec![1, 2]);
+        assert_eq!(show.specials.len(), 2);
+        assert_eq!(show.specials[0].episodes, vec![5]);
+
+        let json = serde_json::to_value(&show).unwrap();
+        assert_eq!(json["seasons"][0]["missing"], serde_json::json!([3]));
+
+        // "Pilot" in the title of a regular episode makes no special
+        let releases = parse_all(&["Show.S03E01.720p.HDTV.x264-AAA", "Show.S03E02.720p.HDTV.x264-AAA", "Show.S03E03.720p.HDTV.x264-AAA", "Show.S03E04.Return.of.the.Pilot.720p.HDTV.x264-AAA"]);
+        let show = coverage(&releases).remove(0);
+        assert!(show.specials.is_empty());
+        assert_eq!(show.seasons[0].missing, Vec::<u128>::new());
+    }
+
+    #[test]
+    fn test_huge_episode_numbers() {
+        let mut releases = parse_all(&["Show.S01E01.720p.HDTV.x264-AAA", "Show.S01E03.720p.HDTV.x264-AAA"]);
+        releases[1].episode = Some(u128::MAX);
+        releases[1].episodes = vec![u128::MAX];
+        let show = coverage(&releases).remove(0);
+        assert_eq!(show.seasons[0].present, vec![1, u128::MAX]);
+        assert_eq!(show.seasons[0].missing.len(), MAX_MISSING_EPISODE as usize - 1);
+        assert_eq!(show.seasons[0].missing.last(), Some(&MAX_MISSING_EPISODE));
+
+        let counts = HashMap::from([(1, u128::MAX), (2, 2)]);
+        let show = coverage(&releases).remove(0).with_episode_counts(&counts);
+        assert_eq!(show.seasons[0].missing.len(), MAX_MISSING_EPISODE as usize - 1);
+        assert_eq!(show.seasons[1].missing, vec![1, 2]);
+    }
+}
@@ -0,0 +1,216 @@
+//! Air dates of daily episodes.
+//!
+//! Daily shows are named by air date instead of season and episode, in many
+//! layouts: `2023.10.30`, `30.10.2023`, `20231030`, `231030` (common for
+//! Korean shows) or `Oct.30.2023`. [`find_dates`] finds them all; only
+//! real calendar dates are kept, so `2023.02.30` is not a date.
+//! [`find_token_dates`] keeps the ones made of whole tokens.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::rules::regex;
+use crate::tokenizer::Token;
+
+/// A calendar date, shown and serialized as `YYYY-MM-DD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AirDate {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+/// Text that is not a `YYYY-MM-DD` calendar date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidDate(pub String);
+
+/// A date found in a release name: the date, its byte span, and the rule
+/// name and confidence of the layout it was written in.
+pub(crate) type FoundDate = (AirDate, Range<usize>, &'static str, f32);
+
+const MONTHS: &[&str] = &["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+
+impl AirDate {
+    /// A date, `None` unless it's on the calendar between 1900 and 2100.
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        let days = match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return None,
+        };
+        ((1900..=2100).contains(&year) && (1..=days).contains(&day)).then_some(Self { year, month, day })
+    }
+}
+
+impl fmt::Display for AirDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for AirDate {
+    type Err = InvalidDate;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidDate(value.to_string());
+        let mut parts = value.trim().splitn(3, '-');
+        let mut next = || parts.next().and_then(|part| part.parse().ok());
+        let (year, month, day) = (next().ok_or_else(invalid)?, next().ok_or_else(invalid)?, next().ok_or_else(invalid)?);
+        Self::new(year, month as u8, day as u8).filter(|_| month <= 12 && day <= 31).ok_or_else(invalid)
+    }
+}
+
+impl Serialize for AirDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AirDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl fmt::Display for InvalidDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} is not a YYYY-MM-DD date", self.0)
+    }
+}
+
+impl Error for InvalidDate {}
+
+/// Every date in a release name, most reliable layout first.
+///
+/// `30.10.2023` is read day first; `10.30.2023` can only be month first, so
+/// it is read that way.
+pub(crate) fn find_dates(release_name: &str) -> Vec<FoundDate> {
+    let mut dates = Vec::new();
+    let mut push = |date: Option<AirDate>, span: Range<usize>, rule: &'static str, confidence: f32| {
+        if let Some(date) = date {
+            if !dates.iter().any(|(_, found, _, _): &FoundDate| found.start < span.end && span.start < found.end) {
+                dates.push((date, span, rule, confidence));
+            }
+        }
+    };
+    let number = |text: &str| text.parse::<u16>().unwrap_or(0);
+
+    // Sonarr and Radarr style: "Show - 2013-10-30 - Title"
+    for cap in regex!(r"-\s*(\d{4}-\d{2}-\d{2})\s*-").captures_iter(release_name) {
+        let date = cap.get(1).unwrap();
+        push(date.as_str().parse().ok(), date.range(), "date.dashed", 0.95);
+    }
+    for cap in regex!(r"\b((?:19|20)\d{2})[. _-](\d{2})[. _-](\d{2})\b").captures_iter(release_name) {
+        push(AirDate::new(number(&cap[1]), number(&cap[2]) as u8, number(&cap[3]) as u8), cap.get(0).unwrap().range(), "date.year_first", 0.9);
+    }
+    for cap in regex!(r"\b(\d{2})[. _-](\d{2})[. _-]((?:19|20)\d{2})\b").captures_iter(release_name) {
+        let (first, second, year) = (number(&cap[1]) as u8, number(&cap[2]) as u8, number(&cap[3]));
+        let date = AirDate::new(year, second, first).or_else(|| AirDate::new(year, first, second));
+        push(date, cap.get(0).unwrap().range(), "date.year_last", 0.85);
+    }
+    let month_first = regex!(
+        r"(?i)\b(Jan(?:uary)?|Feb(?:ruary)?|Mar(?:ch)?|Apr(?:il)?|May|June?|July?|Aug(?:ust)?|Sep(?:t(?:ember)?)?|Oct(?:ober)?|Nov(?:ember)?|Dec(?:ember)?)[. _-](\d{1,2})(?:st|nd|rd|th)?,?[. _-]((?:19|20)\d{2})\b"
+    );
+    for cap in month_first.captures_iter(release_name) {
+        push(AirDate::new(number(&cap[3]), month(&cap[1]), number(&cap[2]) as u8), cap.get(0).unwrap().range(), "date.month_name", 0.9);
+    }
+    let day_first = regex!(
+        r"(?i)\b(\d{1,2})(?:st|nd|rd|th)?[. _-](Jan(?:uary)?|Feb(?:ruary)?|Mar(?:ch)?|Apr(?:il)?|May|June?|July?|Aug(?:ust)?|Sep(?:t(?:ember)?)?|Oct(?:ober)?|Nov(?:ember)?|Dec(?:ember)?)[. _-]((?:19|20)\d{2})\b"
+    );
+    for cap in day_first.captures_iter(release_name) {
+        push(AirDate::new(number(&cap[3]), month(&cap[2]), number(&cap[1]) as u8), cap.get(0).unwrap().range(), "date.month_name", 0.9);
+    }
+    for cap in regex!(r"\b((?:19|20)\d{2})(\d{2})(\d{2})\b").captures_iter(release_name) {
+        push(AirDate::new(number(&cap[1]), number(&cap[2]) as u8, number(&cap[3]) as u8), cap.get(0).unwrap().range(), "date.compact", 0.8);
+    }
+    // YYMMDD, this century
+    for cap in regex!(r"\b(\d{2})(\d{2})(\d{2})\b").captures_iter(release_name) {
+        push(AirDate::new(2000 + number(&cap[1]), number(&cap[2]) as u8, number(&cap[3]) as u8), cap.get(0).unwrap().range(), "date.compact_short", 0.6);
+    }
+    dates
+}
+
+/// Dates made of whole tokens, ahead of the release group. The numbers of
+/// `{tvdb-201231}` and of the `-GRP.101231` suffix are no air dates.
+pub(crate) fn find_token_dates(release_name: &str, tokens: &[Token]) -> Vec<FoundDate> {
+    let bytes = release_name.as_bytes();
+    // The dash of "x264-GRP"; the ones of "Show - Title" have spaces around them
+    let group_dash = (1..bytes.len()).rev().find(|&i| bytes[i] == b'-' && !bytes[i - 1].is_ascii_whitespace() && bytes.get(i + 1).is_some_and(|c| c.is_ascii_alphabetic()));
+    find_dates(release_name)
+        .into_iter()
+        .filter(|(_, span, _, _)| {
+            tokens.iter().any(|token| token.span.start == span.start)
+                && tokens.iter().any(|token| token.span.end == span.end)
+                && group_dash.is_none_or(|dash| span.start < dash)
+        })
+        .collect()
+}
+
+/// Number of a month name, at least its first three letters.
+fn month(name: &str) -> u8 {
+    let prefix = name.get(..3).unwrap_or(name).to_lowercase();
+    MONTHS.iter().position(|month| *month == prefix).map_or(0, |index| index as u8 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dates(release_name: &str) -> Vec<String> {
+        find_dates(release_name).iter().map(|(date, _, _, _)| date.to_string()).collect()
+    }
+
+    #[test]
+    fn test_calendar() {
+        assert!(AirDate::new(2024, 2, 29).is_some());
+        assert!(AirDate::new(2023, 2, 29).is_none());
+        assert!(AirDate::new(1900, 2, 29).is_none());
+        assert!(AirDate::new(2023, 4, 31).is_none());
+        assert!(AirDate::new(2023, 13, 1).is_none());
+        assert_eq!("2013-10-30".parse(), Ok(AirDate { year: 2013, month: 10, day: 30 }));
+        assert!("2013-10".parse::<AirDate>().is_err());
+        assert!("2013-10-300".parse::<AirDate>().is_err());
+        assert_eq!(serde_json::to_string(&AirDate::new(2013, 1, 2).unwrap()).unwrap(), "\"2013-01-02\"");
+    }
+
+    #[test]
+    fn test_layouts() {
+        assert_eq!(dates("Show - 2013-10-30 - Title"), vec!["2013-10-30"]);
+        assert_eq!(dates("The.Daily.Show.2023.10.30.Guest.Name.720p.WEB.h264-GRP"), vec!["2023-10-30"]);
+        assert_eq!(dates("Show 2023 10 30 720p"), vec!["2023-10-30"]);
+        assert_eq!(dates("Show.30.10.2023.720p"), vec!["2023-10-30"]);
+        assert_eq!(dates("Show.10.30.2023.720p"), vec!["2023-10-30"]);
+        assert_eq!(dates("Show.05.04.2023.720p"), vec!["2023-04-05"]);
+        assert_eq!(dates("Show.Oct.30.2023.720p"), vec!["2023-10-30"]);
+        assert_eq!(dates("Show.30th.October.2023.720p"), vec!["2023-10-30"]);
+        assert_eq!(dates("Show.20231030.720p"), vec!["2023-10-30"]);
+        assert_eq!(dates("Show.231030.E123.720p"), vec!["2023-10-30"]);
+    }
+
+    #[test]
+    fn test_token_dates() {
+        let dates = |release_name: &str| -> Vec<String> {
+            let tokens = crate::tokenizer::tokenize(release_name);
+            find_token_dates(release_name, &tokens).iter().map(|(date, _, _, _)| date.to_string()).collect()
+        };
+        assert_eq!(dates("Show - 2013-10-30 - Title [HDTV-720p]-GRP"), vec!["2013-10-30"]);
+        assert_eq!(dates("Show.231030.E123.720p-GRP"), vec!["2023-10-30"]);
+        assert!(dates("Show (2020) {tvdb-201231} - S01E01").is_empty());
+        assert!(dates("Show.S02E05.720p.HDTV.x264-GRP.101231").is_empty());
+    }
+
+    #[test]
+    fn test_not_dates() {
+        assert!(dates("Show.2023.02.30.720p").is_empty());
+        assert!(dates("Ranma.1.2.2024.S01E01").is_empty());
+        assert!(dates("Movie.2020.1080p.BluRay.x264").is_empty());
+        assert!(dates("Show.999999.720p").is_empty());
+    }
+}
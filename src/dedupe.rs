@@ -0,0 +1,444 @@
+//! Duplicate releases and which copy to keep.
+//!
+//! Releases of the same movie or episode share an identity key: the
+//! normalized title and year, or a database ID, followed by the episode
+//! and the edition, part or disc.
+//! [`QualityRanking::duplicates`] groups releases by that key and decides,
+//! within every group, which copy to keep and why the others can go.
+
+use crate::media::{AudioCodec, HdrFormat, Resolution, Source};
+use crate::quality::Revision;
+use crate::rules::EDITION_FLAGS;
+use crate::types::ParsedRelease;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Aspects of a release the ranking compares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Criterion {
+    Resolution,
+    Source,
+    Hdr,
+    Audio,
+    /// The [`Revision`]: PROPER, REPACK and REAL releases fix an earlier one
+    Revision,
+}
+
+/// Order of preference between releases of the same thing.
+///
+/// Releases are compared on each criterion in turn; the first one that
+/// differs decides. Values are ranked by their position in the lists, best
+/// first. Values missing from a list rank below all listed ones, and a
+/// release without a value ranks last.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QualityRanking {
+    pub criteria: Vec<Criterion>,
+    pub resolutions: Vec<Resolution>,
+    pub sources: Vec<Source>,
+    pub hdr: Vec<HdrFormat>,
+    pub audio: Vec<AudioCodec>,
+}
+
+impl Default for QualityRanking {
+    fn default() -> Self {
+        Self {
+            criteria: vec![Criterion::Resolution, Criterion::Source, Criterion::Revision, Criterion::Hdr, Criterion::Audio],
+            resolutions: vec![
+                Resolution::P4320,
+                Resolution::P2160,
+                Resolution::P1440,
+                Resolution::P1080,
+                Resolution::P720,
+                Resolution::P576,
+                Resolution::P540,
+                Resolution::P480,
+                Resolution::P360,
+            ],
+            sources: vec![
+                Source::Remux,
+                Source::BluRay,
+                Source::WebDl,
+                Source::WebRip,
+                Source::Web,
+                Source::BdRip,
+                Source::HdRip,
+                Source::Hdtv,
+                Source::Dvd,
+                Source::DvdRip,
+                Source::Vod,
+                Source::Pdtv,
+                Source::Dsr,
+                Source::TvRip,
+                Source::Ppv,
+                Source::Vhs,
+                Source::R5,
+                Source::Screener,
+                Source::Telecine,
+                Source::Telesync,
+                Source::Workprint,
+                Source::Cam,
+            ],
+            hdr: vec![
+                HdrFormat::DolbyVisionHdr10Plus,
+                HdrFormat::DolbyVisionHdr10,
+                HdrFormat::DolbyVision,
+                HdrFormat::Hdr10Plus,
+                HdrFormat::Hdr10,
+                HdrFormat::Hdr,
+                HdrFormat::Hlg,
+            ],
+            audio: vec![
+                AudioCodec::TrueHd,
+                AudioCodec::DtsX,
+                AudioCodec::DtsHdMa,
+                AudioCodec::Pcm,
+                AudioCodec::Flac,
+                AudioCodec::DtsHd,
+                AudioCodec::Eac3,
+                AudioCodec::Dts,
+                AudioCodec::Ac3,
+                AudioCodec::Opus,
+                AudioCodec::Aac,
+                AudioCodec::Mp3,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Keep,
+    Delete,
+}
+
+/// What to do with one copy of a duplicate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Decision {
+    /// Position of the release in the input
+    pub index: usize,
+    pub release: String,
+    pub verdict: Verdict,
+    pub justification: String,
+}
+
+/// Releases of the same movie or episode, best first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub key: String,
+    /// One decision per release; the first one is kept
+    pub decisions: Vec<Decision>,
+}
+
+impl QualityRanking {
+    /// Compare two releases, `Ordering::Greater` when `a` is better.
+    pub fn compare(&self, a: &ParsedRelease, b: &ParsedRelease) -> Ordering {
+        self.deciding(a, b).map_or(Ordering::Equal, |(_, ordering)| ordering)
+    }
+
+    /// Group releases of the same movie or episode and decide which copy of
+    /// each to keep. Releases without duplicates are left out.
+    pub fn duplicates(&self, releases: &[ParsedRelease]) -> Vec<DuplicateGroup> {
+        let mut groups: Vec<DuplicateGroup> = Vec::new();
+        for (key, mut members) in group_by_identity(releases) {
+            if members.len() < 2 {
+                continue;
+            }
+            // Stable, so equal copies keep their input order
+            members.sort_by(|&a, &b| self.compare(&releases[b], &releases[a]));
+            let best = &releases[members[0]];
+            let mut decisions = vec![Decision {
+                index: members[0],
+                release: best.release.clone(),
+                verdict: Verdict::Keep,
+                justification: format!("best of {} copies: {}", members.len(), self.describe(best)),
+            }];
+            for &index in &members[1..] {
+                let release = &releases[index];
+                let justification = match self.deciding(best, release) {
+                    Some((criterion, _)) => format!(
+                        "{} is worse than the kept copy on {}: {} < {}",
+                        release.release,
+                        criterion.name(),
+                        value_of(release, criterion),
+                        value_of(best, criterion)
+                    ),
+                    None => format!("{} is the same quality as the kept copy, which came first", release.release),
+                };
+                decisions.push(Decision {
+                    index,
+                    release: release.release.clone(),
+                    verdict: Verdict::Delete,
+                    justification,
+                });
+            }
+            groups.push(DuplicateGroup { key, decisions });
+        }
+        groups
+    }
+
+    /// First criterion on which the releases differ, with the comparison.
+    fn deciding(&self, a: &ParsedRelease, b: &ParsedRelease) -> Option<(Criterion, Ordering)> {
+        self.criteria.iter().find_map(|&criterion| {
+            let ordering = match criterion {
+                Criterion::Revision => a.revision().cmp(&b.revision()),
+                // Lower rank is better
+                _ => self.rank(b, criterion).cmp(&self.rank(a, criterion)),
+            };
+            (ordering != Ordering::Equal).then_some((criterion, ordering))
+        })
+    }
+
+    /// Position of the release's value in the list of `criterion`, the
+    /// length of the list for unlisted values and one more for none.
+    fn rank(&self, parsed: &ParsedRelease, criterion: Criterion) -> usize {
+        fn position<T: PartialEq>(list: &[T], value: Option<T>) -> usize {
+            match value {
+                Some(value) => list.iter().position(|known| *known == value).unwrap_or(list.len()),
+                None => list.len() + 1,
+            }
+        }
+        match criterion {
+            Criterion::Resolution => position(&self.resolutions, parsed.resolution()),
+            Criterion::Source => position(&self.sources, parsed.source()),
+            Criterion::Hdr => position(&self.hdr, parsed.hdr_format()),
+            Criterion::Audio => position(&self.audio, parsed.audio_codec()),
+            // Compared as a `Revision` in `deciding`
+            Criterion::Revision => 0,
+        }
+    }
+
+    fn describe(&self, parsed: &ParsedRelease) -> String {
+        let values: Vec<String> = self.criteria.iter().map(|&criterion| value_of(parsed, criterion)).filter(|value| value != "none").collect();
+        if values.is_empty() {
+            "no known quality".to_string()
+        } else {
+            values.join(", ")
+        }
+    }
+}
+
+impl Criterion {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Resolution => "resolution",
+            Self::Source => "source",
+            Self::Hdr => "HDR",
+            Self::Audio => "audio",
+            Self::Revision => "revision",
+        }
+    }
+}
+
+fn value_of(parsed: &ParsedRelease, criterion: Criterion) -> String {
+    let value = match criterion {
+        Criterion::Resolution => parsed.resolution.clone(),
+        Criterion::Source => parsed.source.clone(),
+        Criterion::Hdr => parsed.hdr.clone(),
+        Criterion::Audio => parsed.audio.clone(),
+        Criterion::Revision => match parsed.revision() {
+            revision if revision == Revision::default() => String::new(),
+            revision => revision.to_string(),
+        },
+    };
+    if value.is_empty() {
+        "none".to_string()
+    } else {
+        value
+    }
+}
+
+/// Key shared by releases of the same movie or episode: the normalized
+/// title and year, like `breakingbad:2008:s05e14`. Editions, parts and
+/// discs are told apart: `vanillasky:2001:directorscut:cd1`. See
+/// [`identity_keys`] for the keys by database ID.
+pub fn identity_key(parsed: &ParsedRelease) -> String {
+    let year = parsed.year.map(|year| year.to_string()).unwrap_or_default();
+    format!("{}:{}{}{}", normalize(&parsed.title), year, episode_key(parsed), version_key(parsed))
+}
+
+/// Every key identifying the release: [`identity_key`] when there is a
+/// title, and one per database ID, like `tvdb:79169:s01e01`.
+pub fn identity_keys(parsed: &ParsedRelease) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    if !parsed.title.is_empty() {
+        keys.push(identity_key(parsed));
+    }
+    for (name, id) in [("tmdb", &parsed.tmdb_id), ("tvdb", &parsed.tvdb_id), ("imdb", &parsed.imdb_id)] {
+        if let Some(id) = id {
+            keys.push(format!("{}:{}{}{}", name, id, episode_key(parsed), version_key(parsed)));
+        }
+    }
+    keys
+}
+
+/// `:s01e01e02` for episodes, `:2013-10-30` for daily shows, empty for movies.
+fn episode_key(parsed: &ParsedRelease) -> String {
+    if let Some(date) = &parsed.date {
+        return format!(":{}", date);
+    }
+    let episodes: Vec<u128> = if parsed.episodes.is_empty() { parsed.episode.into_iter().collect() } else { parsed.episodes.clone() };
+    match parsed.season {
+        Some(season) => format!(":s{:02}{}", season, episodes.iter().map(|episode| format!("e{:02}", episode)).collect::<String>()),
+        None if !episodes.is_empty() => episodes.iter().map(|episode| format!(":e{:02}", episode)).collect(),
+        None => String::new(),
+    }
+}
+
+/// `:directorscut:pt1:cd2` for the edition, part and disc of a release. A
+/// part that is an episode, or is left in the episode title, is already
+/// told apart by the episode.
+fn version_key(parsed: &ParsedRelease) -> String {
+    let mut key = String::new();
+    let edition = parsed.edition.as_deref().or_else(|| parsed.flags.iter().map(String::as_str).find(|flag| EDITION_FLAGS.contains(flag)));
+    if let Some(edition) = edition {
+        key.push_str(&format!(":{}", normalize(edition)));
+    }
+    if let (Some(part), true) = (parsed.part, episode_key(parsed).is_empty()) {
+        key.push_str(&format!(":pt{}", part));
+    }
+    if let Some(disc) = parsed.disc {
+        key.push_str(&format!(":cd{}", disc));
+    }
+    key
+}
+
+/// Lowercase letters and digits of `text`: `Director's Cut` as `directorscut`.
+fn normalize(text: &str) -> String {
+    text.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Indices of the releases grouped by identity, releases sharing any key in
+/// one group, in order of first appearance. Each group is named after the
+/// first key of its first release.
+fn group_by_identity(releases: &[ParsedRelease]) -> Vec<(String, Vec<usize>)> {
+    let mut group_of: Vec<usize> = Vec::with_capacity(releases.len());
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+    for (index, parsed) in releases.iter().enumerate() {
+        let keys = identity_keys(parsed);
+        let mut matching: Vec<usize> = keys.iter().filter_map(|key| by_key.get(key).copied()).collect();
+        matching.sort_unstable();
+        let group = matching.first().copied().unwrap_or(index);
+        // Merge groups this release links together
+        for other in &matching[1.min(matching.len())..] {
+            for member in group_of.iter_mut().filter(|member| **member == *other) {
+                *member = group;
+            }
+            for owner in by_key.values_mut().filter(|owner| **owner == *other) {
+                *owner = group;
+            }
+        }
+        group_of.push(group);
+        for key in keys {
+            by_key.insert(key, group);
+        }
+    }
+
+    let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+    for (index, group) in group_of.iter().enumerate() {
+        match groups.iter_mut().find(|(_, members)| group_of[members[0]] == *group) {
+            Some((_, members)) => members.push(index),
+            None => groups.push((identity_keys(&releases[index]).into_iter().next().unwrap_or_default(), vec![index])),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReleaseParser;
+
+    #[test]
+    fn test_duplicates() {
+        let parser = ReleaseParser::new("tv");
+        let releases: Vec<ParsedRelease> = [
+            "Show.S01E01.720p.HDTV.x264-AAA",
+            "Show.S01E01.1080p.WEB-DL.DDP5.1.H.264-BBB",
+            "Show.S01E02.1080p.WEB-DL.DDP5.1.H.264-BBB",
+            "Show.S01E01.1080p.BluRay.x264-CCC",
+            "show.s01e01.1080p.bluray.x264-DDD",
+        ]
+        .iter()
+        .map(|release| parser.parse(release))
+        .collect();
+
+        let groups = QualityRanking::default().duplicates(&releases);
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert_eq!(group.key, "show::s01e01");
+        let order: Vec<usize> = group.decisions.iter().map(|decision| decision.index).collect();
+        assert_eq!(order, vec![3, 4, 1, 0]);
+        assert_eq!(group.decisions[0].verdict, Verdict::Keep);
+        assert!(group.decisions[1..].iter().all(|decision| decision.verdict == Verdict::Delete));
+        assert!(group.decisions[1].justification.contains("same quality"));
+        assert_eq!(
+            group.decisions[2].justification,
+            "Show.S01E01.1080p.WEB-DL.DDP5.1.H.264-BBB is worse than the kept copy on source: WEB-DL < BluRay"
+        );
+        assert!(group.decisions[3].justification.contains("on resolution: 720p < 1080p"));
+    }
+
+    #[test]
+    fn test_revision_and_custom_ranking() {
+        let parser = ReleaseParser::new("movie");
+        let releases = vec![
+            parser.parse("Movie.2020.1080p.WEB-DL.DDP5.1.H.264-AAA"),
+            parser.parse("Movie.2020.PROPER.1080p.WEB-DL.DDP5.1.H.264-BBB"),
+            parser.parse("Movie (2020) {tmdb-123} [Bluray-720p]-CCC"),
+        ];
+        let ranking = QualityRanking::default();
+        assert_eq!(ranking.compare(&releases[1], &releases[0]), Ordering::Greater);
+        assert_eq!(ranking.duplicates(&releases)[0].decisions[0].index, 1);
+
+        // Source before resolution
+        let ranking = QualityRanking {
+            criteria: vec![Criterion::Source, Criterion::Resolution],
+            ..Default::default()
+        };
+        let group = &ranking.duplicates(&releases)[0];
+        assert_eq!(group.decisions[0].index, 2);
+        let order: Vec<usize> = group.decisions.iter().map(|decision| decision.index).collect();
+        assert_eq!(order, vec![2, 0, 1]);
+        assert_eq!(group.decisions[1].justification, "Movie.2020.1080p.WEB-DL.DDP5.1.H.264-AAA is worse than the kept copy on source: WEB-DL < BluRay");
+    }
+
+    #[test]
+    fn test_identity_keys() {
+        let parser = ReleaseParser::new("tv");
+        let parsed = parser.parse("Seinfeld (1989) {tvdb-79169} - S01E01-E02 - Pilot [HDTV-720p]-GRP");
+        assert_eq!(identity_keys(&parsed), vec!["seinfeld:1989:s01e01e02", "tvdb:79169:s01e01e02"]);
+
+        let mut renamed = parsed.clone();
+        renamed.title = "Seinfeld US".to_string();
+        let groups = group_by_identity(&[parsed, renamed]);
+        assert_eq!(groups.len(), 1);
+    }
+
+    #[test]
+    fn test_versions_kept_apart() {
+        let parser = ReleaseParser::new("movie");
+        let releases: Vec<ParsedRelease> = [
+            "Movie.2010.CD1.XviD-GRP",
+            "Movie.2010.CD2.XviD-GRP",
+            "Movie.2010.Part.1.1080p.BluRay.x264-GRP",
+            "Movie.2010.Part.2.1080p.BluRay.x264-GRP",
+            "Movie (2010) {tmdb-1} {edition-Directors Cut} [Bluray-1080p]-GRP",
+            "Movie (2010) {tmdb-1} [Bluray-1080p]-GRP",
+        ]
+        .iter()
+        .map(|release| parser.parse(release))
+        .collect();
+        assert!(QualityRanking::default().duplicates(&releases).is_empty());
+        assert_eq!(identity_key(&releases[1]), "movie:2010:cd2");
+
+        // A scene name tags the edition as a flag
+        let scene = parser.parse("Movie.2010.Directors.Cut.720p.BluRay.x264-AAA");
+        assert_eq!(identity_keys(&scene), vec!["movie:2010:directorscut"]);
+        let groups = QualityRanking::default().duplicates(&[releases[4].clone(), scene]);
+        assert_eq!(groups.len(), 1);
+    }
+}
@@ -0,0 +1,36 @@
+//! Errors of the `try_` parsing methods.
+
+use std::error::Error;
+use std::fmt;
+
+/// Why a release name or path could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The release name or path is empty or only whitespace
+    Empty,
+    /// A path component is not valid UTF-8; holds the component with invalid bytes replaced
+    NonUtf8Component(String),
+    /// The path has no file name (it ends in `/` or `..`)
+    NoFileName,
+    /// The file has no parent directory to read the series or movie from
+    NoParentDirectory,
+    /// The directories around the file don't match a known library layout
+    UnrecognizedLayout(String),
+    /// Strict mode: a mandatory field could not be determined
+    MissingField(&'static str),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty release name"),
+            Self::NonUtf8Component(component) => write!(f, "path component {:?} is not valid UTF-8", component),
+            Self::NoFileName => write!(f, "path has no file name"),
+            Self::NoParentDirectory => write!(f, "path has no parent directory"),
+            Self::UnrecognizedLayout(reason) => write!(f, "unrecognized layout: {}", reason),
+            Self::MissingField(field) => write!(f, "could not determine {}", field),
+        }
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,265 @@
+//! Which part of a release name produced which field.
+//!
+//! [`ReleaseParser::explain`](crate::ReleaseParser::explain) cuts the release
+//! name into segments, each labelled with the fields read from it, and can
+//! render the name with the segments underlined next to a legend. Paste the
+//! plain rendering into a bug report to show exactly where a misparse comes from.
+
+use crate::parser::Analysis;
+use crate::report::{FieldReport, ParseReport};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+
+/// ANSI colors given to the fields of an explanation, in order of appearance.
+const COLORS: &[&str] = &["31", "32", "33", "34", "35", "36", "91", "92", "93", "94", "95", "96"];
+
+/// Keys marking the fields in the plain rendering.
+const KEYS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// A piece of the release name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    /// Byte span in the release name
+    pub span: Range<usize>,
+    pub text: String,
+    /// Fields read from this segment, empty for separators and unrecognized
+    /// text. Usually one; `S05E14` gives both `season` and `episode`.
+    pub fields: Vec<String>,
+}
+
+/// A release name cut into [`Segment`]s, with the report of the parse.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Explanation {
+    pub release: String,
+    /// Segments covering the whole release name, in order
+    pub segments: Vec<Segment>,
+    pub report: ParseReport,
+}
+
+impl Explanation {
+    pub(crate) fn new(release_name: &str, analysis: Analysis) -> Self {
+        let labels = byte_labels(release_name, &analysis);
+        let mut segments: Vec<Segment> = Vec::new();
+        let mut start = 0;
+        for end in 1..=labels.len() {
+            if end < labels.len() && labels[end] == labels[start] {
+                continue;
+            }
+            let span = start..end;
+            let fields = match labels[start] {
+                Some(label) => fields_of(label, &span, &analysis.fields),
+                None => Vec::new(),
+            };
+            segments.push(Segment {
+                text: release_name[span.clone()].to_string(),
+                span,
+                fields,
+            });
+            start = end;
+        }
+
+        Self {
+            release: release_name.to_string(),
+            segments,
+            report: ParseReport {
+                parsed: analysis.parsed,
+                fields: analysis.fields,
+                alternatives: analysis.alternatives,
+            },
+        }
+    }
+
+    /// Spans of the segments `field` was read from.
+    pub fn spans(&self, field: &str) -> Vec<Range<usize>> {
+        self.segments
+            .iter()
+            .filter(|segment| segment.fields.iter().any(|name| name == field))
+            .map(|segment| segment.span.clone())
+            .collect()
+    }
+
+    /// The release name with each field underlined in its own color, a key
+    /// line below it and a legend with the value, rule and confidence of
+    /// every field.
+    pub fn render(&self) -> String {
+        self.render_with(true)
+    }
+
+    /// Like [`render`](Self::render) but without ANSI colors, for logs and
+    /// bug reports. Fields are told apart by the letters of the key line.
+    pub fn render_plain(&self) -> String {
+        self.render_with(false)
+    }
+
+    fn render_with(&self, color: bool) -> String {
+        let legend = self.legend();
+        let paint = |text: &str, index: Option<usize>, underline: bool| match index {
+            Some(index) if color => {
+                let style = if underline { "4;" } else { "" };
+                format!("\x1b[{}{}m{}\x1b[0m", style, COLORS[index % COLORS.len()], text)
+            }
+            _ => text.to_string(),
+        };
+
+        let mut name = String::new();
+        let mut keys = String::new();
+        for segment in &self.segments {
+            let index = legend.iter().position(|(fields, _)| *fields == segment.fields);
+            name.push_str(&paint(&segment.text, index, true));
+            let width = segment.text.chars().count();
+            let key = match index {
+                Some(index) => (KEYS[index % KEYS.len()] as char).to_string().repeat(width),
+                None => " ".repeat(width),
+            };
+            keys.push_str(&paint(&key, index, false));
+        }
+
+        let mut out = format!("{}\n{}\n", name, keys.trim_end());
+        for (index, (fields, reports)) in legend.iter().enumerate() {
+            let key = (KEYS[index % KEYS.len()] as char).to_string();
+            let details: Vec<String> = reports
+                .iter()
+                .map(|report| format!("{}={:?} ({} {:.2})", report.field, report.value, report.rule, report.confidence))
+                .collect();
+            let line = format!("{} {:<20} {}", key, fields.join("+"), details.join(", "));
+            out.push_str(&paint(&line, Some(index), false));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Distinct field sets of the segments with their reports, in order of appearance.
+    fn legend(&self) -> Vec<(Vec<String>, Vec<&FieldReport>)> {
+        let mut legend: Vec<(Vec<String>, Vec<&FieldReport>)> = Vec::new();
+        for segment in &self.segments {
+            if segment.fields.is_empty() || legend.iter().any(|(fields, _)| *fields == segment.fields) {
+                continue;
+            }
+            let spans: Vec<&Range<usize>> = self.segments.iter().filter(|other| other.fields == segment.fields).map(|other| &other.span).collect();
+            let reports = self
+                .report
+                .fields
+                .iter()
+                .filter(|report| segment.fields.contains(&report.field))
+                .filter(|report| report.span.as_ref().is_some_and(|span| spans.iter().any(|other| overlaps(span, other))))
+                .collect();
+            legend.push((segment.fields.clone(), reports));
+        }
+        legend
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render_plain())
+    }
+}
+
+/// Field label of every byte of the release name.
+///
+/// Tokens get the field that owns them. Unowned tokens get the field of a
+/// match inside them (`x264` in `DD5.1.x264`). Separators between two bytes
+/// of the same field belong to that field, so `Breaking.Bad` is one segment.
+fn byte_labels<'a>(release_name: &str, analysis: &'a Analysis) -> Vec<Option<&'a str>> {
+    let mut labels: Vec<Option<&str>> = vec![None; release_name.len()];
+    for (token, owner) in analysis.tokens.iter().zip(&analysis.owners) {
+        if owner.is_some() {
+            labels[token.span.clone()].fill(*owner);
+        }
+    }
+    for report in &analysis.fields {
+        let Some(span) = &report.span else { continue };
+        let inside_free_token = analysis
+            .tokens
+            .iter()
+            .zip(&analysis.owners)
+            .any(|(token, owner)| owner.is_none() && span.start >= token.span.start && span.end <= token.span.end);
+        if inside_free_token {
+            labels[span.clone()].fill(Some(report.field.as_str()));
+        }
+    }
+
+    let in_token: Vec<bool> = (0..release_name.len())
+        .map(|i| analysis.tokens.iter().any(|token| token.span.contains(&i)))
+        .collect();
+    let mut i = 0;
+    while i < labels.len() {
+        if in_token[i] {
+            i += 1;
+            continue;
+        }
+        let end = (i..labels.len()).find(|&j| in_token[j]).unwrap_or(labels.len());
+        let before = i.checked_sub(1).and_then(|j| labels[j]);
+        let after = labels.get(end).copied().flatten();
+        if before.is_some() && before == after {
+            labels[i..end].fill(before);
+        }
+        i = end;
+    }
+    labels
+}
+
+/// Fields read from a segment labelled `label`: the field itself and any
+/// field read from exactly the same match (the season of `S05E14`).
+fn fields_of(label: &str, span: &Range<usize>, reports: &[FieldReport]) -> Vec<String> {
+    let matches: Vec<&Range<usize>> = reports
+        .iter()
+        .filter(|report| report.field == label)
+        .filter_map(|report| report.span.as_ref())
+        .filter(|report_span| overlaps(report_span, span))
+        .collect();
+    let mut fields: Vec<String> = Vec::new();
+    for report in reports {
+        let same_match = report.field == label || report.span.as_ref().is_some_and(|report_span| matches.contains(&report_span));
+        if same_match && !fields.contains(&report.field) {
+            fields.push(report.field.clone());
+        }
+    }
+    if !fields.iter().any(|field| field == label) {
+        fields.push(label.to_string());
+    }
+    fields
+}
+
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ReleaseParser;
+
+    #[test]
+    fn test_segments_cover_release_name() {
+        let release = "Breaking.Bad.S05E14.Ozymandias.1080p.WEB-DL.DD5.1.H.264-GROUP";
+        let explanation = ReleaseParser::new("tv").explain(release);
+
+        let joined: String = explanation.segments.iter().map(|segment| segment.text.as_str()).collect();
+        assert_eq!(joined, release);
+        assert_eq!(explanation.spans("title"), vec![0..12]);
+        assert_eq!(explanation.spans("season"), vec![13..19]);
+        assert_eq!(explanation.spans("episode"), vec![13..19]);
+        assert_eq!(explanation.spans("group"), vec![56..61]);
+        assert_eq!(explanation.report.parsed.title, "Breaking Bad");
+    }
+
+    #[test]
+    fn test_match_inside_word() {
+        let explanation = ReleaseParser::new("movie").explain("Movie.2020.DD5.1x264-GRP");
+        assert_eq!(explanation.spans("format"), vec![16..20]);
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let rendered = ReleaseParser::new("movie").explain("The.Matrix.1999.1080p-GROUP").render_plain();
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines[0], "The.Matrix.1999.1080p-GROUP");
+        assert_eq!(lines[1], "aaaaaaaaaa bbbb ccccc ddddd");
+        assert!(lines[2].starts_with("a title"));
+        assert!(lines[3].contains("year=\"1999\" (year.bare"));
+        assert!(!rendered.contains('\x1b'));
+        assert!(ReleaseParser::new("movie").explain("The.Matrix.1999").render().contains("\x1b[4;31mThe.Matrix\x1b[0m"));
+    }
+}
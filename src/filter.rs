@@ -0,0 +1,489 @@
+//! Filter expressions over parsed releases.
+//!
+//! A [`Filter`] is read from an expression such as
+//! `resolution >= 1080p and source in [WEB-DL, BluRay] and not flags contains NUKED and group != "XYZ"`.
+//! Fields have the names of [`ParsedRelease::get`]. Values are bare words or
+//! double-quoted strings.
+//!
+//! - `==` (or `=`) and `!=` compare like scoring conditions: source, codec,
+//!   resolution, HDR and audio by canonical name, numbers as numbers, other
+//!   text ignoring case
+//! - `<`, `<=`, `>` and `>=` work on `resolution` (by height), `year`,
+//!   `season`, `episode`, `disc`, `part` and `volume`, and never hold when the field is missing
+//! - `in [a, b]` holds when the field equals one of the values
+//! - `contains` tests a flag, an episode of a multi-episode release, a
+//!   season of a season pack, an unparsed token, or a substring of a text
+//!   field, ignoring case
+//!
+//! Comparisons combine with `not`, `and` and `or`, in that order of
+//! precedence, and parentheses.
+
+use crate::media::Resolution;
+use crate::types::ParsedRelease;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Fields with an order, for `<`, `<=`, `>` and `>=`.
+const ORDERED_FIELDS: &[&str] = &["resolution", "year", "season", "episode", "disc", "part", "volume"];
+
+/// A parsed filter expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    expression: String,
+    root: Expr,
+}
+
+/// Why a filter expression could not be read. Positions are byte offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterError {
+    /// The expression stops where more was expected
+    UnexpectedEnd,
+    /// A token that doesn't fit, and where it starts
+    UnexpectedToken { position: usize, found: String },
+    /// A string opened at the position is not closed
+    UnclosedQuote(usize),
+    /// A field [`get`](ParsedRelease::get) doesn't know
+    UnknownField(String),
+    /// An operator the field can't be compared with, like `title > 3`
+    UnsupportedOperator { field: String, operator: String },
+    /// A value the field can't be ordered by, like `year >= soon`
+    InvalidValue { field: String, value: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare { field: String, operator: Operator, values: Vec<String> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    In,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// A bare word or, when quoted, a string
+    Word { text: String, quoted: bool },
+    Operator(Operator),
+    OpenParen,
+    CloseParen,
+    OpenBracket,
+    CloseBracket,
+    Comma,
+}
+
+impl Filter {
+    pub fn parse(expression: &str) -> Result<Self, FilterError> {
+        let tokens = tokenize(expression)?;
+        let mut parser = Parser { tokens, next: 0 };
+        let root = parser.or()?;
+        if let Some((position, token)) = parser.tokens.get(parser.next) {
+            return Err(FilterError::UnexpectedToken {
+                position: *position,
+                found: token.to_string(),
+            });
+        }
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            root,
+        })
+    }
+
+    /// The expression the filter was read from.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Whether a release passes the filter.
+    pub fn matches(&self, parsed: &ParsedRelease) -> bool {
+        self.root.matches(parsed)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilterError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        Self::parse(expression)
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+impl Expr {
+    fn matches(&self, parsed: &ParsedRelease) -> bool {
+        match self {
+            Self::Or(left, right) => left.matches(parsed) || right.matches(parsed),
+            Self::And(left, right) => left.matches(parsed) && right.matches(parsed),
+            Self::Not(expr) => !expr.matches(parsed),
+            Self::Compare { field, operator, values } => compare(parsed, field, *operator, values),
+        }
+    }
+}
+
+fn compare(parsed: &ParsedRelease, field: &str, operator: Operator, values: &[String]) -> bool {
+    let value = values.first().map(String::as_str).unwrap_or_default();
+    let ordering = || Some(field_number(parsed, field)?.cmp(&value_number(field, value)?));
+    match operator {
+        Operator::Equal => parsed.field_is(field, value),
+        Operator::NotEqual => !parsed.field_is(field, value),
+        Operator::In => values.iter().any(|value| parsed.field_is(field, value)),
+        Operator::Less => ordering().is_some_and(|ordering| ordering.is_lt()),
+        Operator::LessOrEqual => ordering().is_some_and(|ordering| ordering.is_le()),
+        Operator::Greater => ordering().is_some_and(|ordering| ordering.is_gt()),
+        Operator::GreaterOrEqual => ordering().is_some_and(|ordering| ordering.is_ge()),
+        Operator::Contains => {
+            let same = |candidate: &String| candidate.eq_ignore_ascii_case(value);
+            match field {
+                "flags" => parsed.flags.iter().any(same),
+                "unparsed_tokens" => parsed.unparsed_tokens.iter().any(same),
+                "episodes" => parsed.episodes.iter().any(|episode| value.parse() == Ok(*episode)),
+                "seasons" => parsed.seasons.iter().any(|season| value.parse() == Ok(*season)),
+                _ => parsed.get(field).unwrap_or_default().to_lowercase().contains(&value.to_lowercase()),
+            }
+        }
+    }
+}
+
+/// The value of an ordered field; the first episode of a multi-episode release.
+fn field_number(parsed: &ParsedRelease, field: &str) -> Option<u128> {
+    match field {
+        "resolution" => parsed.resolution()?.height().map(u128::from),
+        "year" => parsed.year.map(u128::from),
+        "season" => parsed.season,
+        "episode" => parsed.episode.or_else(|| parsed.episodes.first().copied()),
+        "disc" => parsed.disc.map(u128::from),
+        "part" => parsed.part.map(u128::from),
+        "volume" => parsed.volume.map(u128::from),
+        _ => None,
+    }
+}
+
+fn value_number(field: &str, value: &str) -> Option<u128> {
+    match field {
+        "resolution" => value.parse::<Resolution>().unwrap_or_else(|never| match never {}).height().map(u128::from),
+        _ => value.parse().ok(),
+    }
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+}
+
+impl Parser {
+    fn or(&mut self) -> Result<Expr, FilterError> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, FilterError> {
+        let mut expr = self.unary()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, FilterError> {
+        if self.keyword("not") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.peek() == Some(&Token::OpenParen) {
+            self.next += 1;
+            let expr = self.or()?;
+            self.expect(Token::CloseParen)?;
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, FilterError> {
+        let field = self.word()?.to_lowercase();
+        if !ParsedRelease::FIELDS.contains(&field.as_str()) {
+            return Err(FilterError::UnknownField(field));
+        }
+
+        let operator = match self.advance()? {
+            (_, Token::Operator(operator)) => operator,
+            (_, Token::Word { text, quoted: false }) if text.eq_ignore_ascii_case("in") => Operator::In,
+            (_, Token::Word { text, quoted: false }) if text.eq_ignore_ascii_case("contains") => Operator::Contains,
+            (position, token) => {
+                return Err(FilterError::UnexpectedToken {
+                    position,
+                    found: token.to_string(),
+                })
+            }
+        };
+        let ordered = matches!(operator, Operator::Less | Operator::LessOrEqual | Operator::Greater | Operator::GreaterOrEqual);
+        if (ordered && !ORDERED_FIELDS.contains(&field.as_str())) || (field == "flags" && !matches!(operator, Operator::Contains | Operator::In)) {
+            return Err(FilterError::UnsupportedOperator {
+                field,
+                operator: operator.to_string(),
+            });
+        }
+
+        let values = if operator == Operator::In { self.list()? } else { vec![self.word()?] };
+        if ordered && value_number(&field, &values[0]).is_none() {
+            return Err(FilterError::InvalidValue {
+                field,
+                value: values[0].clone(),
+            });
+        }
+        Ok(Expr::Compare { field, operator, values })
+    }
+
+    /// `[a, b, c]`; a single value without brackets is taken as a list of one.
+    fn list(&mut self) -> Result<Vec<String>, FilterError> {
+        if self.peek() != Some(&Token::OpenBracket) {
+            return Ok(vec![self.word()?]);
+        }
+        self.next += 1;
+        let mut values = Vec::new();
+        loop {
+            if self.peek() == Some(&Token::CloseBracket) {
+                self.next += 1;
+                return Ok(values);
+            }
+            values.push(self.word()?);
+            if self.peek() == Some(&Token::Comma) {
+                self.next += 1;
+            } else {
+                self.expect(Token::CloseBracket)?;
+                return Ok(values);
+            }
+        }
+    }
+
+    fn word(&mut self) -> Result<String, FilterError> {
+        match self.advance()? {
+            (_, Token::Word { text, .. }) => Ok(text),
+            (position, token) => Err(FilterError::UnexpectedToken {
+                position,
+                found: token.to_string(),
+            }),
+        }
+    }
+
+    /// Consume an unquoted keyword, ignoring case.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Word { text, quoted: false }) if text.eq_ignore_ascii_case(keyword));
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), FilterError> {
+        match self.advance()? {
+            (_, token) if token == expected => Ok(()),
+            (position, token) => Err(FilterError::UnexpectedToken {
+                position,
+                found: token.to_string(),
+            }),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn advance(&mut self) -> Result<(usize, Token), FilterError> {
+        let token = self.tokens.get(self.next).cloned().ok_or(FilterError::UnexpectedEnd)?;
+        self.next += 1;
+        Ok(token)
+    }
+}
+
+fn tokenize(expression: &str) -> Result<Vec<(usize, Token)>, FilterError> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.char_indices().peekable();
+    while let Some(&(position, c)) = chars.peek() {
+        let token = match c {
+            _ if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' | ')' | '[' | ']' | ',' => {
+                chars.next();
+                match c {
+                    '(' => Token::OpenParen,
+                    ')' => Token::CloseParen,
+                    '[' => Token::OpenBracket,
+                    ']' => Token::CloseBracket,
+                    _ => Token::Comma,
+                }
+            }
+            '=' | '!' | '<' | '>' => {
+                chars.next();
+                let equals = chars.next_if(|&(_, next)| next == '=').is_some();
+                match (c, equals) {
+                    ('=', _) => Token::Operator(Operator::Equal),
+                    ('!', true) => Token::Operator(Operator::NotEqual),
+                    ('<', false) => Token::Operator(Operator::Less),
+                    ('<', true) => Token::Operator(Operator::LessOrEqual),
+                    ('>', false) => Token::Operator(Operator::Greater),
+                    ('>', true) => Token::Operator(Operator::GreaterOrEqual),
+                    _ => {
+                        return Err(FilterError::UnexpectedToken {
+                            position,
+                            found: c.to_string(),
+                        })
+                    }
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => text.extend(chars.next().map(|(_, escaped)| escaped)),
+                        Some((_, c)) => text.push(c),
+                        None => return Err(FilterError::UnclosedQuote(position)),
+                    }
+                }
+                Token::Word { text, quoted: true }
+            }
+            _ => {
+                let mut text = String::new();
+                while let Some((_, c)) = chars.next_if(|&(_, c)| !c.is_whitespace() && !"()[],=!<>\"".contains(c)) {
+                    text.push(c);
+                }
+                Token::Word { text, quoted: false }
+            }
+        };
+        tokens.push((position, token));
+    }
+    Ok(tokens)
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::Less => "<",
+            Self::LessOrEqual => "<=",
+            Self::Greater => ">",
+            Self::GreaterOrEqual => ">=",
+            Self::In => "in",
+            Self::Contains => "contains",
+        })
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Word { text, quoted: true } => write!(f, "{:?}", text),
+            Self::Word { text, quoted: false } => f.write_str(text),
+            Self::Operator(operator) => write!(f, "{}", operator),
+            Self::OpenParen => f.write_str("("),
+            Self::CloseParen => f.write_str(")"),
+            Self::OpenBracket => f.write_str("["),
+            Self::CloseBracket => f.write_str("]"),
+            Self::Comma => f.write_str(","),
+        }
+    }
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of filter"),
+            Self::UnexpectedToken { position, found } => write!(f, "unexpected {:?} at {}", found, position),
+            Self::UnclosedQuote(position) => write!(f, "unclosed quote at {}", position),
+            Self::UnknownField(field) => write!(f, "unknown field {:?}", field),
+            Self::UnsupportedOperator { field, operator } => write!(f, "{} can't be compared with {}", field, operator),
+            Self::InvalidValue { field, value } => write!(f, "{:?} is not a valid {}", value, field),
+        }
+    }
+}
+
+impl Error for FilterError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReleaseParser;
+
+    fn matching(expression: &str, releases: &[&str]) -> Vec<usize> {
+        let filter = Filter::parse(expression).unwrap();
+        let parser = ReleaseParser::new("tv");
+        releases.iter().enumerate().filter(|(_, release)| filter.matches(&parser.parse(release))).map(|(index, _)| index).collect()
+    }
+
+    #[test]
+    fn test_filter() {
+        let releases = [
+            "Show.S01E01.720p.HDTV.x264-AAA",
+            "Show.S01E01.1080p.WEB-DL.DDP5.1.H.264-BBB",
+            "Show.S01E01.2160p.BluRay.x265-XYZ",
+            "Show.S02E03.NUKED.1080p.BluRay.x264-CCC",
+            "Show.S02E04.1080p.WEBRip.x264-DDD",
+        ];
+        let expression = r#"resolution >= 1080p and source in [WEB-DL, BluRay] and not flags contains NUKED and group != "XYZ""#;
+        assert_eq!(matching(expression, &releases), vec![1]);
+        assert_eq!(matching("season > 1 or format == HEVC", &releases), vec![2, 3, 4]);
+        assert_eq!(matching("(season = 2 and episode <= 3) or resolution < 1080p", &releases), vec![0, 3]);
+        assert_eq!(matching("NOT (source in [webrip] OR group contains b)", &releases), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_typed_comparisons() {
+        let parser = ReleaseParser::new("movie");
+        let movie = parser.parse("Movie.1999.2160p.BluRay.x264-GRP");
+        assert!(Filter::parse("resolution > 1080p and resolution == 4K").unwrap().matches(&movie));
+        assert!(Filter::parse("year < 2000 and year >= 1999").unwrap().matches(&movie));
+        // A missing field is never ordered
+        assert!(!Filter::parse("season < 5").unwrap().matches(&movie));
+        assert!(Filter::parse("not season >= 0").unwrap().matches(&movie));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Filter::parse("codec == x265"), Err(FilterError::UnknownField("codec".to_string())));
+        assert_eq!(
+            Filter::parse("title > 3"),
+            Err(FilterError::UnsupportedOperator {
+                field: "title".to_string(),
+                operator: ">".to_string()
+            })
+        );
+        assert_eq!(
+            Filter::parse("year >= soon"),
+            Err(FilterError::InvalidValue {
+                field: "year".to_string(),
+                value: "soon".to_string()
+            })
+        );
+        assert_eq!(Filter::parse("source in [WEB-DL,"), Err(FilterError::UnexpectedEnd));
+        assert_eq!(Filter::parse("group == \"XYZ"), Err(FilterError::UnclosedQuote(9)));
+        assert_eq!(
+            Filter::parse("year > 2000 2001"),
+            Err(FilterError::UnexpectedToken {
+                position: 12,
+                found: "2001".to_string()
+            })
+        );
+    }
+}
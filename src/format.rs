@@ -0,0 +1,359 @@
+//! Render a [`ParsedRelease`] back into a release name.
+//!
+//! Two styles are supported, both of which the parser reads back:
+//!
+//! - [`NameStyle::Scene`], `Title.S01E02.Episode.Title.1080p.AMZN.WEB-DL.DDP5.1.H.264-GROUP`
+//! - [`NameStyle::Bracket`], `Title (2023) {tmdb-1} - S01E02 - Episode Title [WEBDL-1080p][EAC3 5.1][h264]-GROUP`
+//!
+//! Scene names have no place for database IDs or an edition, so those are
+//! only written in the bracket style.
+
+use crate::types::{ParsedRelease, SpecialKind};
+use serde::{Deserialize, Serialize};
+
+/// Layout of a formatted release name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameStyle {
+    /// Dot-separated scene name
+    Scene,
+    /// Spaces, `(year)`, `{id}` and `[quality]` blocks, as written by Sonarr and Radarr
+    Bracket,
+}
+
+/// Characters that are not allowed in file names on common file systems.
+const ILLEGAL: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+impl ParsedRelease {
+    /// Render the release back into a name in the given style.
+    ///
+    /// Empty fields are left out. Parsing the result gives back the same
+    /// title, year, season, episodes, date, episode title, resolution,
+    /// source, codec, audio, HDR format, streaming provider, flags,
+    /// languages, special kind, part, volume and group (plus IDs and
+    /// edition in the bracket style).
+    pub fn format(&self, style: NameStyle) -> String {
+        match style {
+            NameStyle::Scene => self.format_scene(),
+            NameStyle::Bracket => self.format_bracket(),
+        }
+    }
+
+    fn format_scene(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        push_words(&mut parts, &self.title);
+        if let Some(year) = self.year {
+            parts.push(year.to_string());
+        }
+        if let Some(date) = &self.date {
+            parts.push(format!("{}.{:02}.{:02}", date.year, date.month, date.day));
+        } else if let Some(episode) = self.episode_marker(NameStyle::Scene) {
+            parts.push(episode);
+        }
+        if let Some(volume) = self.volume {
+            parts.push(format!("Vol.{}", volume));
+        }
+        push_words(&mut parts, &self.episode_title);
+        for flag in &self.flags {
+            push_words(&mut parts, flag);
+        }
+        for language in self.language_names() {
+            push_words(&mut parts, language);
+        }
+        parts.push(self.resolution.clone());
+        parts.push(self.streaming_provider.clone());
+        parts.push(self.source.clone());
+        push_words(&mut parts, &scene_audio(&self.audio));
+        push_words(&mut parts, &self.hdr);
+        parts.push(self.format.clone());
+        parts.retain(|part| !part.is_empty());
+
+        with_group(parts.join("."), &self.group)
+    }
+
+    fn format_bracket(&self) -> String {
+        let mut name = sanitize(&self.title);
+        if let Some(year) = self.year {
+            name.push_str(&format!(" ({})", year));
+        }
+        for (key, id) in [("tmdb", &self.tmdb_id), ("tvdb", &self.tvdb_id), ("imdb", &self.imdb_id)] {
+            if let Some(id) = id {
+                name.push_str(&format!(" {{{}-{}}}", key, id));
+            }
+        }
+        if let Some(edition) = &self.edition {
+            name.push_str(&format!(" {{edition-{}}}", sanitize(edition)));
+        }
+        if let Some(volume) = self.volume {
+            name.push_str(&format!(" Vol {}", volume));
+        }
+        let marker = self.date.map(|date| date.to_string()).or_else(|| self.episode_marker(NameStyle::Bracket));
+        if let Some(marker) = marker {
+            name.push_str(&format!(" - {}", marker));
+            if !self.episode_title.is_empty() {
+                name.push_str(&format!(" - {}", sanitize(&self.episode_title)));
+            }
+        }
+
+        // [AMZN WEBDL-1080p Proper], the quality block
+        let mut quality: Vec<String> = Vec::new();
+        quality.push(self.streaming_provider.clone());
+        // `AMZN WEBDL` reads back as `WEBDL`, so keep the dash after a provider
+        let source = if self.source == "WEB-DL" && !self.streaming_provider.is_empty() { "WEB-DL" } else { bracket_source(&self.source) };
+        quality.push(quality_title(source, &self.resolution));
+        quality.extend(self.flags.iter().map(|flag| sanitize(flag)));
+        quality.retain(|part| !part.is_empty());
+
+        let mut blocks = vec![quality.join(" "), self.hdr.clone(), self.audio.clone()];
+        blocks.extend(self.language_names().into_iter().map(sanitize));
+        blocks.push(self.format.clone());
+        let blocks: String = blocks.iter().filter(|block| !block.is_empty()).map(|block| format!("[{}]", block)).collect();
+        if !blocks.is_empty() {
+            name.push(' ');
+            name.push_str(&blocks);
+        }
+
+        with_group(name, &self.group)
+    }
+
+    /// `S01E02`, `S01E01-E03` for a run of episodes, `S01E01E03` otherwise;
+    /// `S02` or `S01-S05` for a season pack.
+    ///
+    /// Without a season the part, special or episode number is written
+    /// instead: `Part.3.of.6`, `OVA.02`, `E005`.
+    pub(crate) fn episode_marker(&self, style: NameStyle) -> Option<String> {
+        let episodes = if self.episodes.is_empty() { self.episode.into_iter().collect() } else { self.episodes.clone() };
+        if episodes.is_empty() && (self.season.is_some() || (self.part.is_none() && self.special_kind.is_none())) {
+            let (first, last) = (*self.seasons.first()?, *self.seasons.last()?);
+            return Some(if first == last { format!("S{:02}", first) } else { format!("S{:02}-S{:02}", first, last) });
+        }
+        let Some(season) = self.season else {
+            return self.seasonless_marker(&episodes, style);
+        };
+        let (first, last) = (*episodes.first()?, *episodes.last()?);
+        let contiguous = episodes.windows(2).all(|pair| pair[1] == pair[0] + 1);
+        let marker = if episodes.len() == 1 {
+            format!("S{:02}E{:02}", season, first)
+        } else if contiguous {
+            format!("S{:02}E{:02}-E{:02}", season, first, last)
+        } else {
+            let list: String = episodes.iter().map(|episode| format!("E{:02}", episode)).collect();
+            format!("S{:02}{}", season, list)
+        };
+        // "S01E05.NCOP"; with an episode title the tag would be read as part of it
+        Some(match self.special_kind {
+            Some(kind @ (SpecialKind::Ova | SpecialKind::Oad | SpecialKind::Ona | SpecialKind::CreditlessOpening | SpecialKind::CreditlessEnding))
+                if self.episode_title.is_empty() =>
+            {
+                format!("{}{}{}", marker, separator(style), special_tag(kind))
+            }
+            _ => marker,
+        })
+    }
+
+    fn seasonless_marker(&self, episodes: &[u128], style: NameStyle) -> Option<String> {
+        let separator = separator(style);
+        if let Some(part) = self.part {
+            let total = self.part_total.map(|total| format!("{0}of{0}{1}", separator, total)).unwrap_or_default();
+            return Some(format!("Part{}{}{}", separator, part, total));
+        }
+        if let Some(kind) = self.special_kind {
+            let numbered = !matches!(kind, SpecialKind::Recap | SpecialKind::Pilot | SpecialKind::Preview);
+            return Some(match episodes {
+                [number] if numbered => format!("{}{}{:02}", special_tag(kind), separator, number),
+                _ => special_tag(kind).to_string(),
+            });
+        }
+        let (first, last) = (*episodes.first()?, *episodes.last()?);
+        let contiguous = episodes.windows(2).all(|pair| pair[1] == pair[0] + 1);
+        // A bare `005` only reads as an episode between dashes, so it needs an episode title after it
+        let bare = style == NameStyle::Bracket && !self.episode_title.is_empty();
+        Some(if episodes.len() == 1 {
+            if bare { format!("{:03}", first) } else { format!("E{:03}", first) }
+        } else if contiguous {
+            if bare { format!("{:03}-{:03}", first, last) } else { format!("E{:02}-E{:02}", first, last) }
+        } else {
+            episodes.iter().map(|episode| format!("E{:02}", episode)).collect()
+        })
+    }
+
+    /// Language names ordered by language code, so the output is stable.
+    fn language_names(&self) -> Vec<&str> {
+        let mut languages: Vec<(&String, &String)> = self.language.iter().collect();
+        languages.sort();
+        languages.into_iter().map(|(_, name)| name.as_str()).collect()
+    }
+}
+
+/// Separator between the words of a marker: `Part.3.of.6`, `Part 3 of 6`.
+fn separator(style: NameStyle) -> &'static str {
+    match style {
+        NameStyle::Scene => ".",
+        NameStyle::Bracket => " ",
+    }
+}
+
+/// The tag a special is released under.
+fn special_tag(kind: SpecialKind) -> &'static str {
+    match kind {
+        SpecialKind::Special => "Special",
+        SpecialKind::Ova => "OVA",
+        SpecialKind::Oad => "OAD",
+        SpecialKind::Ona => "ONA",
+        SpecialKind::CreditlessOpening => "NCOP",
+        SpecialKind::CreditlessEnding => "NCED",
+        SpecialKind::Recap => "Recap",
+        SpecialKind::Pilot => "Pilot",
+        SpecialKind::Preview => "Preview",
+    }
+}
+
+/// Quality as Sonarr and Radarr write it: `WEBDL-1080p`, `Bluray-2160p Remux`.
+pub(crate) fn quality_title(source: &str, resolution: &str) -> String {
+    match (source, resolution) {
+        ("Remux", "") => "Remux".to_string(),
+        ("Remux", resolution) => format!("Bluray-{} Remux", resolution),
+        ("", resolution) => resolution.to_string(),
+        (source, "") => source.to_string(),
+        (source, resolution) => format!("{}-{}", source, resolution),
+    }
+}
+
+/// Source name as written in quality blocks: `WEBDL`, `Bluray`, `HDTV`.
+pub(crate) fn bracket_source(source: &str) -> &str {
+    match source {
+        "WEB-DL" => "WEBDL",
+        "BluRay" => "Bluray",
+        _ => source,
+    }
+}
+
+/// `DDP 5.1` as `DDP5.1` and `AAC 2.0` as `AAC2.0`, the way scene names
+/// write Dolby Digital and AAC; other codecs keep the channels separate.
+fn scene_audio(audio: &str) -> String {
+    match audio.split_once(' ') {
+        Some((codec @ ("AAC" | "DD" | "DDP"), channels)) => format!("{}{}", codec, channels),
+        _ => audio.to_string(),
+    }
+}
+
+/// Split `text` into words and append them, without characters that would
+/// break the name.
+fn push_words(parts: &mut Vec<String>, text: &str) {
+    parts.extend(sanitize(text).split_whitespace().map(str::to_string));
+}
+
+fn sanitize(text: &str) -> String {
+    text.chars().filter(|c| !ILLEGAL.contains(c)).collect::<String>().trim().to_string()
+}
+
+fn with_group(name: String, group: &str) -> String {
+    if group.is_empty() {
+        name
+    } else {
+        format!("{}-{}", name, group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReleaseParser;
+
+    /// Fields both styles carry.
+    const FIELDS: &[&str] = &[
+        "title", "episode_title", "year", "season", "episode", "episodes", "seasons", "pack", "source", "format", "resolution", "audio", "hdr",
+        "streaming_provider", "special_kind", "part", "part_total", "volume", "group",
+    ];
+
+    fn assert_round_trip(parser: &ReleaseParser, release: &str, style: NameStyle, fields: &[&str]) {
+        let parsed = parser.parse(release);
+        let name = parsed.format(style);
+        let reparsed = parser.parse(&name);
+        for field in fields {
+            assert_eq!(reparsed.get(field), parsed.get(field), "{} of {:?} formatted as {:?}", field, release, name);
+        }
+        assert_eq!(reparsed.flags, parsed.flags, "flags of {:?} formatted as {:?}", release, name);
+        assert_eq!(reparsed.language, parsed.language, "languages of {:?} formatted as {:?}", release, name);
+    }
+
+    #[test]
+    fn test_format_scene() {
+        let parsed = ReleaseParser::new("tv").parse("Breaking Bad (2008) - S01E02 - Cat's in the Bag [AMZN WEB-DL-1080p][DDP 5.1][H.264]-GROUP");
+        assert_eq!(parsed.format(NameStyle::Scene), "Breaking.Bad.2008.S01E02.Cat's.in.the.Bag.1080p.AMZN.WEB-DL.DDP5.1.H.264-GROUP");
+    }
+
+    #[test]
+    fn test_format_bracket() {
+        let parsed = ReleaseParser::new("movie").parse("The.Matrix.1999.REPACK.1080p.BluRay.DTS-HD.MA.5.1.x264-GROUP");
+        assert_eq!(parsed.format(NameStyle::Bracket), "The Matrix (1999) [Bluray-1080p REPACK][DTS-HD MA 5.1][x264]-GROUP");
+
+        let parsed = ReleaseParser::new("tv").parse("Show.S01E01-E03.720p.HDTV.x264-GRP");
+        assert_eq!(parsed.format(NameStyle::Bracket), "Show - S01E01-E03 [HDTV-720p][x264]-GRP");
+    }
+
+    #[test]
+    fn test_format_without_season() {
+        let parser = ReleaseParser::new("tv");
+        let parsed = parser.parse("Planet.Earth.Part.3.of.6.720p.BluRay.x264-GRP");
+        assert_eq!(parsed.format(NameStyle::Scene), "Planet.Earth.Part.3.of.6.720p.BluRay.x264-GRP");
+        assert_eq!(parsed.format(NameStyle::Bracket), "Planet Earth - Part 3 of 6 [Bluray-720p][x264]-GRP");
+
+        let parsed = parser.parse("[Group] Frieren - OVA 02 [1080p]");
+        assert_eq!(parsed.format(NameStyle::Scene), "Frieren.OVA.02.1080p-Group");
+        assert_eq!(parsed.format(NameStyle::Bracket), "Frieren - OVA 02 [1080p]-Group");
+
+        let parsed = parser.parse("Show - 005 - Title [HDTV-720p]-GRP");
+        assert_eq!(parsed.format(NameStyle::Scene), "Show.E005.Title.720p.HDTV-GRP");
+        assert_eq!(parsed.format(NameStyle::Bracket), "Show - 005 - Title [HDTV-720p]-GRP");
+    }
+
+    #[test]
+    fn test_round_trip_tv() {
+        let parser = ReleaseParser::new("tv");
+        let releases = [
+            "Breaking.Bad.S05E14.Ozymandias.1080p.AMZN.WEB-DL.DDP5.1.H.264-GROUP",
+            "Show.S01E02.1080p.WEB-DL.DTS-HD.MA.5.1.H.265-GRP",
+            "Show.S01E02.German.1080p.BluRay.x264-GRP",
+            "Arrow (2012) - S05E04 - Penance [Bluray-1080p Remux][DTS-HD MA 5.1][AVC]-EPSiLON",
+            "Show (2012) - S05E04-E05 - Penance [WEBDL-1080p Proper][EAC3 5.1][DV HDR10][h264]-GRP",
+            "Seinfeld (1989) {tvdb-79169} - S01E01 - The Seinfeld Chronicles [HDTV-720p][AC3 2.0][x264]-GRP",
+            "Show.S02.1080p.BluRay.x264-GRP",
+            "Show.S01-S05.COMPLETE.1080p.BluRay.x264-GRP",
+            "Show.S00E05.720p.HDTV.x264-GRP",
+            "Show.S01E05.NCOP.1080p.WEB.x264-GRP",
+            "Planet.Earth.Part.3.of.6.720p.BluRay.x264-GRP",
+            "[Group] Frieren - OVA 02 [1080p]",
+            "[Group] Frieren - NCED2 [1080p]",
+            "[Group] Frieren Vol.3 [1080p]",
+            "Show.Special.720p.HDTV.x264-GRP",
+            "Show.E005.720p.HDTV.x264-GRP",
+            "Show.E01E02.720p.HDTV.x264-GRP",
+            "Show - 005 - Title [HDTV-720p]-GRP",
+        ];
+        for release in releases {
+            assert_round_trip(&parser, release, NameStyle::Scene, FIELDS);
+            assert_round_trip(&parser, release, NameStyle::Bracket, &[FIELDS, &["tvdb_id"]].concat());
+        }
+
+        let release = "The Series Title! (2010) - 2013-10-30 - Episode Title 1 [AMZN WEBDL-1080p Proper][DV HDR10][DTS 5.1][x264]-RlsGrp";
+        assert_round_trip(&parser, release, NameStyle::Bracket, &[FIELDS, &["date"]].concat());
+    }
+
+    #[test]
+    fn test_round_trip_movie() {
+        let parser = ReleaseParser::new("movie");
+        let releases = [
+            "The.Matrix.1999.1080p.BluRay.x264-GROUP",
+            "Movie.2019.FRENCH.1080p.BluRay.DTS.5.1.x264-GRP",
+            "Some.Movie.2019.720p.NF.WEBRip.DDP5.1.x264-GRP",
+            "Vanilla Sky (2001) {tmdb-1903} {edition-Directors Cut} [Remux-2160p Proper][DV HDR10][DTS-HD MA 5.1][HEVC]-FraMeSToR",
+            "Movie (2010) [imdbid-tt0066921] [WEBRip-720p][AAC 2.0][x264]-GRP",
+            "Vanilla.Sky.2001.Part.2.1080p.BluRay.x264-GRP",
+        ];
+        for release in releases {
+            assert_round_trip(&parser, release, NameStyle::Scene, FIELDS);
+            assert_round_trip(&parser, release, NameStyle::Bracket, &[FIELDS, &["tmdb_id", "imdb_id", "edition"]].concat());
+        }
+    }
+}
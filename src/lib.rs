@@ -1,7 +1,45 @@
+pub mod classify;
+pub mod coverage;
+pub mod date;
+pub mod dedupe;
+pub mod error;
+pub mod explain;
+pub mod filter;
+pub mod format;
+pub mod media;
+pub mod naming;
+pub mod organize;
 pub mod parser;
+pub mod plan;
+pub mod quality;
+pub mod report;
+mod rules;
+pub mod scan;
+pub mod scoring;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod tokenizer;
 pub mod types;
 
+pub use classify::Classification;
+pub use coverage::{coverage, SeasonCoverage, SeriesCoverage};
+pub use date::{AirDate, InvalidDate};
+pub use dedupe::{Criterion, Decision, DuplicateGroup, QualityRanking, Verdict};
+pub use error::ParseError;
+pub use explain::{Explanation, Segment};
+pub use filter::{Filter, FilterError};
+pub use format::NameStyle;
+pub use media::{AudioCodec, HdrFormat, Resolution, Source, VideoCodec};
+pub use naming::{LibraryNaming, NamedPath, NamingScheme, NamingTemplate, TemplateError};
+pub use organize::{EditionStyle, IdStyle, MediaServer, ServerLayout};
 pub use parser::ReleaseParser;
+pub use plan::{Journal, RenamePlan, Strategy};
+pub use quality::{Quality, QualityModel, QualitySource, Revision};
+pub use report::{FieldReport, ParseReport};
+pub use scan::{Inventory, MediaFile, Movie, Season, Series, Sidecar, SidecarKind};
+pub use scoring::{Condition, MatchedRule, Score, ScoringError, ScoringRule, ScoringRules};
+#[cfg(feature = "server")]
+pub use server::{ParseService, ServerConfig};
 pub use types::*;
 
 
@@ -0,0 +1,250 @@
+//! Typed views of the technical fields of a [`ParsedRelease`](crate::ParsedRelease).
+//!
+//! The parser keeps `source`, `format`, `resolution`, `hdr` and `audio` as the
+//! strings found in the release name. The enums here map the many spellings
+//! of each value ("WEBDL", "WEB-DL", "Web Download") to one canonical name.
+//! Values that are not known keep their original text in `Other`.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Declare a string-backed enum with a canonical name and aliases per variant,
+/// plus an `Other(String)` variant for everything else.
+macro_rules! string_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $( $(#[$variant_meta:meta])* $variant:ident => $canonical:literal $(| $alias:literal)* ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $( $(#[$variant_meta])* $variant, )*
+            /// A value without a canonical name, kept as found
+            Other(String),
+        }
+
+        impl $name {
+            /// Canonical name, or the original text for `Other`.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $( Self::$variant => $canonical, )*
+                    Self::Other(value) => value,
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            /// Case, dots, dashes, underscores and spaces are ignored when
+            /// matching names; unknown values become `Other`.
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                let key = normalize(value);
+                $(
+                    if [$canonical $(, $alias)*].iter().any(|name| normalize(name) == key) {
+                        return Ok(Self::$variant);
+                    }
+                )*
+                Ok(Self::Other(value.trim().to_string()))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                Ok(value.parse().unwrap_or_else(|never| match never {}))
+            }
+        }
+    };
+}
+
+fn normalize(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, '.' | '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+string_enum! {
+    /// Where the video was captured or ripped from.
+    pub enum Source {
+        Cam => "CAM" | "HDCAM" | "CAMRip",
+        Telesync => "TELESYNC" | "TS" | "HDTS",
+        Telecine => "TELECINE" | "TC",
+        Workprint => "Workprint" | "WP",
+        Screener => "SCR" | "DVDScr" | "Screener",
+        R5 => "R5",
+        Ppv => "PPVRip" | "PPV",
+        Vhs => "VHSRip" | "VHS",
+        Hdtv => "HDTV",
+        Pdtv => "PDTV",
+        Dsr => "DSR" | "SATRip" | "DTHRip",
+        TvRip => "TVRip",
+        Dvd => "DVD" | "DVDR" | "DVD5" | "DVD9",
+        DvdRip => "DVDRip",
+        WebDl => "WEB-DL" | "Web Download" | "AMZN WEBDL" | "MA WEBDL",
+        WebRip => "WEBRip" | "Web Capture",
+        Web => "WEB",
+        Vod => "VODRip" | "VOD",
+        HdRip => "HDRip",
+        BdRip => "BDRip" | "BRRip",
+        BluRay => "BluRay" | "BD" | "Blu-ray",
+        Remux => "Remux" | "BDRemux",
+    }
+}
+
+string_enum! {
+    /// Video codec, whatever encoder produced it.
+    pub enum VideoCodec {
+        H264 => "H.264" | "x264" | "AVC",
+        H265 => "H.265" | "x265" | "HEVC",
+        Av1 => "AV1",
+        Vp9 => "VP9",
+        Vc1 => "VC-1",
+        Xvid => "XviD",
+        Divx => "DivX",
+        Mpeg2 => "MPEG2",
+        Mpeg4 => "MPEG4",
+    }
+}
+
+string_enum! {
+    /// Vertical video resolution.
+    pub enum Resolution {
+        P360 => "360p",
+        P480 => "480p" | "480i" | "SD",
+        P540 => "540p",
+        P576 => "576p" | "576i",
+        P720 => "720p",
+        P1080 => "1080p" | "1080i",
+        P1440 => "1440p",
+        P2160 => "2160p" | "4K" | "UHD",
+        P4320 => "4320p" | "8K",
+    }
+}
+
+string_enum! {
+    /// High dynamic range format.
+    pub enum HdrFormat {
+        Hdr => "HDR",
+        Hdr10 => "HDR10",
+        Hdr10Plus => "HDR10+" | "HDR10Plus",
+        DolbyVision => "DV" | "DoVi" | "Dolby Vision",
+        DolbyVisionHdr10 => "DV HDR10",
+        DolbyVisionHdr10Plus => "DV HDR10+" | "DV HDR10Plus",
+        Hlg => "HLG",
+    }
+}
+
+string_enum! {
+    /// Audio codec, without channel layout or Atmos.
+    pub enum AudioCodec {
+        Aac => "AAC",
+        Ac3 => "AC3" | "DD" | "Dolby Digital",
+        Eac3 => "EAC3" | "DDP" | "DD+" | "Dolby Digital Plus",
+        TrueHd => "TrueHD",
+        Dts => "DTS",
+        DtsHd => "DTS-HD" | "DTS-HD HRA",
+        DtsHdMa => "DTS-HD MA" | "DTS-HDMA",
+        DtsX => "DTS:X" | "DTS-X",
+        Flac => "FLAC",
+        Mp3 => "MP3",
+        Opus => "Opus",
+        Pcm => "PCM" | "LPCM",
+    }
+}
+
+impl Resolution {
+    /// Number of vertical lines, `None` for `Other`.
+    pub fn height(&self) -> Option<u32> {
+        match self {
+            Self::P360 => Some(360),
+            Self::P480 => Some(480),
+            Self::P540 => Some(540),
+            Self::P576 => Some(576),
+            Self::P720 => Some(720),
+            Self::P1080 => Some(1080),
+            Self::P1440 => Some(1440),
+            Self::P2160 => Some(2160),
+            Self::P4320 => Some(4320),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl AudioCodec {
+    /// Codec of an audio field like `"EAC3 Atmos 5.1"` or `"DDP5.1"`, ignoring
+    /// the channel layout and Atmos.
+    pub fn from_audio(audio: &str) -> Option<Self> {
+        let re = crate::rules::regex!(r"(?i)(?:\s*\bAtmos\b)?(?:\s*\d\.\d)?$");
+        let codec = re.replace(audio.trim(), "");
+        let codec = codec.trim();
+        (!codec.is_empty()).then(|| codec.parse().unwrap_or_else(|never| match never {}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_spellings() {
+        assert_eq!("WEBDL".parse::<Source>().unwrap(), Source::WebDl);
+        assert_eq!("web-dl".parse::<Source>().unwrap(), Source::WebDl);
+        assert_eq!("Bluray".parse::<Source>().unwrap(), Source::BluRay);
+        assert_eq!("DvDrip".parse::<Source>().unwrap(), Source::DvdRip);
+        assert_eq!("iNTERNAL".parse::<Source>().unwrap(), Source::Other("iNTERNAL".to_string()));
+        assert_eq!(Source::WebDl.to_string(), "WEB-DL");
+    }
+
+    #[test]
+    fn test_video_codec_spellings() {
+        for codec in ["h265", "x265", "HEVC", "H.265"] {
+            assert_eq!(codec.parse::<VideoCodec>().unwrap(), VideoCodec::H265);
+        }
+        assert_eq!("AVC".parse::<VideoCodec>().unwrap(), VideoCodec::H264);
+        assert_eq!(VideoCodec::H264.to_string(), "H.264");
+    }
+
+    #[test]
+    fn test_resolution() {
+        assert_eq!("1080i".parse::<Resolution>().unwrap(), Resolution::P1080);
+        assert_eq!("4K".parse::<Resolution>().unwrap(), Resolution::P2160);
+        assert_eq!(Resolution::P2160.height(), Some(2160));
+        assert_eq!(Resolution::P720.to_string(), "720p");
+    }
+
+    #[test]
+    fn test_audio_codec_from_audio() {
+        assert_eq!(AudioCodec::from_audio("EAC3 Atmos 5.1"), Some(AudioCodec::Eac3));
+        assert_eq!(AudioCodec::from_audio("DDP5.1"), Some(AudioCodec::Eac3));
+        assert_eq!(AudioCodec::from_audio("DTS-HD MA 5.1"), Some(AudioCodec::DtsHdMa));
+        assert_eq!(AudioCodec::from_audio("AAC"), Some(AudioCodec::Aac));
+        assert_eq!(AudioCodec::from_audio("TrueHD Atmos"), Some(AudioCodec::TrueHd));
+        assert_eq!(AudioCodec::from_audio(""), None);
+    }
+
+    #[test]
+    fn test_serde_uses_canonical_names() {
+        assert_eq!(serde_json::to_string(&HdrFormat::Hdr10Plus).unwrap(), "\"HDR10+\"");
+        assert_eq!(serde_json::from_str::<HdrFormat>("\"HDR10Plus\"").unwrap(), HdrFormat::Hdr10Plus);
+        assert_eq!(serde_json::from_str::<Source>("\"SomethingNew\"").unwrap(), Source::Other("SomethingNew".to_string()));
+    }
+}
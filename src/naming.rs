@@ -0,0 +1,613 @@
+//! Folder and file names from Sonarr/Radarr-style naming templates.
+//!
+//! A [`NamingTemplate`] is text with tokens in braces, like
+//! `{Series Title} - S{season:00}E{episode:00} - {Episode Title}`:
+//!
+//! - `{season:00}` pads a number with zeros to the given width.
+//! - `{[Quality Full]}` and `{-Release Group}` are conditional: the brackets,
+//!   dashes, dots, spaces and parentheses around the token name are only
+//!   written when the token has a value.
+//! - `{tmdb-{TmdbId}}` writes `{tmdb-1903}`, or nothing without an ID.
+//! - The separator inside the name sets the one between words of the value:
+//!   `{Series.Title}` gives `Breaking.Bad`. An all lowercase or all uppercase
+//!   name does the same for the letter case of the value.
+//!
+//! Values are made safe for file names (`:` becomes `-`, `/` becomes `+`,
+//! `?` and `*` are removed), and what is left of empty sections, like `()`
+//! or a doubled ` - `, is cleaned up.
+
+use crate::format::{bracket_source, quality_title};
+use crate::rules::regex;
+use crate::types::{ParsedRelease, PathInfo};
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Why a naming template could not be compiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A token name that is not supported, as written in the template
+    UnknownToken(String),
+    /// A `{` without its `}`; holds the byte offset of the `{`
+    UnclosedBrace(usize),
+    /// Padding other than zeros (`{season:00}`), or on a token that is not a number
+    InvalidPadding(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownToken(token) => write!(f, "unknown naming token {:?}", token),
+            Self::UnclosedBrace(offset) => write!(f, "unclosed brace at byte {}", offset),
+            Self::InvalidPadding(token) => write!(f, "invalid padding in {:?}", token),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+/// Values a token can stand for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Title,
+    TitleYear,
+    CleanTitle,
+    OriginalTitle,
+    Year,
+    Season,
+    Episode,
+    EpisodeTitle,
+    EpisodeCleanTitle,
+    AirDate,
+    QualityFull,
+    QualityTitle,
+    VideoCodec,
+    AudioCodec,
+    AudioChannels,
+    VideoDynamicRange,
+    VideoDynamicRangeType,
+    ReleaseGroup,
+    TmdbId,
+    TvdbId,
+    ImdbId,
+    Edition,
+}
+
+impl Token {
+    /// Token for a name with its words lowercased and separated by spaces.
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "series title" | "movie title" => Self::Title,
+            "series titleyear" | "movie titleyear" => Self::TitleYear,
+            "series cleantitle" | "movie cleantitle" => Self::CleanTitle,
+            "original title" => Self::OriginalTitle,
+            "release year" | "year" => Self::Year,
+            "season" => Self::Season,
+            "episode" => Self::Episode,
+            "episode title" => Self::EpisodeTitle,
+            "episode cleantitle" => Self::EpisodeCleanTitle,
+            "air date" => Self::AirDate,
+            "quality full" => Self::QualityFull,
+            "quality title" => Self::QualityTitle,
+            "mediainfo videocodec" => Self::VideoCodec,
+            "mediainfo audiocodec" => Self::AudioCodec,
+            "mediainfo audiochannels" => Self::AudioChannels,
+            "mediainfo videodynamicrange" => Self::VideoDynamicRange,
+            "mediainfo videodynamicrangetype" => Self::VideoDynamicRangeType,
+            "release group" => Self::ReleaseGroup,
+            "tmdbid" => Self::TmdbId,
+            "tvdbid" => Self::TvdbId,
+            "imdbid" => Self::ImdbId,
+            "edition tags" => Self::Edition,
+            _ => return None,
+        })
+    }
+
+    fn is_number(self) -> bool {
+        matches!(self, Self::Season | Self::Episode | Self::Year)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Keep,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Token {
+        token: Token,
+        /// Written before the value, only when there is one
+        prefix: String,
+        suffix: String,
+        /// Separator between the words of the value
+        separator: char,
+        case: Case,
+        /// Width numbers are padded to with zeros
+        width: usize,
+    },
+}
+
+/// What tokens are filled from: the series or movie, the file and its season.
+struct Subject<'a> {
+    show: &'a ParsedRelease,
+    file: &'a ParsedRelease,
+    season: Option<u128>,
+}
+
+impl Subject<'_> {
+    fn value(&self, token: Token, width: usize) -> String {
+        let (show, file) = (self.show, self.file);
+        match token {
+            Token::Title => show.title.clone(),
+            Token::TitleYear => match show.year.or(file.year) {
+                Some(year) => format!("{} ({})", show.title, year),
+                None => show.title.clone(),
+            },
+            Token::CleanTitle => clean_title(&show.title),
+            Token::OriginalTitle => file.release.clone(),
+            Token::Year => show.year.or(file.year).map(|year| format!("{:0width$}", year)).unwrap_or_default(),
+            Token::Season => season_range(self.season, file, width),
+            Token::Episode => episode_range(file, width),
+            Token::EpisodeTitle => file.episode_title.clone(),
+            Token::EpisodeCleanTitle => clean_title(&file.episode_title),
+            Token::AirDate => file.date.map(|date| date.to_string()).unwrap_or_default(),
+            Token::QualityFull => {
+                let mut quality = quality_title(bracket_source(&file.source), &file.resolution);
+                for flag in &file.flags {
+                    let revision = match flag.to_uppercase().as_str() {
+                        "PROPER" => "Proper",
+                        "REPACK" => "Repack",
+                        _ => continue,
+                    };
+                    quality.push(' ');
+                    quality.push_str(revision);
+                }
+                quality
+            }
+            Token::QualityTitle => quality_title(bracket_source(&file.source), &file.resolution),
+            Token::VideoCodec => file.format.clone(),
+            Token::AudioCodec => split_channels(&file.audio).0.to_string(),
+            Token::AudioChannels => split_channels(&file.audio).1.to_string(),
+            Token::VideoDynamicRange => if file.hdr.is_empty() { "" } else { "HDR" }.to_string(),
+            Token::VideoDynamicRangeType => file.hdr.clone(),
+            Token::ReleaseGroup => file.group.clone(),
+            Token::TmdbId => show.tmdb_id.clone().or_else(|| file.tmdb_id.clone()).unwrap_or_default(),
+            Token::TvdbId => show.tvdb_id.clone().or_else(|| file.tvdb_id.clone()).unwrap_or_default(),
+            Token::ImdbId => show.imdb_id.clone().or_else(|| file.imdb_id.clone()).unwrap_or_default(),
+            Token::Edition => show.edition.clone().or_else(|| file.edition.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+/// A compiled naming template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamingTemplate {
+    pattern: String,
+    parts: Vec<Part>,
+}
+
+impl NamingTemplate {
+    /// Compile a template like `{Series Title} - S{season:00}E{episode:00}`.
+    pub fn parse(pattern: &str) -> Result<Self, TemplateError> {
+        let mut parts: Vec<Part> = Vec::new();
+        let mut literal = String::new();
+        let mut rest = pattern;
+        while let Some(open) = rest.find('{') {
+            literal.push_str(&rest[..open]);
+            let offset = pattern.len() - rest.len() + open;
+            let end = closing_brace(&rest[open..]).ok_or(TemplateError::UnclosedBrace(offset))?;
+            if !literal.is_empty() {
+                parts.push(Part::Literal(std::mem::take(&mut literal)));
+            }
+            parts.push(parse_token(&rest[open + 1..open + end])?);
+            rest = &rest[open + end + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            parts,
+        })
+    }
+
+    /// The template as it was written.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Render the template with the fields of a single release.
+    pub fn render(&self, parsed: &ParsedRelease) -> String {
+        self.render_subject(&Subject {
+            show: parsed,
+            file: parsed,
+            season: parsed.season,
+        })
+    }
+
+    /// Render the template for a file in a library. Series and movie tokens
+    /// (title, year, IDs) come from the parsed directory when there is one,
+    /// the season from the season directory when the file name lacks it.
+    pub fn render_path(&self, path_info: &PathInfo) -> String {
+        self.render_subject(&Subject {
+            show: path_info.directory.as_ref().unwrap_or(&path_info.file),
+            file: &path_info.file,
+            season: path_info.file.season.or(path_info.season),
+        })
+    }
+
+    fn render_subject(&self, subject: &Subject) -> String {
+        let mut name = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => name.push_str(text),
+                Part::Token { token, prefix, suffix, separator, case, width } => {
+                    let mut value = safe_value(&subject.value(*token, *width));
+                    if *separator != ' ' {
+                        value = value.replace(' ', &separator.to_string());
+                        if *token == Token::AirDate {
+                            value = value.replace('-', &separator.to_string());
+                        }
+                    }
+                    match case {
+                        _ if token.is_number() => {}
+                        Case::Keep => {}
+                        Case::Lower => value = value.to_lowercase(),
+                        Case::Upper => value = value.to_uppercase(),
+                    }
+                    if !value.is_empty() {
+                        name.push_str(prefix);
+                        name.push_str(&value);
+                        name.push_str(suffix);
+                    }
+                }
+            }
+        }
+        tidy(&name)
+    }
+}
+
+impl FromStr for NamingTemplate {
+    type Err = TemplateError;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Self::parse(pattern)
+    }
+}
+
+impl fmt::Display for NamingTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.pattern)
+    }
+}
+
+/// Templates for every level of a TV or movie library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamingScheme {
+    pub series_folder: NamingTemplate,
+    pub season_folder: NamingTemplate,
+    pub episode_file: NamingTemplate,
+    /// Used instead of `episode_file` for episodes known only by their air date
+    pub daily_episode_file: NamingTemplate,
+    /// Used instead of `episode_file` for season packs and other releases without an episode number
+    pub season_pack_file: NamingTemplate,
+    pub movie_folder: NamingTemplate,
+    pub movie_file: NamingTemplate,
+}
+
+/// Names of the folders and the file of a release, without extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedPath {
+    /// Series or movie folder
+    pub folder: String,
+    /// Season folder, `None` for movies
+    pub season_folder: Option<String>,
+    pub file: String,
+}
+
+impl NamedPath {
+    /// The relative path of the file, with `extension` (without the dot) added.
+    pub fn to_path(&self, extension: &str) -> PathBuf {
+        let mut path = PathBuf::from(&self.folder);
+        if let Some(season_folder) = &self.season_folder {
+            path.push(season_folder);
+        }
+        if extension.is_empty() {
+            path.push(&self.file);
+        } else {
+            path.push(format!("{}.{}", self.file, extension));
+        }
+        path
+    }
+}
+
+/// Names the files of a library: implemented by [`NamingScheme`] and
+/// [`ServerLayout`](crate::ServerLayout).
+pub trait LibraryNaming {
+    /// Folder and file names for a file in a library.
+    fn name_path(&self, path_info: &PathInfo) -> NamedPath;
+}
+
+impl LibraryNaming for NamingScheme {
+    fn name_path(&self, path_info: &PathInfo) -> NamedPath {
+        NamingScheme::name_path(self, path_info)
+    }
+}
+
+impl Default for NamingScheme {
+    /// The recommended formats of Sonarr and Radarr.
+    fn default() -> Self {
+        let template = |pattern: &str| NamingTemplate::parse(pattern).expect("default naming template");
+        let quality = "{[Quality Full]}{[MediaInfo VideoDynamicRangeType]}{[MediaInfo AudioCodec}{ MediaInfo AudioChannels]}{[MediaInfo VideoCodec]}{-Release Group}";
+        Self {
+            series_folder: template("{Series TitleYear}"),
+            season_folder: template("Season {season:00}"),
+            episode_file: template(&format!("{{Series TitleYear}} - S{{season:00}}E{{episode:00}} - {{Episode Title}} {}", quality)),
+            daily_episode_file: template(&format!("{{Series TitleYear}} - {{Air-Date}} - {{Episode Title}} {}", quality)),
+            season_pack_file: template(&format!("{{Series TitleYear}} - S{{season:00}} {}", quality)),
+            movie_folder: template("{Movie Title} ({Release Year})"),
+            movie_file: template(&format!("{{Movie Title}} ({{Release Year}}) {{edition-{{Edition Tags}}}} {}", quality)),
+        }
+    }
+}
+
+impl NamingScheme {
+    /// Folder and file names for a single release.
+    pub fn name(&self, parsed: &ParsedRelease) -> NamedPath {
+        self.name_subject(&Subject {
+            show: parsed,
+            file: parsed,
+            season: parsed.season,
+        })
+    }
+
+    /// Folder and file names for a file in a library, see [`NamingTemplate::render_path`].
+    pub fn name_path(&self, path_info: &PathInfo) -> NamedPath {
+        self.name_subject(&Subject {
+            show: path_info.directory.as_ref().unwrap_or(&path_info.file),
+            file: &path_info.file,
+            season: path_info.file.season.or(path_info.season),
+        })
+    }
+
+    fn name_subject(&self, subject: &Subject) -> NamedPath {
+        if subject.file.release_type == "movie" {
+            return NamedPath {
+                folder: self.movie_folder.render_subject(subject),
+                season_folder: None,
+                file: self.movie_file.render_subject(subject),
+            };
+        }
+        let folder = self.series_folder.render_subject(subject);
+        let daily = subject.season.is_none() && subject.file.date.is_some();
+        let pack = subject.file.pack.is_some() || (subject.file.episode.is_none() && subject.file.episodes.is_empty());
+        let file = if daily {
+            self.daily_episode_file.render_subject(subject)
+        } else if pack && subject.season.is_none() && subject.file.seasons.is_empty() {
+            // A complete series has no season to number it by
+            folder.clone()
+        } else if pack {
+            self.season_pack_file.render_subject(subject)
+        } else {
+            self.episode_file.render_subject(subject)
+        };
+        NamedPath {
+            folder,
+            season_folder: subject.season.map(|_| self.season_folder.render_subject(subject)),
+            file,
+        }
+    }
+}
+
+/// Length of `text` up to its first `{` and the matching `}`.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Compile the text between the braces of a token.
+fn parse_token(text: &str) -> Result<Part, TemplateError> {
+    // `{tmdb-{TmdbId}}`: the outer braces and text around the inner token
+    // are written with the value
+    if let (Some(open), Some(close)) = (text.find('{'), text.rfind('}')) {
+        let Part::Token { token, prefix, suffix, separator, case, width } = parse_token(&text[open + 1..close])? else {
+            unreachable!("parse_token only returns tokens");
+        };
+        return Ok(Part::Token {
+            token,
+            prefix: format!("{{{}{}", &text[..open], prefix),
+            suffix: format!("{}{}}}", suffix, &text[close + 1..]),
+            separator,
+            case,
+            width,
+        });
+    }
+
+    let re = regex!(r"^(?P<prefix>[-\s._\[(]*)(?P<name>[A-Za-z0-9]+(?:[-\s._]+[A-Za-z0-9]+)*?)(?::(?P<padding>[^\])\s]*))?(?P<suffix>[-\s._)\]]*)$");
+    let captures = re.captures(text).ok_or_else(|| TemplateError::UnknownToken(text.to_string()))?;
+    let name = &captures["name"];
+    let words: Vec<&str> = name.split(|c: char| "- ._".contains(c)).filter(|word| !word.is_empty()).collect();
+    let token = Token::from_name(&words.join(" ").to_lowercase()).ok_or_else(|| TemplateError::UnknownToken(name.to_string()))?;
+
+    let width = match captures.name("padding") {
+        None => 0,
+        Some(padding) if token.is_number() && !padding.as_str().is_empty() && padding.as_str().chars().all(|c| c == '0') => padding.len(),
+        Some(_) => return Err(TemplateError::InvalidPadding(text.to_string())),
+    };
+    let separator = name.chars().find(|c| "- ._".contains(*c)).unwrap_or(' ');
+    let case = if !name.chars().any(|c| c.is_ascii_uppercase()) {
+        Case::Lower
+    } else if !name.chars().any(|c| c.is_ascii_lowercase()) {
+        Case::Upper
+    } else {
+        Case::Keep
+    };
+
+    Ok(Part::Token {
+        token,
+        prefix: captures["prefix"].to_string(),
+        suffix: captures["suffix"].to_string(),
+        separator,
+        case,
+        width,
+    })
+}
+
+/// Season number for `S{season:00}`, or `01-S05` for a pack of several
+/// seasons, so the template reads `S01-S05`.
+fn season_range(season: Option<u128>, parsed: &ParsedRelease, width: usize) -> String {
+    if let Some(season) = season {
+        return format!("{:0width$}", season);
+    }
+    match (parsed.seasons.first(), parsed.seasons.last()) {
+        (Some(first), Some(last)) if first != last => format!("{:0width$}-S{:0width$}", first, last),
+        (Some(first), _) => format!("{:0width$}", first),
+        _ => String::new(),
+    }
+}
+
+/// Episode numbers for `E{episode:00}`: `01`, `01-E03` for a run of
+/// episodes and `01E03` otherwise, so the template reads `S01E01-E03`.
+fn episode_range(parsed: &ParsedRelease, width: usize) -> String {
+    let episodes: Vec<u128> = if parsed.episodes.is_empty() { parsed.episode.into_iter().collect() } else { parsed.episodes.clone() };
+    let (Some(first), Some(last)) = (episodes.first(), episodes.last()) else {
+        return String::new();
+    };
+    if episodes.len() > 1 && episodes.windows(2).all(|pair| pair[1] == pair[0] + 1) {
+        return format!("{:0width$}-E{:0width$}", first, last);
+    }
+    episodes.iter().map(|episode| format!("{:0width$}", episode)).collect::<Vec<_>>().join("E")
+}
+
+/// `("EAC3 Atmos", "5.1")` for `"EAC3 Atmos 5.1"`.
+fn split_channels(audio: &str) -> (&str, &str) {
+    match audio.trim().rsplit_once(' ') {
+        Some((codec, channels)) if regex!(r"^\d\.\d$").is_match(channels) => (codec, channels),
+        _ => (audio.trim(), ""),
+    }
+}
+
+/// Title without punctuation, the way Sonarr writes `CleanTitle`.
+fn clean_title(title: &str) -> String {
+    let title = title.replace('&', " and ");
+    let title: String = title.chars().filter(|c| c.is_alphanumeric() || c.is_whitespace()).collect();
+    title.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// A token value with the characters file systems reject replaced.
+pub(crate) fn safe_value(value: &str) -> String {
+    let value = value.replace(": ", " - ").replace(':', "-").replace(['/', '\\'], "+").replace('|', "-");
+    let value: String = value.chars().filter(|c| !"?*<>\"".contains(*c) && !c.is_control()).collect();
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Remove what empty tokens leave behind: `()`, `[]`, doubled or dangling
+/// ` - ` and runs of spaces. Trailing dots are dropped as Windows can't
+/// store them.
+fn tidy(name: &str) -> String {
+    let name = regex!(r"\(\s*\)|\[\s*\]|\{\s*\}").replace_all(name, "");
+    let name = regex!(r"\s+").replace_all(&name, " ");
+    let name = regex!(r"(?:\s+-)+\s+").replace_all(&name, " - ");
+    let name = regex!(r"^(?:\s|-\s)+|(?:\s+-)+$").replace_all(&name, "");
+    name.trim().trim_end_matches('.').trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReleaseParser;
+
+    #[test]
+    fn test_render_tokens() {
+        let parsed = ReleaseParser::new("tv").parse("Breaking.Bad.2008.S05E14.Ozymandias.PROPER.1080p.AMZN.WEB-DL.DDP5.1.H.264-GROUP");
+        let template = NamingTemplate::parse("{Series Title} - S{season:00}E{episode:00} - {Episode Title} {[Quality Full]}{[MediaInfo AudioCodec}{ MediaInfo AudioChannels]}{[MediaInfo VideoCodec]}{-Release Group}").unwrap();
+        assert_eq!(template.render(&parsed), "Breaking Bad - S05E14 - Ozymandias [WEBDL-1080p Proper][DDP 5.1][H.264]-GROUP");
+
+        let template: NamingTemplate = "{Series.Title}.{season:0}x{episode:000}.{release group}".parse().unwrap();
+        assert_eq!(template.render(&parsed), "Breaking.Bad.5x014.group");
+    }
+
+    #[test]
+    fn test_conditional_sections() {
+        let parsed = ReleaseParser::new("movie").parse("Vanilla Sky (2001) {tmdb-1903} [Bluray-1080p][x264]");
+        let template = NamingTemplate::parse("{Movie Title} ({Release Year}) {tmdb-{TmdbId}} {imdb-{ImdbId}} {edition-{Edition Tags}} {[Quality Full]}{-Release Group}").unwrap();
+        assert_eq!(template.render(&parsed), "Vanilla Sky (2001) {tmdb-1903} [Bluray-1080p]");
+
+        let parsed = ReleaseParser::new("movie").parse("Movie");
+        assert_eq!(template.render(&parsed), "Movie");
+    }
+
+    #[test]
+    fn test_safe_values() {
+        let parsed = ReleaseParser::new("tv").parse("Star Trek: Picard - S01E02 - Maps/Legends? [WEBDL-1080p]-GRP");
+        let template = NamingTemplate::parse("{Series Title} - {Episode Title} - {Series CleanTitle}").unwrap();
+        assert_eq!(template.render(&parsed), "Star Trek - Picard - Maps+Legends - Star Trek Picard");
+    }
+
+    #[test]
+    fn test_template_errors() {
+        assert_eq!(NamingTemplate::parse("{Series Title"), Err(TemplateError::UnclosedBrace(0)));
+        assert_eq!(NamingTemplate::parse("x {Series Title"), Err(TemplateError::UnclosedBrace(2)));
+        assert_eq!(NamingTemplate::parse("{Series Name}"), Err(TemplateError::UnknownToken("Series Name".to_string())));
+        assert_eq!(NamingTemplate::parse("{Series Title:00}"), Err(TemplateError::InvalidPadding("Series Title:00".to_string())));
+        assert_eq!(NamingTemplate::parse("{season:0x}"), Err(TemplateError::InvalidPadding("season:0x".to_string())));
+    }
+
+    #[test]
+    fn test_scheme_path() {
+        let parser = ReleaseParser::new("tv");
+        let path_info = parser
+            .parse_path("/tv/Seinfeld (1989) {tvdb-79169}/Season 01/Seinfeld.S01E01.The.Seinfeld.Chronicles.720p.HDTV.x264-GRP.mkv")
+            .unwrap();
+        let named = NamingScheme::default().name_path(&path_info);
+        assert_eq!(named.folder, "Seinfeld (1989)");
+        assert_eq!(named.season_folder.as_deref(), Some("Season 01"));
+        assert_eq!(named.to_path("mkv"), PathBuf::from("Seinfeld (1989)/Season 01").join(format!("{}.mkv", named.file)));
+        assert_eq!(named.file, "Seinfeld (1989) - S01E01 - The Seinfeld Chronicles [HDTV-720p][x264]-GRP");
+
+        let parsed = ReleaseParser::new("movie").parse("The.Matrix.1999.1080p.BluRay.DTS-HD.MA.5.1.x264-GROUP");
+        let named = NamingScheme::default().name(&parsed);
+        assert_eq!(named.folder, "The Matrix (1999)");
+        assert_eq!(named.season_folder, None);
+        assert_eq!(named.file, "The Matrix (1999) [Bluray-1080p][DTS-HD MA 5.1][x264]-GROUP");
+    }
+
+    #[test]
+    fn test_multi_episode() {
+        let mut parsed = ReleaseParser::new("tv").parse("Show.S01E01-E03.720p.HDTV.x264-GRP");
+        let template = NamingTemplate::parse("S{season:00}E{episode:00}").unwrap();
+        assert_eq!(template.render(&parsed), "S01E01-E03");
+        parsed.episodes = vec![1, 3];
+        assert_eq!(template.render(&parsed), "S01E01E03");
+    }
+
+    #[test]
+    fn test_season_packs() {
+        let parser = ReleaseParser::new("tv");
+        let scheme = NamingScheme::default();
+        let named = scheme.name(&parser.parse("Show.S02.1080p.BluRay.x264-GRP"));
+        assert_eq!(named.season_folder.as_deref(), Some("Season 02"));
+        assert_eq!(named.file, "Show - S02 [Bluray-1080p][x264]-GRP");
+        assert_eq!(scheme.name(&parser.parse("Show.S01-S05.COMPLETE.1080p.BluRay.x264-GRP")).file, "Show - S01-S05 [Bluray-1080p][x264]-GRP");
+        assert_eq!(scheme.name(&parser.parse("Show.Complete.Series.1080p.BluRay.x264-GRP")).file, "Show");
+    }
+}
@@ -0,0 +1,281 @@
+//! Library paths in the layouts media servers expect.
+//!
+//! Plex, Jellyfin, Emby and Kodi all read `Title (Year)/Season 01/Title (Year) - S01E01.mkv`,
+//! but differ in how database IDs and movie editions are tagged, what the
+//! season 0 folder is called and how the parts of a split movie are numbered.
+//! A [`ServerLayout`] holds those choices; [`ServerLayout::new`] gives the
+//! defaults of each [`MediaServer`].
+
+use crate::format::NameStyle;
+use crate::naming::{safe_value, LibraryNaming, NamedPath};
+use crate::types::{ParsedRelease, PathInfo};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Media servers with a known library layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaServer {
+    Plex,
+    Jellyfin,
+    Emby,
+    Kodi,
+}
+
+/// How database IDs are written in series and movie folder names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdStyle {
+    /// No IDs
+    None,
+    /// `{tvdb-79169}`, read by Plex
+    Braces,
+    /// `[tvdbid-79169]`, read by Jellyfin
+    Brackets,
+    /// `[tvdbid=79169]`, read by Emby
+    BracketsEquals,
+}
+
+/// How the edition of a movie is written in its file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EditionStyle {
+    /// Left out
+    None,
+    /// `Movie (2001) {edition-Director's Cut}`, read by Plex
+    Braces,
+    /// `Movie (2001) - Director's Cut`, read as a version by Jellyfin and Emby
+    Suffix,
+}
+
+/// Naming choices of a media server library.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerLayout {
+    pub ids: IdStyle,
+    pub editions: EditionStyle,
+    /// Folder of season 0, `Specials` or `Season 00`
+    pub specials_folder: String,
+    /// Word before the part number of a split movie: `pt` gives `Movie (2001) - pt1`
+    pub part_label: String,
+    /// Write the episode title after the episode number
+    pub episode_titles: bool,
+}
+
+impl ServerLayout {
+    /// Default layout of `server`.
+    pub fn new(server: MediaServer) -> Self {
+        let (ids, editions, specials_folder, part_label) = match server {
+            MediaServer::Plex => (IdStyle::Braces, EditionStyle::Braces, "Specials", "pt"),
+            MediaServer::Jellyfin => (IdStyle::Brackets, EditionStyle::Suffix, "Season 00", "part"),
+            MediaServer::Emby => (IdStyle::BracketsEquals, EditionStyle::Suffix, "Season 00", "part"),
+            MediaServer::Kodi => (IdStyle::None, EditionStyle::None, "Specials", "pt"),
+        };
+        Self {
+            ids,
+            editions,
+            specials_folder: specials_folder.to_string(),
+            part_label: part_label.to_string(),
+            episode_titles: true,
+        }
+    }
+
+    /// Folder and file names for a single release.
+    pub fn name(&self, parsed: &ParsedRelease) -> NamedPath {
+        self.name_for(parsed, parsed, parsed.season)
+    }
+
+    /// Folder and file names for a file in a library. The title, year and
+    /// IDs come from the parsed directory when there is one, the season from
+    /// the season directory when the file name lacks it.
+    pub fn name_path(&self, path_info: &PathInfo) -> NamedPath {
+        let show = path_info.directory.as_ref().unwrap_or(&path_info.file);
+        self.name_for(show, &path_info.file, path_info.file.season.or(path_info.season))
+    }
+
+    /// Path of a library file relative to the library root, keeping its extension.
+    pub fn target_path(&self, path_info: &PathInfo) -> PathBuf {
+        let extension = Path::new(&path_info.full_path.replace('\\', "/")).extension().and_then(|extension| extension.to_str()).unwrap_or("").to_string();
+        self.name_path(path_info).to_path(&extension)
+    }
+
+    fn name_for(&self, show: &ParsedRelease, file: &ParsedRelease, season: Option<u128>) -> NamedPath {
+        let mut title = safe_value(&show.title);
+        if let Some(year) = show.year.or(file.year) {
+            title.push_str(&format!(" ({})", year));
+        }
+
+        let movie = file.release_type == "movie" || show.release_type == "movie";
+        let mut folder = title.clone();
+        if let Some(id) = self.id_tag(show, file, movie) {
+            folder.push(' ');
+            folder.push_str(&id);
+        }
+
+        if movie {
+            let mut name = title;
+            match (self.editions, show.edition.as_ref().or(file.edition.as_ref())) {
+                (EditionStyle::Braces, Some(edition)) => name.push_str(&format!(" {{edition-{}}}", safe_value(edition))),
+                (EditionStyle::Suffix, Some(edition)) => name.push_str(&format!(" - {}", safe_value(edition))),
+                _ => {}
+            }
+            if let Some(part) = file.part.or(file.disc.map(u16::from)) {
+                name.push_str(&format!(" - {}{}", self.part_label, part));
+            }
+            return NamedPath {
+                folder,
+                season_folder: None,
+                file: name,
+            };
+        }
+
+        // Daily shows go by air date, in a folder per year
+        let season_folder = match (&file.date, season) {
+            (Some(date), _) => Some(format!("Season {}", date.year)),
+            (None, Some(0)) => Some(self.specials_folder.clone()),
+            (None, Some(season)) => Some(format!("Season {:02}", season)),
+            (None, None) => None,
+        };
+
+        let mut name = title;
+        let episode_title = if self.episode_titles { safe_value(&file.episode_title) } else { String::new() };
+        // Without a season the episode, part or special number is written on its own: `Show - E005`
+        let numbered = ParsedRelease {
+            season,
+            episode_title: episode_title.clone(),
+            ..file.clone()
+        };
+        if let Some(marker) = file.date.map(|date| date.to_string()).or_else(|| numbered.episode_marker(NameStyle::Bracket)) {
+            name.push_str(&format!(" - {}", marker));
+            if !episode_title.is_empty() {
+                name.push_str(&format!(" - {}", episode_title));
+            }
+        }
+        NamedPath {
+            folder,
+            season_folder,
+            file: name,
+        }
+    }
+
+    /// Tag of the ID the server matches on: TVDB for series, TMDB for movies,
+    /// then whichever is known.
+    fn id_tag(&self, show: &ParsedRelease, file: &ParsedRelease, movie: bool) -> Option<String> {
+        let id = |key: &str| -> Option<String> {
+            let (show_id, file_id) = match key {
+                "tvdb" => (&show.tvdb_id, &file.tvdb_id),
+                "tmdb" => (&show.tmdb_id, &file.tmdb_id),
+                _ => (&show.imdb_id, &file.imdb_id),
+            };
+            show_id.clone().or_else(|| file_id.clone())
+        };
+        let order = if movie { ["tmdb", "imdb", "tvdb"] } else { ["tvdb", "tmdb", "imdb"] };
+        let (key, id) = order.into_iter().find_map(|key| id(key).map(|id| (key, id)))?;
+        match self.ids {
+            IdStyle::None => None,
+            IdStyle::Braces => Some(format!("{{{}-{}}}", key, id)),
+            IdStyle::Brackets => Some(format!("[{}id-{}]", key, id)),
+            IdStyle::BracketsEquals => Some(format!("[{}id={}]", key, id)),
+        }
+    }
+}
+
+impl LibraryNaming for ServerLayout {
+    fn name_path(&self, path_info: &PathInfo) -> NamedPath {
+        ServerLayout::name_path(self, path_info)
+    }
+}
+
+impl From<MediaServer> for ServerLayout {
+    fn from(server: MediaServer) -> Self {
+        Self::new(server)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::AirDate;
+    use crate::ReleaseParser;
+
+    #[test]
+    fn test_plex_episode() {
+        let path_info = ReleaseParser::new("tv")
+            .parse_path("/downloads/Seinfeld (1989) {tvdb-79169}/Season 01/Seinfeld.S01E01-E02.The.Seinfeld.Chronicles.720p.HDTV.x264-GRP.mkv")
+            .unwrap();
+        let plex = ServerLayout::new(MediaServer::Plex);
+        assert_eq!(
+            plex.target_path(&path_info),
+            PathBuf::from("Seinfeld (1989) {tvdb-79169}/Season 01/Seinfeld (1989) - S01E01-E02 - The Seinfeld Chronicles.mkv")
+        );
+
+        let jellyfin = ServerLayout::new(MediaServer::Jellyfin);
+        assert_eq!(jellyfin.name_path(&path_info).folder, "Seinfeld (1989) [tvdbid-79169]");
+        assert_eq!(ServerLayout::new(MediaServer::Emby).name_path(&path_info).folder, "Seinfeld (1989) [tvdbid=79169]");
+        assert_eq!(ServerLayout::new(MediaServer::Kodi).name_path(&path_info).folder, "Seinfeld (1989)");
+    }
+
+    #[test]
+    fn test_specials_and_daily() {
+        let mut parsed = ReleaseParser::new("tv").parse("Show.S01E05.720p.HDTV.x264-GRP");
+        parsed.season = Some(0);
+        let named = ServerLayout::new(MediaServer::Plex).name(&parsed);
+        assert_eq!(named.season_folder.as_deref(), Some("Specials"));
+        assert_eq!(named.file, "Show - S00E05");
+        assert_eq!(ServerLayout::new(MediaServer::Jellyfin).name(&parsed).season_folder.as_deref(), Some("Season 00"));
+
+        let parsed = ParsedRelease {
+            title: "The Daily Show".to_string(),
+            date: AirDate::new(2013, 10, 30),
+            episode_title: "Guest".to_string(),
+            release_type: "tv".to_string(),
+            ..Default::default()
+        };
+        let named = ServerLayout::new(MediaServer::Plex).name(&parsed);
+        assert_eq!(named.season_folder.as_deref(), Some("Season 2013"));
+        assert_eq!(named.file, "The Daily Show - 2013-10-30 - Guest");
+    }
+
+    #[test]
+    fn test_episodes_without_season() {
+        let parser = ReleaseParser::new("tv");
+        let plex = ServerLayout::new(MediaServer::Plex);
+        let first = plex.name(&parser.parse("Show.E001.720p.HDTV.x264-GRP"));
+        let second = plex.name(&parser.parse("Show.E002.720p.HDTV.x264-GRP"));
+        assert_eq!((first.season_folder.as_deref(), first.file.as_str()), (None, "Show - E001"));
+        assert_eq!(second.file, "Show - E002");
+
+        assert_eq!(plex.name(&parser.parse("Show - 005 - Title [HDTV-720p]-GRP")).file, "Show - 005 - Title");
+        assert_eq!(plex.name(&parser.parse("Planet.Earth.Part.3.of.6.720p.BluRay.x264-GRP")).file, "Planet Earth - Part 3 of 6");
+        assert_eq!(plex.name(&parser.parse("[Group] Frieren - OVA 02 [1080p]")).file, "Frieren - OVA 02");
+    }
+
+    #[test]
+    fn test_movie_edition_and_parts() {
+        let mut parsed = ReleaseParser::new("movie").parse("Vanilla Sky (2001) {tmdb-1903} {edition-Directors Cut} [Bluray-1080p][x264]-GRP");
+        let plex = ServerLayout::new(MediaServer::Plex);
+        assert_eq!(plex.name(&parsed).to_path("mkv"), PathBuf::from("Vanilla Sky (2001) {tmdb-1903}/Vanilla Sky (2001) {edition-Directors Cut}.mkv"));
+
+        let jellyfin = ServerLayout::new(MediaServer::Jellyfin);
+        assert_eq!(jellyfin.name(&parsed).to_path("mkv"), PathBuf::from("Vanilla Sky (2001) [tmdbid-1903]/Vanilla Sky (2001) - Directors Cut.mkv"));
+
+        parsed.edition = None;
+        parsed.disc = Some(2);
+        assert_eq!(plex.name(&parsed).file, "Vanilla Sky (2001) - pt2");
+        assert_eq!(jellyfin.name(&parsed).file, "Vanilla Sky (2001) - part2");
+        parsed.part = Some(3);
+        assert_eq!(plex.name(&parsed).file, "Vanilla Sky (2001) - pt3");
+    }
+
+    #[test]
+    fn test_custom_layout() {
+        let layout = ServerLayout {
+            specials_folder: "Extras".to_string(),
+            episode_titles: false,
+            ..MediaServer::Plex.into()
+        };
+        let mut parsed = ReleaseParser::new("tv").parse("Show.S01E05.Title.720p.HDTV.x264-GRP");
+        assert_eq!(layout.name(&parsed).file, "Show - S01E05");
+        parsed.season = Some(0);
+        assert_eq!(layout.name(&parsed).season_folder.as_deref(), Some("Extras"));
+    }
+}
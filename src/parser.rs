@@ -1,15 +1,66 @@
-use crate::types::ParsedRelease;
-use regex::Regex;
-use std::collections::HashMap;
+use crate::classify::{classify, Classification};
+use crate::date::{find_token_dates, AirDate};
+use crate::explain::Explanation;
+use crate::report::{FieldReport, ParseReport};
+use crate::rules::{self, regex, Rule};
+use crate::tokenizer::{tokenize, Separator, Token};
+use crate::error::ParseError;
+use crate::scan::Inventory;
+use crate::types::{PackKind, ParsedRelease, PathInfo, SpecialKind};
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+/// Release type of parsers created with [`ReleaseParser::auto`].
+const AUTO: &str = "auto";
+
+/// Most episodes a range like `E01-E24` may add; longer ones are misreads.
+const MAX_EPISODE_RANGE: u128 = 100;
+
+/// Fields of technical tags, after which a capitalized last word is a group.
+const TAG_FIELDS: &[&str] = &["resolution", "audio", "streaming_provider", "source", "format", "hdr", "language", "flags", "device", "os", "version"];
 
 pub struct ReleaseParser {
     release_type: String,
+    strict: bool,
 }
 
 impl ReleaseParser {
     pub fn new(release_type: &str) -> Self {
         Self {
             release_type: release_type.to_string(),
+            strict: false,
+        }
+    }
+
+    /// In strict mode [`try_parse`](Self::try_parse) and
+    /// [`try_parse_path`](Self::try_parse_path) fail with
+    /// [`ParseError::MissingField`] when the title or group can't be determined.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// A parser that detects the release type of every name it parses.
+    ///
+    /// `release_type` of the result holds the detected type; use
+    /// [`classify`](Self::classify) to also get the confidence.
+    pub fn auto() -> Self {
+        Self::new(AUTO)
+    }
+
+    /// The release type this parser uses for `release_name`.
+    ///
+    /// For [`auto`](Self::auto) parsers the type is detected from the tokens,
+    /// otherwise it is the configured type with a confidence of 1.0.
+    pub fn classify(&self, release_name: &str) -> Classification {
+        if self.release_type == AUTO {
+            classify(release_name)
+        } else {
+            Classification {
+                release_type: self.release_type.clone(),
+                confidence: 1.0,
+            }
         }
     }
 
@@ -22,35 +73,31 @@ impl ReleaseParser {
         };
 
         // Extract year
-        if let Some(year) = self.extract_year(directory_name) {
+        if let Some((year, _, _)) = self.extract_year(directory_name).into_iter().next() {
             parsed.year = Some(year);
         }
 
         // Extract IDs
-        if let Some(tmdb_id) = self.extract_tmdb_id(directory_name) {
+        if let Some((tmdb_id, _, _)) = self.extract_tmdb_id(directory_name) {
             parsed.tmdb_id = Some(tmdb_id);
         }
-        if let Some(tvdb_id) = self.extract_tvdb_id(directory_name) {
+        if let Some((tvdb_id, _, _)) = self.extract_tvdb_id(directory_name) {
             parsed.tvdb_id = Some(tvdb_id);
         }
-        if let Some(imdb_id) = self.extract_imdb_id(directory_name) {
+        if let Some((imdb_id, _, _)) = self.extract_imdb_id(directory_name) {
             parsed.imdb_id = Some(imdb_id);
         }
 
         // Extract title (remove year and IDs)
         let mut title = directory_name.to_string();
-        if let Ok(year_re) = Regex::new(r"\s*\(\d{4}\)\s*") {
-            title = year_re.replace_all(&title, " ").to_string();
-        }
-        if let Ok(tmdb_re) = Regex::new(r"\s*\{tmdb-\d+\}\s*") {
-            title = tmdb_re.replace_all(&title, " ").to_string();
-        }
-        if let Ok(tvdb_re) = Regex::new(r"\s*\{tvdb-\d+\}\s*|\[tvdb(?:id)?-\d+\]") {
-            title = tvdb_re.replace_all(&title, " ").to_string();
-        }
-        if let Ok(imdb_re) = Regex::new(r"\s*\{imdb-tt\d+\}\s*|\[imdb(?:id)?-tt\d+\]") {
-            title = imdb_re.replace_all(&title, " ").to_string();
-        }
+        let year_re = regex!(r"\s*\(\d{4}\)\s*");
+        title = year_re.replace_all(&title, " ").to_string();
+        let tmdb_re = regex!(r"\s*\{tmdb-\d+\}\s*");
+        title = tmdb_re.replace_all(&title, " ").to_string();
+        let tvdb_re = regex!(r"\s*\{tvdb-\d+\}\s*|\[tvdb(?:id)?-\d+\]");
+        title = tvdb_re.replace_all(&title, " ").to_string();
+        let imdb_re = regex!(r"\s*\{imdb-tt\d+\}\s*|\[imdb(?:id)?-tt\d+\]");
+        title = imdb_re.replace_all(&title, " ").to_string();
         parsed.title = clean_title(&title);
 
         parsed
@@ -65,29 +112,26 @@ impl ReleaseParser {
         };
 
         // Extract year
-        if let Some(year) = self.extract_year(directory_name) {
+        if let Some((year, _, _)) = self.extract_year(directory_name).into_iter().next() {
             parsed.year = Some(year);
         }
 
         // Extract IDs
-        if let Some(tmdb_id) = self.extract_tmdb_id(directory_name) {
+        if let Some((tmdb_id, _, _)) = self.extract_tmdb_id(directory_name) {
             parsed.tmdb_id = Some(tmdb_id);
         }
-        if let Some(imdb_id) = self.extract_imdb_id(directory_name) {
+        if let Some((imdb_id, _, _)) = self.extract_imdb_id(directory_name) {
             parsed.imdb_id = Some(imdb_id);
         }
 
         // Extract title (remove year and IDs)
         let mut title = directory_name.to_string();
-        if let Ok(year_re) = Regex::new(r"\s*\(\d{4}\)\s*") {
-            title = year_re.replace_all(&title, " ").to_string();
-        }
-        if let Ok(tmdb_re) = Regex::new(r"\s*\{tmdb-\d+\}\s*|\[tmdb(?:id)?-\d+\]") {
-            title = tmdb_re.replace_all(&title, " ").to_string();
-        }
-        if let Ok(imdb_re) = Regex::new(r"\s*\{imdb-tt\d+\}\s*|\[imdb(?:id)?-tt\d+\]") {
-            title = imdb_re.replace_all(&title, " ").to_string();
-        }
+        let year_re = regex!(r"\s*\(\d{4}\)\s*");
+        title = year_re.replace_all(&title, " ").to_string();
+        let tmdb_re = regex!(r"\s*\{tmdb-\d+\}\s*|\[tmdb(?:id)?-\d+\]");
+        title = tmdb_re.replace_all(&title, " ").to_string();
+        let imdb_re = regex!(r"\s*\{imdb-tt\d+\}\s*|\[imdb(?:id)?-tt\d+\]");
+        title = imdb_re.replace_all(&title, " ").to_string();
         parsed.title = clean_title(&title);
 
         parsed
@@ -96,27 +140,46 @@ impl ReleaseParser {
     /// Parse a season directory name (e.g., "Season 01" or "Season 1")
     pub fn parse_season_directory(&self, directory_name: &str) -> Option<u128> {
         // Match "Season 01" or "Season 1"
-        if let Ok(re) = Regex::new(r"(?i)Season\s+(\d+)") {
-            if let Some(cap) = re.captures(directory_name) {
-                if let Ok(season) = cap.get(1).unwrap().as_str().parse::<u128>() {
-                    return Some(season);
-                }
+        let re = regex!(r"(?i)Season\s+(\d+)");
+        if let Some(cap) = re.captures(directory_name) {
+            if let Ok(season) = cap.get(1).unwrap().as_str().parse::<u128>() {
+                return Some(season);
             }
         }
         None
     }
 
     /// Parse a full file path, extracting directory, season (if TV), and file information
-    pub fn parse_path(&self, file_path: &str) -> Option<crate::types::PathInfo> {
-        use std::path::Path;
-        use crate::types::PathInfo;
-        
+    ///
+    /// Returns `None` where [`try_parse_path`](Self::try_parse_path) returns an error.
+    pub fn parse_path(&self, file_path: &str) -> Option<PathInfo> {
+        self.read_path(file_path).ok()
+    }
+
+    /// Like [`parse_path`](Self::parse_path), but says why a path can't be
+    /// parsed and accepts paths that are not valid UTF-8.
+    pub fn try_parse_path(&self, file_path: impl AsRef<Path>) -> Result<PathInfo, ParseError> {
+        let file_path = file_path.as_ref();
+        let Some(file_path) = file_path.to_str() else {
+            let component = file_path.components().map(|component| component.as_os_str()).find(|component| component.to_str().is_none());
+            return Err(ParseError::NonUtf8Component(component.unwrap_or(file_path.as_os_str()).to_string_lossy().into_owned()));
+        };
+        let path_info = self.read_path(file_path)?;
+        self.check_mandatory_fields(&path_info.file)?;
+        Ok(path_info)
+    }
+
+    fn read_path(&self, file_path: &str) -> Result<PathInfo, ParseError> {
+        if file_path.trim().is_empty() {
+            return Err(ParseError::Empty);
+        }
+
         // Normalize Windows paths (backslashes to forward slashes) for cross-platform compatibility
         let normalized_path = file_path.replace('\\', "/");
         let path = Path::new(&normalized_path);
         
         // Remove file extension
-        let file_name_without_ext = path.file_stem()?.to_str()?;
+        let file_name_without_ext = path.file_stem().and_then(|stem| stem.to_str()).ok_or(ParseError::NoFileName)?;
         
         // Parse the file
         let file_parsed = self.parse(file_name_without_ext);
@@ -129,17 +192,19 @@ impl ReleaseParser {
         };
         
         // Get parent directory
-        let parent = path.parent()?;
+        let parent = path.parent().ok_or(ParseError::NoParentDirectory)?;
         
         // Check if parent is a season directory
-        let season_dir_name = parent.file_name()?.to_str()?;
+        let season_dir_name = directory_name(parent).ok_or(ParseError::NoParentDirectory)?;
         let season = self.parse_season_directory(season_dir_name);
         
         // Get series/movie directory (parent of season, or parent itself if no season)
         let series_dir = if season.is_some() {
-            parent.parent()?.file_name()?.to_str()?
+            parent.parent().and_then(directory_name).ok_or_else(|| {
+                ParseError::UnrecognizedLayout(format!("season directory {:?} is not inside a series directory", season_dir_name))
+            })?
         } else {
-            parent.file_name()?.to_str()?
+            season_dir_name
         };
         
         // Parse the series/movie directory
@@ -150,7 +215,7 @@ impl ReleaseParser {
             Some(directory_parser.parse_movie_directory(series_dir))
         };
         
-        Some(PathInfo {
+        Ok(PathInfo {
             directory,
             season,
             file: file_parsed,
@@ -159,24 +224,115 @@ impl ReleaseParser {
     }
 
     pub fn parse(&self, release_name: &str) -> ParsedRelease {
+        self.analyze(release_name).parsed
+    }
+
+    /// Like [`parse`](Self::parse), but fails on an empty release name and,
+    /// in [`strict`](Self::strict) mode, when the title or group is missing.
+    pub fn try_parse(&self, release_name: &str) -> Result<ParsedRelease, ParseError> {
+        if release_name.trim().is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let parsed = self.parse(release_name);
+        self.check_mandatory_fields(&parsed)?;
+        Ok(parsed)
+    }
+
+    fn check_mandatory_fields(&self, parsed: &ParsedRelease) -> Result<(), ParseError> {
+        if !self.strict {
+            return Ok(());
+        }
+        if parsed.title.is_empty() {
+            return Err(ParseError::MissingField("title"));
+        }
+        if parsed.group.is_empty() {
+            return Err(ParseError::MissingField("group"));
+        }
+        Ok(())
+    }
+
+    /// Walk a library root and sort every media file into a series or movie.
+    /// See [`Inventory`].
+    pub fn scan(&self, root: impl AsRef<Path>) -> io::Result<Inventory> {
+        Inventory::scan(self, root.as_ref())
+    }
+
+    /// Parse a release name and report, for every field, the rule that
+    /// matched, its confidence and its span, along with the values that
+    /// matched but were not used.
+    pub fn parse_report(&self, release_name: &str) -> ParseReport {
+        let analysis = self.analyze(release_name);
+        ParseReport {
+            parsed: analysis.parsed,
+            fields: analysis.fields,
+            alternatives: analysis.alternatives,
+        }
+    }
+
+    /// Parse a release name and map every part of it to the fields read from
+    /// it. See [`Explanation::render`] for a printable version.
+    pub fn explain(&self, release_name: &str) -> Explanation {
+        Explanation::new(release_name, self.analyze(release_name))
+    }
+
+    /// Parse a release name and keep the tokens along with the field each one was assigned to.
+    ///
+    /// Fields claim their tokens in a fixed priority order (identifiers first,
+    /// then group, episode markers, year, resolution, ...). A token belongs to
+    /// at most one field; a value whose tokens were all taken by an earlier
+    /// field is dropped. The title is built from what is left.
+    pub(crate) fn analyze(&self, release_name: &str) -> Analysis {
+        if self.release_type == AUTO {
+            let classification = classify(release_name);
+            let mut analysis = ReleaseParser::new(&classification.release_type).analyze(release_name);
+            analysis.fields[0].rule = "type.detected".to_string();
+            analysis.fields[0].confidence = classification.confidence;
+            return analysis;
+        }
         let mut parsed = ParsedRelease {
             release: release_name.to_string(),
             release_type: self.release_type.clone(),
             ..Default::default()
         };
+        let mut analysis = Analysis::new(release_name);
+        analysis.record("type", &self.release_type, None, Rule("type.configured", 1.0));
+
+        // Extract IDs and edition ({tmdb-123}, [imdbid-tt123], {edition-...})
+        parsed.tmdb_id = analysis.take("tmdb_id", self.extract_tmdb_id(release_name));
+        parsed.tvdb_id = analysis.take("tvdb_id", self.extract_tvdb_id(release_name));
+        parsed.imdb_id = analysis.take("imdb_id", self.extract_imdb_id(release_name));
+        parsed.edition = analysis.take("edition", self.extract_edition(release_name));
 
         // Extract group (usually at the end after a dash)
-        if let Some(group) = self.extract_group(release_name) {
-            parsed.group = group;
+        parsed.group = analysis.take("group", self.extract_group(release_name)).unwrap_or_default();
+
+        // Extract date (for date-based episodes), before the numbers in it pass for an episode or year
+        parsed.date = analysis.take_first("date", self.extract_date(release_name, &analysis.tokens));
+        // "13-10" in "2013-10-30" is neither an episode nor a season
+        let date_span = analysis.field_span("date");
+        let outside_date = |span: &Range<usize>| date_span.as_ref().is_none_or(|date| span.end <= date.start || date.end <= span.start);
+
+        // Season packs (S02, Season 1-3, S01-S05, Complete Series), before "1-3" passes for season 1 episode 3
+        if self.release_type == "tv" {
+            let complete = analysis.take("pack", self.extract_complete_series(release_name));
+            parsed.seasons = analysis.take_first("seasons", self.extract_season_pack(release_name)).unwrap_or_default();
+            parsed.pack = complete.or(match parsed.seasons.len() {
+                0 => None,
+                1 => Some(PackKind::Season),
+                _ => Some(PackKind::SeasonRange),
+            });
+            if let [season] = parsed.seasons[..] {
+                parsed.season = Some(season);
+            }
         }
 
         // Extract season and episode for TV shows
         if self.release_type == "tv" {
-            // Try date-based format first (2013-10-30)
-            if let Some((season, episode, episodes)) = self.extract_season_episode(release_name) {
-                // Only set season if it's not 0 (0 indicates no season for episode-only formats)
-                if season > 0 {
-                    parsed.season = Some(season);
+            let candidates = self.extract_season_episode(release_name).into_iter().filter(|(_, span, _)| outside_date(span)).collect();
+            if let Some((season, episode, episodes)) = analysis.take_first("episode", candidates) {
+                // Episode-only formats have no season; S00E05 is in season 0
+                if season.is_some() {
+                    parsed.season = season;
                 }
                 parsed.episodes = episodes.clone();
                 // Only set episode if there's a single episode, otherwise set to None
@@ -187,9 +343,11 @@ impl ReleaseParser {
                 }
             } else {
                 // Handle episode-only formats (like "Episode 61") - no season
-                if let Ok(re) = Regex::new(r"(?i)Episode\s+(\d{1,3})") {
-                    if let Some(cap) = re.captures(release_name) {
-                        if let Ok(episode) = cap.get(1).unwrap().as_str().parse::<u128>() {
+                let re = regex!(r"(?i)Episode\s+(\d{1,3})");
+                if let Some(cap) = re.captures(release_name) {
+                    if let Ok(episode) = cap.get(1).unwrap().as_str().parse::<u128>() {
+                        let found = Some((episode, cap.get(0).unwrap().range(), Rule("episode.word", 0.8)));
+                        if analysis.take("episode", found).is_some() {
                             // Don't set season for episode-only formats
                             parsed.episode = Some(episode);
                             parsed.episodes = vec![episode];
@@ -199,61 +357,9 @@ impl ReleaseParser {
             }
         }
 
-        // Extract year
-        if let Some(year) = self.extract_year(release_name) {
-            parsed.year = Some(year);
-        }
-
-        // Extract source (DVDRip, WEB-DL, HDTV, etc.)
-        parsed.source = self.extract_source(release_name);
-
-        // Extract format (SVCD, VCD, etc.)
-        parsed.format = self.extract_format(release_name);
-
-        // Extract resolution (1080p, 720p, 480p, etc.)
-        parsed.resolution = self.extract_resolution(release_name);
-
-        // Extract audio information
-        parsed.audio = self.extract_audio(release_name);
-
-        // Extract device (XBOX, PS3, etc.)
-        parsed.device = self.extract_device(release_name);
-
-        // Extract OS (Linux, Windows, etc.)
-        parsed.os = self.extract_os(release_name);
-
-        // Extract version
-        parsed.version = self.extract_version(release_name);
-
-        // Extract languages
-        parsed.language = self.extract_languages(release_name);
-
-        // Extract flags
-        parsed.flags = self.extract_flags(release_name);
-
-        // Extract TMDB ID
-        if let Some(tmdb_id) = self.extract_tmdb_id(release_name) {
-            parsed.tmdb_id = Some(tmdb_id);
-        }
-
-        // Extract TVDB ID
-        if let Some(tvdb_id) = self.extract_tvdb_id(release_name) {
-            parsed.tvdb_id = Some(tvdb_id);
-        }
-
-        // Extract IMDB ID
-        if let Some(imdb_id) = self.extract_imdb_id(release_name) {
-            parsed.imdb_id = Some(imdb_id);
-        }
-
-        // Extract edition
-        if let Some(edition) = self.extract_edition(release_name) {
-            parsed.edition = Some(edition);
-        }
-
         // Extract episode number (001, 001-003 format, or [119] format)
         // For ranges, extract all numbers; for single numbers, use directly
-        if let Some(ep_num) = self.extract_episode_number(release_name) {
+        if let Some(ep_num) = analysis.take("episode", self.extract_episode_number(release_name)) {
             // If it's a range (like "001-003"), extract all numbers
             if ep_num.contains('-') {
                 let parts: Vec<&str> = ep_num.split('-').collect();
@@ -262,16 +368,9 @@ impl ReleaseParser {
                         parts[0].parse::<u128>(),
                         parts[1].parse::<u128>(),
                     ) {
-                        // Generate episode range
-                        let mut episodes = Vec::new();
-                        for ep in ep_start..=ep_end {
-                            episodes.push(ep);
-                        }
-                        parsed.episodes = episodes.clone();
-                        // Only set episode if single episode
-                        if episodes.len() == 1 {
-                            parsed.episode = Some(ep_start);
-                        } else {
+                        // A descending or absurd range is left alone
+                        if let Some(episodes) = multi_episodes(ep_start, [(true, ep_end)]) {
+                            parsed.episodes = episodes;
                             parsed.episode = None;
                         }
                     }
@@ -288,46 +387,176 @@ impl ReleaseParser {
             }
         }
 
-        // Extract date (for date-based episodes)
-        if let Some(date) = self.extract_date(release_name) {
-            parsed.date = Some(date);
+        // Specials, OVAs and extras; their number is the episode when there is no other
+        if self.release_type == "tv" {
+            let episode_start = analysis.field_span("episode").map(|span| span.start);
+            // "S01E00" or "S00E05" is no regular episode, "S01E01" is
+            let regular = parsed.season != Some(0) && !parsed.episodes.contains(&0);
+            let special = analysis.take_first("special_kind", self.extract_special_tag(release_name)).or_else(|| {
+                let found = self.extract_special_word(release_name, episode_start);
+                if episode_start.is_none() {
+                    // Without an episode marker the word is the marker: "Show.Special.720p"
+                    return analysis.take("special_kind", found);
+                }
+                // Otherwise it only counts right after an episode that is no regular one, and
+                // is left for the episode title: the "Pilot" of "S01E00.Pilot", but not the
+                // "Special" of "S01E01.The.Special.One"
+                let next = analysis.unclaimed_after("episode").next().map(|token| token.span.start);
+                let ((kind, number), span, rule) = found.filter(|(_, span, _)| !regular && next == Some(span.start))?;
+                analysis.record("special_kind", kind, Some(span), rule);
+                Some((kind, number))
+            });
+            if let Some((kind, number)) = special {
+                parsed.special_kind = Some(kind);
+                if let (Some(number), true) = (number, parsed.episodes.is_empty()) {
+                    parsed.episode = Some(number);
+                    parsed.episodes = vec![number];
+                }
+            }
+            if parsed.season == Some(0) {
+                parsed.special_kind.get_or_insert(SpecialKind::Special);
+            }
         }
 
-        // Extract HDR information
-        parsed.hdr = self.extract_hdr(release_name);
+        // Extract year
+        parsed.year = analysis.take_first("year", self.extract_year(release_name).into_iter().filter(|(_, span, _)| outside_date(span)).collect());
+
+        // Parts and volumes count after the year or an ID, as the "Part 2" of
+        // "Deathly.Hallows.Part.2.2011" belongs to the title. TV also takes a
+        // part of a total, "Part.3.of.6", and any part of a name without either
+        let anchor_end = ["year", "tmdb_id", "tvdb_id", "imdb_id"].into_iter().filter_map(|field| analysis.field_span(field)).map(|span| span.end).min();
+        let counts = |span: &Range<usize>, total: bool| {
+            anchor_end.is_some_and(|end| span.start >= end) || (self.release_type == "tv" && (total || anchor_end.is_none()))
+        };
+        let part = self.extract_part(release_name).filter(|((_, total), span, _)| counts(span, total.is_some()));
+        let part = if parsed.episodes.is_empty() {
+            analysis.take("part", part)
+        } else {
+            // "S01E02.The.Reckoning.Part.2": the part is left in the episode title
+            part.map(|((part, total), span, rule)| {
+                analysis.record("part", part, Some(span), rule);
+                (part, total)
+            })
+        };
+        if let Some((part, total)) = part {
+            parsed.part = Some(part);
+            parsed.part_total = total;
+        }
+        parsed.volume = analysis.take("volume", self.extract_volume(release_name).filter(|(_, span, _)| counts(span, false)));
+        // The part of a miniseries is its episode; a volume holds several
+        if self.release_type == "tv" && parsed.season.is_none() && parsed.episodes.is_empty() {
+            if let Some(part) = parsed.part {
+                parsed.episode = Some(u128::from(part));
+                parsed.episodes = vec![u128::from(part)];
+            }
+        }
+
+        // Extract resolution (1080p, 720p, 480p, etc.)
+        parsed.resolution = analysis.take_first("resolution", self.extract_resolution(release_name)).unwrap_or_default();
+
+        // Extract audio information (before providers, so the MA of DTS-HD.MA is audio)
+        parsed.audio = analysis.take("audio", self.extract_audio(release_name)).unwrap_or_default();
+
+        // Flags, languages, sources and providers are also common words
+        // ("Retail.Therapy", "Mad.Max"), so they only count from the end of
+        // the title on: the year, the episode, or the first technical tag
+        let sources = self.extract_source(release_name);
+        let format = self.extract_format(release_name);
+        let hdr = self.extract_hdr(release_name);
+        let strong_sources = sources.iter().filter(|(source, span, rule)| is_strong_source(release_name, source, span, rule)).map(|(_, span, _)| span);
+        let tags = strong_sources.chain(format.iter().map(|(_, span, _)| span)).chain(hdr.iter().map(|(_, span, _)| span));
+        let title_end = analysis.title_end().into_iter().chain(tags.map(|span| span.start)).min();
+        let episode_title = analysis.dashed_episode_title();
+        let after_title = |span: &Range<usize>| title_end.is_none_or(|end| span.start >= end) && !episode_title.as_ref().is_some_and(|words| words.contains(&span.start));
 
         // Extract streaming provider
-        parsed.streaming_provider = self.extract_streaming_provider(release_name);
+        let (providers, in_title): (Candidates<String>, _) = self.extract_streaming_provider(release_name).into_iter().partition(|(_, span, _)| after_title(span));
+        parsed.streaming_provider = analysis.take_first("streaming_provider", providers).unwrap_or_default();
+        // "Mad.Max" might still have been a provider
+        analysis.add_alternatives(in_title.into_iter().flat_map(|(provider, span, rule)| provider.describe("streaming_provider", span, rule)).collect());
+
+        // Extract source (DVDRip, WEB-DL, HDTV, etc.)
+        let sources = sources.into_iter().filter(|(source, span, rule)| after_title(span) || is_strong_source(release_name, source, span, rule)).collect();
+        parsed.source = analysis.take_first("source", sources).unwrap_or_default();
+
+        // Extract format (SVCD, VCD, etc.)
+        parsed.format = analysis.take("format", format).unwrap_or_default();
 
-        // Extract title and episode_title
-        let (title, episode_title) = self.extract_title(release_name, &parsed);
-        parsed.title = title;
-        parsed.episode_title = episode_title;
+        // Extract HDR information
+        parsed.hdr = analysis.take("hdr", hdr).unwrap_or_default();
+
+        // Extract languages; codes in brackets are tags wherever they are
+        for (code, name, span, rule) in self.extract_languages(release_name, &analysis.tokens, title_end) {
+            if parsed.language.contains_key(&code) || (matches!(rule.0, "language.name" | "language.multi") && !after_title(&span)) {
+                continue;
+            }
+            if let Some(code) = analysis.take("language", Some((code, span, rule))) {
+                parsed.language.insert(code, name);
+            }
+        }
+
+        // Extract flags
+        for found in self.extract_flags(release_name).into_iter().filter(|(_, span, _)| after_title(span)) {
+            if let Some(flag) = analysis.take("flags", Some(found)) {
+                parsed.flags.push(flag);
+            }
+        }
+
+        // Extract device (XBOX, PS3, etc.)
+        parsed.device = analysis.take("device", self.extract_device(release_name).filter(|(_, span, _)| after_title(span))).unwrap_or_default();
+
+        // Extract OS (Linux, Windows, etc.)
+        parsed.os = analysis.take("os", self.extract_os(release_name).filter(|(_, span, _)| after_title(span))).unwrap_or_default();
+
+        // Extract version
+        parsed.version = analysis.take("version", self.extract_version(release_name)).unwrap_or_default();
 
         // Extract disc number
-        if let Some(disc) = self.extract_disc(release_name) {
-            parsed.disc = Some(disc);
+        parsed.disc = analysis.take("disc", self.extract_disc(release_name));
+
+        // A group without a dash is the last word, once every tag is known
+        if parsed.group.is_empty() {
+            parsed.group = analysis.take("group", self.extract_trailing_group(release_name, &analysis)).unwrap_or_default();
         }
 
-        parsed
+        // The title and episode title are the words left over
+        let title = self.extract_title(release_name, &analysis);
+        if let Some((title, span)) = title {
+            analysis.claim("title", &span);
+            analysis.record("title", &title, Some(span), Rule("title.remaining_tokens", 0.7));
+            parsed.title = title;
+        }
+        let episode_title = if self.release_type == "tv" { self.extract_episode_title(&analysis) } else { None };
+        if let Some((episode_title, span)) = episode_title {
+            analysis.claim("episode_title", &span);
+            analysis.record("episode_title", &episode_title, Some(span), Rule("episode_title.remaining_tokens", 0.7));
+            parsed.episode_title = episode_title;
+        }
+
+        // Whatever is left was not recognized
+        let unparsed: Vec<&Token> = analysis.unparsed().collect();
+        parsed.unparsed_tokens = unparsed.iter().map(|token| token.text.clone()).collect();
+        parsed.unparsed_spans = unparsed.iter().map(|token| token.span.clone()).collect();
+
+        analysis.parsed = parsed;
+        analysis
     }
 
-    fn extract_group(&self, release_name: &str) -> Option<String> {
+    fn extract_group(&self, release_name: &str) -> Found<String> {
         // First check for release group in brackets at the start: [GM-Team], [Erai-raws], [ToonsHub]
-        if let Ok(re) = Regex::new(r"^\[([^\]]+)\]") {
-            if let Some(cap) = re.captures(release_name) {
-                let potential_group = cap.get(1).unwrap().as_str().trim();
-                // Check if it looks like a release group (not metadata like [国漫], [AVC], etc.)
-                // Release groups typically contain letters, numbers, hyphens, and are 3-30 chars
-                if potential_group.len() >= 3 && potential_group.len() <= 30 {
-                    // Check if it contains mostly alphanumeric characters (allow hyphens, underscores, and spaces)
-                    let alnum_count = potential_group.chars().filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_' || *c == ' ').count();
-                    if alnum_count as f32 / potential_group.len() as f32 > 0.7 {
-                        // Exclude common metadata tags
-                        let metadata_tags = vec!["AVC", "GB", "1080P", "720p", "WEB-DL", "WEBDL", "WEBRiP", "BluRay", "x264", "x265", "h264", "h265", "HEVC", "AAC", "AC3", "DTS", "MultiSub", "Multi-Subs"];
-                        if !metadata_tags.iter().any(|&tag| potential_group.eq_ignore_ascii_case(tag)) {
-                            return Some(potential_group.to_string());
-                        }
+        let re = regex!(r"^\[([^\]]+)\]");
+        if let Some(cap) = re.captures(release_name) {
+            let potential_group = cap.get(1).unwrap().as_str().trim();
+            // Check if it looks like a release group (not metadata like [国漫], [AVC], etc.)
+            // Release groups typically contain letters, numbers, hyphens, and are 3-30 chars
+            if potential_group.len() >= 3 && potential_group.len() <= 30 {
+                // Check if it contains mostly alphanumeric characters (allow hyphens, underscores, and spaces)
+                let alnum_count = potential_group.chars().filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_' || *c == ' ').count();
+                if alnum_count as f32 / potential_group.len() as f32 > 0.7 {
+                    // Exclude common metadata tags
+                    let metadata_tags = ["AVC", "GB", "1080P", "720p", "WEB-DL", "WEBDL", "WEBRiP", "BluRay", "x264", "x265", "h264", "h265", "HEVC", "AAC", "AC3", "DTS", "MultiSub", "Multi-Subs"];
+                    if !metadata_tags.iter().any(|&tag| potential_group.eq_ignore_ascii_case(tag)) {
+                        return Some((potential_group.to_string(), trimmed_span(cap.get(1).unwrap()), Rule("group.leading_bracket", 0.8)));
                     }
                 }
             }
@@ -335,18 +564,17 @@ impl ReleaseParser {
         
         // Check for group in brackets at the end: [AnoZu], [RSG], etc.
         // This should be checked BEFORE checking for dashes to avoid conflicts
-        if let Ok(re) = Regex::new(r"\[([^\]]+)\]$") {
-            if let Some(cap) = re.captures(release_name) {
-                let potential_group = cap.get(1).unwrap().as_str().trim();
-                // Check if it looks like a release group (not metadata)
-                if potential_group.len() >= 2 && potential_group.len() <= 30 {
-                    let alnum_count = potential_group.chars().filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_').count();
-                    if alnum_count as f32 / potential_group.len() as f32 > 0.7 {
-                        // Exclude common metadata tags
-                        let metadata_tags = vec!["AVC", "GB", "1080P", "720p", "WEB-DL", "WEBDL", "WEBRiP", "BluRay", "x264", "x265", "h264", "h265", "HEVC", "AAC", "AC3", "DTS", "MultiSub", "Multi-Subs", "H264", "AAC 2.0"];
-                        if !metadata_tags.iter().any(|&tag| potential_group.eq_ignore_ascii_case(tag)) {
-                            return Some(potential_group.to_string());
-                        }
+        let re = regex!(r"\[([^\]]+)\]$");
+        if let Some(cap) = re.captures(release_name) {
+            let potential_group = cap.get(1).unwrap().as_str().trim();
+            // Check if it looks like a release group (not metadata)
+            if potential_group.len() >= 2 && potential_group.len() <= 30 {
+                let alnum_count = potential_group.chars().filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_').count();
+                if alnum_count as f32 / potential_group.len() as f32 > 0.7 {
+                    // Exclude common metadata tags
+                    let metadata_tags = ["AVC", "GB", "1080P", "720p", "WEB-DL", "WEBDL", "WEBRiP", "BluRay", "x264", "x265", "h264", "h265", "HEVC", "AAC", "AC3", "DTS", "MultiSub", "Multi-Subs", "H264", "AAC 2.0"];
+                    if !metadata_tags.iter().any(|&tag| potential_group.eq_ignore_ascii_case(tag)) {
+                        return Some((potential_group.to_string(), trimmed_span(cap.get(1).unwrap()), Rule("group.trailing_bracket", 0.8)));
                     }
                 }
             }
@@ -357,127 +585,137 @@ impl ReleaseParser {
             let group = &release_name[last_dash + 1..];
             // Remove file extension if present
             let group_clean = group.split('.').next().unwrap_or(group);
-            if !group_clean.is_empty() && group_clean.len() < 50 {
-                return Some(group_clean.to_string());
-            }
-        }
-        // Some old formats have group at the end without a dash (e.g., "WahDee")
-        // Try to extract the last word that looks like a group name
-        if let Ok(re) = Regex::new(r"\.([A-Z][a-zA-Z0-9]{2,15})$") {
-            if let Some(cap) = re.captures(release_name) {
-                let potential_group = cap.get(1).unwrap().as_str();
-                // Check if it's not a common word/format
-                let not_group = vec!["DVDRip", "x264", "x265", "AC3", "DTS", "AAC", "MP3", "FLAC", "HEVC", "AVC"];
-                if !not_group.iter().any(|&word| potential_group.eq_ignore_ascii_case(word)) {
-                    return Some(potential_group.to_string());
-                }
+            // The dash of "Show - S01E01" separates, it doesn't lead a group,
+            // and the "3" of "Season.1-3" ends a season range
+            let separates = group.starts_with(char::is_whitespace);
+            let season_range = regex!(r"(?i)\b(?:S|Seasons?[. _]?)\d{1,2}$").is_match(&release_name[..last_dash]) && regex!(r"(?i)^S?\d{1,2}$").is_match(group_clean);
+            if !separates && !season_range && !group_clean.is_empty() && group_clean.len() < 50 && !group_clean.contains([']', '[']) {
+                let start = last_dash + 1;
+                return Some((group_clean.to_string(), start..start + group_clean.len(), Rule("group.dash_suffix", 0.9)));
             }
         }
         None
     }
 
-    fn extract_season_episode(&self, release_name: &str) -> Option<(u128, u128, Vec<u128>)> {
+    /// Some old formats have the group at the end without a dash (e.g.,
+    /// "XviD.WahDee"). Only a last word no other field took, right after a
+    /// tag, is the group: the "S01E01" of "Show.S01E01" and the "Series" of
+    /// "Show.Complete.Series" are not.
+    fn extract_trailing_group(&self, release_name: &str, analysis: &Analysis) -> Found<String> {
+        let re = regex!(r"\.([A-Z][a-zA-Z0-9]{2,15})$");
+        let cap = re.captures(release_name)?;
+        let potential_group = cap.get(1).unwrap();
+        // Check if it's not a common word/format
+        let not_group = ["DVDRip", "x264", "x265", "AC3", "DTS", "AAC", "MP3", "FLAC", "HEVC", "AVC"];
+        if not_group.iter().any(|&word| potential_group.as_str().eq_ignore_ascii_case(word)) {
+            return None;
+        }
+        let index = analysis.tokens.iter().position(|token| token.span == potential_group.range())?;
+        let after_tag = index.checked_sub(1).and_then(|before| analysis.owners[before]).is_some_and(|owner| TAG_FIELDS.contains(&owner));
+        (after_tag && analysis.owners[index].is_none()).then(|| (potential_group.as_str().to_string(), potential_group.range(), Rule("group.trailing_word", 0.4)))
+    }
+
+    fn extract_season_episode(&self, release_name: &str) -> Candidates<(Option<u128>, u128, Vec<u128>)> {
+        let mut candidates = Vec::new();
         // Match patterns like S01E01, S1E1, 1x01, 1x1, etc.
         // Also handle multiple episodes: S01E01-E02, S01E01-E03 (extract range)
         
-        // First, try to match episode-only format: E01E02 (no season, multiple episodes)
-        // This must be checked before single episode patterns to avoid partial matches
-        if let Ok(re) = Regex::new(r"(?i)E(\d{1,3})E(\d{1,3})") {
-            if let Some(caps) = re.captures(release_name) {
-                if let (Ok(ep1), Ok(ep2)) = (
-                    caps.get(1).unwrap().as_str().parse::<u128>(),
-                    caps.get(2).unwrap().as_str().parse::<u128>(),
-                ) {
-                    // Multiple episodes, no season
-                    let episodes = vec![ep1, ep2];
-                    return Some((0u128, ep1, episodes)); // Season 0 indicates no season
+        // First, try multi-episode markers, before single episode patterns take their first episode:
+        // S01E01E02E03, S01E01-E03, S01E01-03, S01E01+E02, S01E01.E02, 1x01-1x03, 1x01x02
+        let next_episode = regex!(r"(?i)([-+.x]?)E?(?:(\d{1,2})x)?(\d{1,3})");
+        let patterns = [
+            (regex!(r"(?i)\bS(\d{1,2})E(\d{1,3})((?:(?:[-+.]?E|[-+])\d{1,3})+)\b"), Rule("season_episode.multi", 0.95)),
+            (regex!(r"(?i)\b(\d{1,2})x(\d{1,3})((?:(?:[-+](?:\d{1,2}x)?|x)\d{1,3})+)\b"), Rule("season_episode.multi_nxnn", 0.8)),
+        ];
+        for (re, rule) in patterns {
+            for caps in re.captures_iter(release_name) {
+                let (season, first) = (caps[1].parse::<u128>().unwrap_or(0), caps[2].parse::<u128>().unwrap_or(0));
+                // 1x01-1x03 stays in its season
+                let rest: Option<Vec<(bool, u128)>> = next_episode
+                    .captures_iter(&caps[3])
+                    .map(|next| {
+                        let same_season = next.get(2).is_none_or(|other| other.as_str().parse() == Ok(season));
+                        same_season.then(|| (&next[1] == "-", next[3].parse().unwrap_or(0)))
+                    })
+                    .collect();
+                if let Some(episodes) = rest.and_then(|rest| multi_episodes(first, rest)) {
+                    candidates.push(((Some(season), first, episodes), caps.get(0).unwrap().range(), rule));
                 }
             }
         }
+
+        // Episode-only chain: E01E02 (no season, multiple episodes)
+        let re = regex!(r"(?i)\bE(\d{1,3})((?:[-+]?E\d{1,3})+)\b");
+        for caps in re.captures_iter(release_name) {
+            let first = caps[1].parse::<u128>().unwrap_or(0);
+            let rest = next_episode.captures_iter(&caps[2]).map(|next| (&next[1] == "-", next[3].parse().unwrap_or(0)));
+            if let Some(episodes) = multi_episodes(first, rest) {
+                candidates.push(((None, first, episodes), caps.get(0).unwrap().range(), Rule("season_episode.double_episode", 0.8)));
+            }
+        }
         
         // Handle episode-only format: E780 (single episode, no season, 3+ digits)
         // Pattern: E780, E123, etc. (must be standalone, not part of S01E01)
         // Note: Only handle if <= 255, numbers > 255 are handled separately in parse()
-        if let Ok(re) = Regex::new(r"(?i)\bE(\d{3})\b") {
-            if let Some(caps) = re.captures(release_name) {
-                // Make sure it's not part of S01E01 pattern (already handled above)
-                // Check if there's no S\d+ before this E\d+
-                let ep_start = caps.get(0).unwrap().start();
-                // Look backwards to see if there's S\d+ pattern
-                let before = &release_name[..ep_start];
-                if !Regex::new(r"(?i)S\d+\s*$").unwrap_or_else(|_| Regex::new(r".").unwrap()).is_match(before) {
-                    if let Ok(episode) = caps.get(1).unwrap().as_str().parse::<u128>() {
-                        // Single episode, no season
-                        return Some((0u128, episode, vec![episode])); // Season 0 indicates no season
-                    }
+        let re = regex!(r"(?i)\bE(\d{3})\b");
+        if let Some(caps) = re.captures(release_name) {
+            // Make sure it's not part of S01E01 pattern (already handled above)
+            // Check if there's no S\d+ before this E\d+
+            let ep_start = caps.get(0).unwrap().start();
+            // Look backwards to see if there's S\d+ pattern, or an episode it continues (S01E01-E999)
+            let before = &release_name[..ep_start];
+            if !regex!(r"(?i)(?:S\d+|E\d+[-+.]?)\s*$").is_match(before) {
+                if let Ok(episode) = caps.get(1).unwrap().as_str().parse::<u128>() {
+                    // Single episode, no season
+                    candidates.push(((None, episode, vec![episode]), caps.get(0).unwrap().range(), Rule("season_episode.absolute_episode", 0.7)));
                 }
             }
         }
         
-        // Second, try to match range pattern: S01E01-E03
-        if let Ok(re) = Regex::new(r"(?i)S(\d{1,2})E(\d{1,3})-E(\d{1,3})") {
+        // Match single episode patterns: S01E01, S1E1, 1x01, 1x1, etc.
+        let patterns = [
+            (regex!(r"(?i)S(\d{1,2})E(\d{1,3})"), Rule("season_episode.sxxexx", 0.95)),  // S01E01
+            (regex!(r"(?i)(\d{1,2})x(\d{1,3})"), Rule("season_episode.nxnn", 0.75)),
+            (regex!(r"(?i)Season\s*(\d{1,2})\s*Episode\s*(\d{1,3})"), Rule("season_episode.verbose", 0.9)),
+        ];
+
+        for (re, rule) in patterns {
             if let Some(caps) = re.captures(release_name) {
-                if let (Ok(season), Ok(ep_start), Ok(ep_end)) = (
+                if let (Ok(season), Ok(episode)) = (
                     caps.get(1).unwrap().as_str().parse::<u128>(),
                     caps.get(2).unwrap().as_str().parse::<u128>(),
-                    caps.get(3).unwrap().as_str().parse::<u128>(),
                 ) {
-                    // Generate episode range
-                    let mut episodes = Vec::new();
-                    for ep in ep_start..=ep_end {
-                        episodes.push(ep);
-                    }
-                    return Some((season, ep_start, episodes));
-                }
-            }
-        }
-        
-        // Match single episode patterns: S01E01, S1E1, 1x01, 1x1, etc.
-        let patterns = vec![
-            r"(?i)S(\d{1,2})E(\d{1,3})",  // S01E01
-            r"(?i)(\d{1,2})x(\d{1,3})",
-            r"(?i)Season\s*(\d{1,2})\s*Episode\s*(\d{1,3})",
-        ];
-
-        for pattern in patterns {
-            if let Ok(re) = Regex::new(pattern) {
-                if let Some(caps) = re.captures(release_name) {
-                    if let (Ok(season), Ok(episode)) = (
-                        caps.get(1).unwrap().as_str().parse::<u128>(),
-                        caps.get(2).unwrap().as_str().parse::<u128>(),
-                    ) {
-                        return Some((season, episode, vec![episode]));
-                    }
+                    candidates.push(((Some(season), episode, vec![episode]), caps.get(0).unwrap().range(), rule));
                 }
             }
         }
         
         // Handle anime format: "S5 - 02" (S followed by season - episode)
-        if let Ok(re) = Regex::new(r"(?i)S(\d{1,2})\s*-\s*(\d{1,3})") {
-            if let Some(caps) = re.captures(release_name) {
-                if let (Ok(season), Ok(episode)) = (
-                    caps.get(1).unwrap().as_str().parse::<u128>(),
-                    caps.get(2).unwrap().as_str().parse::<u128>(),
-                ) {
-                    // Only use this if season is reasonable (1-20) and episode is reasonable (1-200)
-                    if (1u128..=20u128).contains(&season) && (1u128..=200u128).contains(&episode) {
-                        return Some((season, episode, vec![episode]));
-                    }
+        let re = regex!(r"(?i)S(\d{1,2})\s*-\s*(\d{1,3})");
+        if let Some(caps) = re.captures(release_name) {
+            if let (Ok(season), Ok(episode)) = (
+                caps.get(1).unwrap().as_str().parse::<u128>(),
+                caps.get(2).unwrap().as_str().parse::<u128>(),
+            ) {
+                // Only use this if season is reasonable (1-20) and episode is reasonable (1-200)
+                if (1u128..=20u128).contains(&season) && (1u128..=200u128).contains(&episode) {
+                    candidates.push(((Some(season), episode, vec![episode]), caps.get(0).unwrap().range(), Rule("season_episode.anime_season_dash", 0.6)));
                 }
             }
         }
         
         // Handle anime format: "5 - 01" (season - episode)
-        if let Ok(re) = Regex::new(r"(\d{1,2})\s*-\s*(\d{1,3})") {
-            if let Some(caps) = re.captures(release_name) {
-                if let (Ok(season), Ok(episode)) = (
-                    caps.get(1).unwrap().as_str().parse::<u128>(),
-                    caps.get(2).unwrap().as_str().parse::<u128>(),
-                ) {
-                    // Only use this if season is reasonable (1-20) and episode is reasonable (1-200)
-                    if (1u128..=20u128).contains(&season) && (1u128..=200u128).contains(&episode) {
-                        return Some((season, episode, vec![episode]));
-                    }
+        let re = regex!(r"(\d{1,2})\s*-\s*(\d{1,3})");
+        if let Some(caps) = re.captures(release_name) {
+            if let (Ok(season), Ok(episode)) = (
+                caps.get(1).unwrap().as_str().parse::<u128>(),
+                caps.get(2).unwrap().as_str().parse::<u128>(),
+            ) {
+                // Only use this if season is reasonable (1-20) and episode is reasonable (1-200)
+                if (1u128..=20u128).contains(&season) && (1u128..=200u128).contains(&episode) {
+                    candidates.push(((Some(season), episode, vec![episode]), caps.get(0).unwrap().range(), Rule("season_episode.anime_dash", 0.35)));
+                    // The other reading of the same words: absolute episode 5
+                    candidates.push(((None, season, vec![season]), caps.get(0).unwrap().range(), Rule("season_episode.anime_dash_absolute", 0.3)));
                 }
             }
         }
@@ -491,339 +729,391 @@ impl ReleaseParser {
         // Note: "Episode 61" format is handled separately via extract_episode_number
         // to avoid setting season when it's not present in the title
         
-        None
+        candidates
+    }
+
+    /// Seasons of a season pack: ranges first, then single seasons that no
+    /// episode follows. A range counting down, `Season.3-1`, has no seasons
+    /// but is still taken, so it is neither an episode nor part of the title.
+    fn extract_season_pack(&self, release_name: &str) -> Candidates<Vec<u128>> {
+        let mut candidates = Vec::new();
+        let ranges = [
+            (regex!(r"(?i)\bS(\d{1,2})[. _]?-[. _]?S(\d{1,2})\b"), Rule("seasons.range", 0.9)),
+            (regex!(r"(?i)\b(?:Seasons?|Staffel|Saison)[. _]?(\d{1,2})[. _]*(?:-|to)[. _]*(\d{1,2})\b"), Rule("seasons.word_range", 0.9)),
+        ];
+        for (re, rule) in ranges {
+            for caps in re.captures_iter(release_name) {
+                let (first, last) = (caps[1].parse::<u128>().unwrap_or(0), caps[2].parse::<u128>().unwrap_or(0));
+                if first < last {
+                    candidates.push(((first..=last).collect(), caps.get(0).unwrap().range(), rule));
+                } else if first > last {
+                    candidates.push((Vec::new(), caps.get(0).unwrap().range(), Rule("seasons.descending_range", 0.2)));
+                }
+            }
+        }
+
+        // "S01 - 05" is episode 5 of season 1, "Season 1 Episode 2" a single episode
+        let episode_follows = regex!(r"(?i)^(?:[. _]?E\d|[. _-]*Episode\b|[. _-]*Folge\b|\s*-\s*\d)");
+        let singles = [
+            (regex!(r"(?i)\bS(\d{1,2})\b"), Rule("seasons.single", 0.8)),
+            (regex!(r"(?i)\b(?:Season|Staffel|Saison)[. _]?(\d{1,2})\b"), Rule("seasons.word", 0.8)),
+        ];
+        for (re, rule) in singles {
+            for caps in re.captures_iter(release_name) {
+                let found = caps.get(0).unwrap();
+                if !episode_follows.is_match(&release_name[found.end()..]) {
+                    candidates.push((vec![caps[1].parse::<u128>().unwrap_or(0)], found.range(), rule));
+                }
+            }
+        }
+        candidates
     }
 
-    fn extract_year(&self, release_name: &str) -> Option<u16> {
+    /// Specials, OVAs and extras tagged as such, with their number.
+    fn extract_special_tag(&self, release_name: &str) -> Candidates<(SpecialKind, Option<u128>)> {
+        let mut candidates = Vec::new();
+        let tags = [
+            (regex!(r"(?i)\bOVA(?:[. _]?(\d{1,3}))?\b"), SpecialKind::Ova),
+            (regex!(r"(?i)\bOAD(?:[. _]?(\d{1,3}))?\b"), SpecialKind::Oad),
+            (regex!(r"(?i)\bONA(?:[. _]?(\d{1,3}))?\b"), SpecialKind::Ona),
+            (regex!(r"(?i)\b(?:NC[. _-]?OP|Creditless[. _]Op(?:ening)?)(?:[. _]?(\d{1,2}))?\b"), SpecialKind::CreditlessOpening),
+            (regex!(r"(?i)\b(?:NC[. _-]?ED|Creditless[. _]End(?:ing)?)(?:[. _]?(\d{1,2}))?\b"), SpecialKind::CreditlessEnding),
+            (regex!(r"(?i)\bSP[. _]?(\d{1,2})\b"), SpecialKind::Special),
+        ];
+        for (re, kind) in tags {
+            for caps in re.captures_iter(release_name) {
+                let number = caps.get(1).and_then(|number| number.as_str().parse().ok());
+                candidates.push(((kind, number), caps.get(0).unwrap().range(), Rule("special_kind.tag", 0.9)));
+            }
+        }
+        candidates
+    }
+
+    /// Specials and extras named by a word that may also belong to a title:
+    /// `Special`, `Recap`, `Pilot` or `Preview`. They only count after the
+    /// episode marker when there is one, as `Special.Victims.Unit.S01E01` is a title.
+    fn extract_special_word(&self, release_name: &str, episode_start: Option<usize>) -> Found<(SpecialKind, Option<u128>)> {
+        let words = [
+            (regex!(r"(?i)\bSpecials?(?:[. _](\d{1,2}))?\b"), SpecialKind::Special),
+            (regex!(r"(?i)\bRecap\b"), SpecialKind::Recap),
+            (regex!(r"(?i)\bPilot\b"), SpecialKind::Pilot),
+            (regex!(r"(?i)\bPreview\b"), SpecialKind::Preview),
+        ];
+        words.into_iter().find_map(|(re, kind)| {
+            let caps = re.captures_iter(release_name).find(|caps| episode_start.is_none_or(|start| caps.get(0).unwrap().start() >= start))?;
+            let number = caps.get(1).and_then(|number| number.as_str().parse().ok());
+            Some(((kind, number), caps.get(0).unwrap().range(), Rule("special_kind.word", 0.7)))
+        })
+    }
+
+    /// `Complete Series`, both words, so neither is left in the title.
+    fn extract_complete_series(&self, release_name: &str) -> Found<PackKind> {
+        let re = regex!(r"(?i)\bComplete[. _-](?:Series|Serie)\b");
+        let series = re.find(release_name)?;
+        Some((PackKind::CompleteSeries, series.range(), Rule("pack.complete_series", 0.9)))
+    }
+
+    fn extract_year(&self, release_name: &str) -> Candidates<u16> {
+        let mut candidates = Vec::new();
         // First try parentheses format: (2023)
-        if let Ok(re) = Regex::new(r"\((\d{4})\)") {
-            if let Some(cap) = re.captures(release_name) {
-                if let Ok(year) = cap.get(1).unwrap().as_str().parse::<u16>() {
-                    if (1900..=2100).contains(&year) {
-                        return Some(year);
-                    }
+        let re = regex!(r"\((\d{4})\)");
+        if let Some(cap) = re.captures(release_name) {
+            if let Ok(year) = cap.get(1).unwrap().as_str().parse::<u16>() {
+                if (1900..=2100).contains(&year) {
+                    candidates.push((year, cap.get(1).unwrap().range(), Rule("year.parentheses", 0.95)));
                 }
             }
         }
         
         // Try square brackets format: [2006]
-        if let Ok(re) = Regex::new(r"\[(\d{4})\]") {
-            if let Some(cap) = re.captures(release_name) {
-                if let Ok(year) = cap.get(1).unwrap().as_str().parse::<u16>() {
-                    if (1900..=2100).contains(&year) {
-                        return Some(year);
-                    }
+        let re = regex!(r"\[(\d{4})\]");
+        if let Some(cap) = re.captures(release_name) {
+            if let Ok(year) = cap.get(1).unwrap().as_str().parse::<u16>() {
+                if (1900..=2100).contains(&year) {
+                    candidates.push((year, cap.get(1).unwrap().range(), Rule("year.brackets", 0.9)));
                 }
             }
         }
         
-        // Fall back to standard format: 2023
-        if let Ok(re) = Regex::new(r"\b(19|20|21)\d{2}\b") {
-            for cap in re.captures_iter(release_name) {
-                if let Ok(year) = cap.get(0).unwrap().as_str().parse::<u16>() {
-                    if (1900..=2100).contains(&year) {
-                        return Some(year);
-                    }
+        // Fall back to standard format: 2023. A number opening the name is
+        // the title when another year follows: "2001.A.Space.Odyssey.1968"
+        let re = regex!(r"\b(19|20|21)\d{2}\b");
+        let mut bare = Vec::new();
+        for cap in re.captures_iter(release_name) {
+            if let Ok(year) = cap.get(0).unwrap().as_str().parse::<u16>() {
+                if (1900..=2100).contains(&year) {
+                    bare.push((year, cap.get(0).unwrap().range(), Rule("year.bare", 0.7)));
                 }
             }
         }
-        None
+        bare.sort_by_key(|(_, span, _)| span.start == 0);
+        candidates.extend(bare);
+        candidates
     }
 
-    fn extract_source(&self, release_name: &str) -> String {
+    fn extract_source(&self, release_name: &str) -> Candidates<String> {
+        let mut candidates = Vec::new();
         // First try bracket format: [Bluray-1080p Remux], [WEBDL-2160p]
         // Check for Remux first (highest priority) - must be in brackets
         // Match any bracket that contains "Remux"
-        if let Ok(re) = Regex::new(r"\[[^\]]*Remux[^\]]*\]") {
-            if re.is_match(release_name) {
-                return "Remux".to_string();
+        let re = regex!(r"\[[^\]]*Remux[^\]]*\]");
+        if let Some(found) = re.find(release_name) {
+            if let Some(span) = span_of(found.as_str(), "Remux", found.start()) {
+                candidates.push(("Remux".to_string(), span, Rule("source.remux_bracket", 0.95)));
             }
         }
         // Also check old format: Remux-2160p
-        if release_name.contains("Remux-") || release_name.contains(".Remux-") {
-            return "Remux".to_string();
+        if let Some(span) = span_of(release_name, "Remux-", 0) {
+            candidates.push(("Remux".to_string(), span.start..span.end - 1, Rule("source.remux_suffix", 0.9)));
         }
         
         // Extract all bracket contents and check them (but skip if Remux was already found)
-        if let Ok(re) = Regex::new(r"\[([^\]]+?)(?:-\d+p)") {
-            for cap in re.captures_iter(release_name) {
-                let bracket_content = cap.get(1).unwrap().as_str();
-                // Normalize common variations
-                // Note: AMZN and MA are streaming providers, not sources - handle them separately
-                if bracket_content.contains("MA WEBDL") || bracket_content.contains("MA.WEBDL") {
-                    return "MA WEBDL".to_string();
-                }
-                if bracket_content.contains("iNTERNAL") {
-                    return "iNTERNAL".to_string();
-                }
-                // For AMZN WEBDL, extract just WEBDL as source (AMZN goes to streaming_provider)
-                if bracket_content.contains("AMZN WEBDL") {
-                    return "WEBDL".to_string();
+        let re = regex!(r"\[([^\]]+?)(?:-\d+p)");
+        for cap in re.captures_iter(release_name) {
+            let content = cap.get(1).unwrap();
+            let bracket_content = content.as_str();
+            let offset = content.start();
+            // Normalize common variations
+            // Note: AMZN and MA are streaming providers, not sources - handle them separately
+            for needle in ["MA WEBDL", "MA.WEBDL"] {
+                if let Some(span) = span_of(bracket_content, needle, offset) {
+                    candidates.push(("MA WEBDL".to_string(), span, Rule("source.bracket", 0.9)));
                 }
-                if bracket_content.contains("WEBDL") || bracket_content.contains("WEB-DL") {
-                    return "WEB-DL".to_string();
+            }
+            // For AMZN WEBDL, extract just WEBDL as source (AMZN goes to streaming_provider)
+            if let Some(span) = span_of(bracket_content, "AMZN WEBDL", offset) {
+                candidates.push(("WEBDL".to_string(), span.start + "AMZN ".len()..span.end, Rule("source.bracket", 0.9)));
+            }
+            for needle in ["WEBDL", "WEB-DL"] {
+                if let Some(span) = span_of(bracket_content, needle, offset) {
+                    candidates.push(("WEB-DL".to_string(), span, Rule("source.bracket", 0.9)));
                 }
-                if bracket_content.contains("Bluray") || bracket_content.contains("BluRay") {
-                    // Only return BluRay if Remux wasn't found in this bracket
+            }
+            for needle in ["Bluray", "BluRay"] {
+                // Only return BluRay if Remux wasn't found in this bracket
+                if let Some(span) = span_of(bracket_content, needle, offset) {
                     if !bracket_content.contains("Remux") {
-                        return "BluRay".to_string();
+                        candidates.push(("BluRay".to_string(), span, Rule("source.bracket", 0.9)));
                     }
                 }
             }
         }
         
         // Check for MA.WEBDL in old format
-        if release_name.contains("MA.WEBDL") || release_name.contains("MA WEBDL") {
-            return "MA WEBDL".to_string();
+        for needle in ["MA.WEBDL", "MA WEBDL"] {
+            if let Some(span) = span_of(release_name, needle, 0) {
+                candidates.push(("MA WEBDL".to_string(), span, Rule("source.ma_webdl", 0.85)));
+            }
         }
         
         // Fall back to standard format (but skip if Remux was found in brackets)
-        // Based on: https://en.wikipedia.org/wiki/Pirated_movie_release_types
-        let sources = vec![
-            // DVD sources
-            "DVDRip", "DVD-Rip", "DVDR", "DVD5", "DVD9", "DVD-R", "DvDrip",
-            // Web sources
-            "WEB-DL", "WEBRip", "Web Rip", "Web Download", "WEB", "WEBDL", "AMZN WEBDL", "MA WEBDL",
-            // TV sources
-            "HDTV", "PDTV", "DSR", "SATRip", "TVRip", "iNTERNAL HDTV", "iNTERNAL",
-            // Blu-ray sources
-            "BluRay", "BDRip", "BRRip", "BD",
-            // Other sources
-            "VHSRip", "R5", "TC", "TS", "CAM", "SCR",
-            "HDCAM", "TELESYNC", "TELECINE", "Remux",
-            // Additional sources from Wikipedia
-            "Workprint", "WP", "PPV Rip", "PPVRip", "DDC",
-            "VOD Rip", "VODRip", "HC HD Rip", "HCHDRip",
-            "Web Capture", "HDRip", "DCP", "Theatre", "Theater",
-        ];
-
-        let remux_re = Regex::new(r"\[[^\]]*Remux[^\]]*\]").ok();
-        for source in &sources {
+        // All source patterns are tested in one pass; the first listed match wins
+        let remux_re = regex!(r"\[[^\]]*Remux[^\]]*\]");
+        for (index, found) in rules::SOURCE_TABLE.matches(release_name) {
+            let source = rules::SOURCES[index];
             // Skip BluRay if Remux was found in brackets
-            if *source == "BluRay" && release_name.contains("[") {
-                if let Some(ref re) = remux_re {
-                    if re.is_match(release_name) {
-                        continue;
-                    }
-                }
-            }
-            // Case-insensitive matching
-            if let Ok(re) = Regex::new(&format!(r"(?i){}", regex::escape(source))) {
-                if re.is_match(release_name) {
-                    return source.to_string();
-                }
+            if source == "BluRay" && release_name.contains("[") && remux_re.is_match(release_name) {
+                continue;
             }
+            candidates.push((source.to_string(), found.range(), Rule("source.keyword", 0.7)));
         }
-        String::new()
+        candidates
     }
 
-    fn extract_format(&self, release_name: &str) -> String {
+    fn extract_format(&self, release_name: &str) -> Found<String> {
         // First try bracket format: [AVC], [h265]
-        if let Ok(re) = Regex::new(r"\[([A-Za-z0-9]+)\]") {
-            for cap in re.captures_iter(release_name) {
-                let format_str = cap.get(1).unwrap().as_str();
-                // Check if it's a known format
-                let known_formats = vec!["AVC", "h265", "h264", "HEVC", "H264", "x265", "x264"];
-                for fmt in &known_formats {
-                    if format_str.eq_ignore_ascii_case(fmt) {
-                        return fmt.to_string();
-                    }
+        let re = regex!(r"\[([A-Za-z0-9]+)\]");
+        for cap in re.captures_iter(release_name) {
+            let format_str = cap.get(1).unwrap().as_str();
+            // Check if it's a known format
+            let known_formats = ["AVC", "h265", "h264", "HEVC", "H264", "x265", "x264"];
+            for fmt in &known_formats {
+                if format_str.eq_ignore_ascii_case(fmt) {
+                    return Some((fmt.to_string(), cap.get(1).unwrap().range(), Rule("format.bracket", 0.95)));
                 }
             }
         }
         
         // Check for H.264 or H.265 format (with dot)
-        if let Ok(re) = Regex::new(r"(?i)H\.(264|265)") {
-            if let Some(caps) = re.captures(release_name) {
-                let version = caps.get(1).unwrap().as_str();
-                return format!("H.{}", version);
-            }
+        let re = regex!(r"(?i)H\.(264|265)");
+        if let Some(caps) = re.captures(release_name) {
+            let version = caps.get(1).unwrap().as_str();
+            return Some((format!("H.{}", version), caps.get(0).unwrap().range(), Rule("format.dotted", 0.9)));
         }
         
         // Fall back to standard format
-        let formats = vec![
+        let formats = [
             "SVCD", "VCD", "XviD", "DivX", "x264", "x265", "HEVC",
             "H264", "AVC", "MPEG2", "MPEG4", "h265", "h264",
         ];
 
         for format in &formats {
-            if release_name.contains(format) {
-                return format.to_string();
+            if let Some(span) = span_of(release_name, format, 0) {
+                return Some((format.to_string(), span, Rule("format.substring", 0.6)));
             }
         }
-        String::new()
+        None
     }
 
-    fn extract_resolution(&self, release_name: &str) -> String {
+    fn extract_resolution(&self, release_name: &str) -> Candidates<String> {
+        let mut candidates = Vec::new();
         // First try bracket format: [Remux-1080p], [Bluray-2160p]
-        if let Ok(re) = Regex::new(r"\[[^\]]*-(\d{3,4})p") {
-            if let Some(cap) = re.captures(release_name) {
-                return format!("{}p", cap.get(1).unwrap().as_str());
-            }
+        let re = regex!(r"\[[^\]]*-(\d{3,4})p");
+        if let Some(cap) = re.captures(release_name) {
+            let digits = cap.get(1).unwrap();
+            candidates.push((format!("{}p", digits.as_str()), digits.start()..digits.end() + 1, Rule("resolution.bracket", 0.95)));
         }
         
         // Try parentheses format: (1080p)
-        if let Ok(re) = Regex::new(r"\((\d{3,4})p\)") {
-            if let Some(cap) = re.captures(release_name) {
-                return format!("{}p", cap.get(1).unwrap().as_str());
-            }
+        let re = regex!(r"\((\d{3,4})p\)");
+        if let Some(cap) = re.captures(release_name) {
+            let digits = cap.get(1).unwrap();
+            candidates.push((format!("{}p", digits.as_str()), digits.start()..digits.end() + 1, Rule("resolution.parentheses", 0.95)));
         }
         
         // Fall back to standard format: 1080p, 720p, etc.
-        if let Ok(re) = Regex::new(r"(?i)(\d{3,4})[pi]") {
-            if let Some(cap) = re.captures(release_name) {
-                return format!("{}p", cap.get(1).unwrap().as_str());
-            }
+        let re = regex!(r"(?i)(\d{3,4})[pi]");
+        for cap in re.captures_iter(release_name) {
+            candidates.push((format!("{}p", cap.get(1).unwrap().as_str()), cap.get(0).unwrap().range(), Rule("resolution.bare", 0.85)));
         }
-        String::new()
+        candidates
     }
 
-    fn extract_audio(&self, release_name: &str) -> String {
+    fn extract_audio(&self, release_name: &str) -> Found<String> {
         // First try bracket format: [TrueHD 5.1], [AC3 2.0], [DTS-HD MA 5.1], [EAC3 Atmos 5.1]
         // Match all bracket patterns and find the one that looks like audio (has format + channels)
-        let audio_format_names = vec!["TrueHD", "DTS-HD MA", "DTS-HD", "EAC3 Atmos", "EAC3", "AC3", "AAC", "MP3", "FLAC", "DDP", "Dolby Digital Plus"];
-        
-        if let Ok(re) = Regex::new(r"\[([^\]]+)\]") {
-            for cap in re.captures_iter(release_name) {
-                let bracket_content = cap.get(1).unwrap().as_str().trim();
-                
-                // Check if bracket contains a known audio format (check longer names first)
-                for format_name in &audio_format_names {
-                    if bracket_content.contains(format_name) {
-                        // Try to extract format + channels - match from the format name onwards
-                        if let Ok(audio_re) = Regex::new(&format!(r"({})\s+(\d+\.\d+)", regex::escape(format_name))) {
-                            if let Some(audio_caps) = audio_re.captures(bracket_content) {
-                                let format = audio_caps.get(1).unwrap().as_str().trim();
-                                let channels = audio_caps.get(2).unwrap().as_str();
-                                return format!("{} {}", format, channels);
-                            }
-                        }
-                        // If no channels found, just return the format name
-                        return format_name.to_string();
+        let re = regex!(r"\[([^\]]+)\]");
+        for cap in re.captures_iter(release_name) {
+            let content = trimmed_span(cap.get(1).unwrap());
+            let bracket_content = &release_name[content.clone()];
+            
+            // Check if bracket contains a known audio format (check longer names first)
+            for (format_name, audio_re) in rules::AUDIO_FORMAT_NAMES.iter().zip(rules::AUDIO_CHANNEL_RES.iter()) {
+                if let Some(span) = span_of(bracket_content, format_name, content.start) {
+                    // Try to extract format + channels - match from the format name onwards
+                    if let Some(audio_caps) = audio_re.captures(bracket_content) {
+                        let format = audio_caps.get(1).unwrap().as_str().trim();
+                        let channels = audio_caps.get(2).unwrap().as_str();
+                        let found = audio_caps.get(0).unwrap();
+                        return Some((format!("{} {}", format, channels), content.start + found.start()..content.start + found.end(), Rule("audio.bracket_channels", 0.9)));
                     }
+                    // If no channels found, just return the format name
+                    return Some((format_name.to_string(), span, Rule("audio.bracket", 0.8)));
                 }
             }
         }
         
         // Fall back to standard format - check for patterns like AAC2.0, AAC 2.0, AAC2.0, DDP5.1, DDP2.0
         // Check both with space and without space/dot
-        if let Ok(re) = Regex::new(r"(?i)(AAC|AC3|DTS|MP3|FLAC|TrueHD|EAC3|DDP|Dolby Digital Plus)(\s+|\.?)(\d+\.\d+)") {
-            if let Some(caps) = re.captures(release_name) {
-                let format = caps.get(1).unwrap().as_str();
-                let channels = caps.get(3).unwrap().as_str();
-                // Normalize DDP to "DDP"
-                let format_normalized = if format.eq_ignore_ascii_case("Dolby Digital Plus") {
-                    "DDP"
-                } else {
-                    format
-                };
-                return format!("{} {}", format_normalized, channels);
-            }
+        let re = regex!(r"(?i)(DTS-HD[. ]MA|DTS-HD|(?:TrueHD|EAC3|DDP)[. ]Atmos|AAC|AC3|DTS|MP3|FLAC|TrueHD|EAC3|DDP|Dolby Digital Plus)(\s+|\.?)(\d+\.\d+)");
+        if let Some(caps) = re.captures(release_name) {
+            let format = caps.get(1).unwrap().as_str().replace('.', " ");
+            let format = format.as_str();
+            let channels = caps.get(3).unwrap().as_str();
+            // Normalize DDP to "DDP"
+            let format_normalized = if format.eq_ignore_ascii_case("Dolby Digital Plus") {
+                "DDP"
+            } else {
+                format
+            };
+            return Some((format!("{} {}", format_normalized, channels), caps.get(0).unwrap().range(), Rule("audio.channels", 0.85)));
         }
         
         // Check for standalone audio formats (without version numbers)
         // Match word boundaries to avoid partial matches
-        if let Ok(re) = Regex::new(r"(?i)\b(AAC|AC3|DTS|MP3|FLAC|TrueHD|EAC3|DDP)\b") {
-            if let Some(caps) = re.captures(release_name) {
-                let format = caps.get(1).unwrap().as_str();
-                // Normalize DDP to "DDP"
-                let format_normalized = if format.eq_ignore_ascii_case("Dolby Digital Plus") {
-                    "DDP"
-                } else {
-                    format
-                };
-                return format_normalized.to_string();
-            }
+        let re = regex!(r"(?i)\b(AAC|AC3|DTS|MP3|FLAC|TrueHD|EAC3|DDP)\b");
+        if let Some(caps) = re.captures(release_name) {
+            let format = caps.get(1).unwrap().as_str();
+            // Normalize DDP to "DDP"
+            let format_normalized = if format.eq_ignore_ascii_case("Dolby Digital Plus") {
+                "DDP"
+            } else {
+                format
+            };
+            return Some((format_normalized.to_string(), caps.get(1).unwrap().range(), Rule("audio.keyword", 0.75)));
         }
         
         // Fall back to standard format - but only if we didn't find bracket format
         // Check if there are brackets first - if so, don't use fallback
         if !release_name.contains('[') {
-            let audio_formats = vec![
+            let audio_formats = [
                 "AC3", "DTS", "AAC", "MP3", "FLAC", "TrueHD",
                 "DTS-HD", "DTS-HDMA", "DTS-HD MA", "EAC3", "EAC3 Atmos",
                 "DD5.1", "DD2.0", "DDP5.1", "DDP2.0", "DDP", "Dolby Digital Plus",
             ];
 
             for audio in &audio_formats {
-                if release_name.contains(audio) {
-                    return audio.to_string();
+                if let Some(span) = span_of(release_name, audio, 0) {
+                    return Some((audio.to_string(), span, Rule("audio.substring", 0.5)));
                 }
             }
         }
-        String::new()
+        None
     }
 
-    fn extract_device(&self, release_name: &str) -> String {
-        let devices = vec![
+    fn extract_device(&self, release_name: &str) -> Found<String> {
+        let devices = [
             "XBOX", "XBOX360", "XBOXONE", "PS2", "PS3", "PS4", "PS5",
             "Wii", "WiiU", "Switch", "PSP", "NDS", "3DS",
         ];
 
         for device in &devices {
-            if release_name.contains(device) {
-                return device.to_string();
+            if let Some(span) = span_of(release_name, device, 0) {
+                return Some((device.to_string(), span, Rule("device.substring", 0.5)));
             }
         }
-        String::new()
+        None
     }
 
-    fn extract_os(&self, release_name: &str) -> String {
-        let os_list = vec![
+    fn extract_os(&self, release_name: &str) -> Found<String> {
+        let os_list = [
             "Linux", "Windows", "MacOS", "OSX", "Unix",
             "Android", "iOS", "WinXP", "Win7", "Win8", "Win10", "Win11",
         ];
 
         for os in &os_list {
-            if release_name.contains(os) {
-                return os.to_string();
+            if let Some(span) = span_of(release_name, os, 0) {
+                return Some((os.to_string(), span, Rule("os.substring", 0.5)));
             }
         }
-        String::new()
+        None
     }
 
-    fn extract_version(&self, release_name: &str) -> String {
+    fn extract_version(&self, release_name: &str) -> Found<String> {
         // Match version patterns like v1.0, v2.3.1, etc.
-        if let Ok(re) = Regex::new(r"(?i)v(\d+(?:\.\d+)*)") {
-            if let Some(cap) = re.captures(release_name) {
-                return cap.get(1).unwrap().as_str().to_string();
-            }
+        let re = regex!(r"(?i)v(\d+(?:\.\d+)*)");
+        if let Some(cap) = re.captures(release_name) {
+            return Some((cap.get(1).unwrap().as_str().to_string(), cap.get(0).unwrap().range(), Rule("version.prefix", 0.6)));
         }
-        String::new()
+        None
     }
 
-    fn extract_languages(&self, release_name: &str) -> HashMap<String, String> {
-        let mut languages = HashMap::new();
+    /// Languages as `(code, name, span, rule)`; the first entry for a code wins.
+    /// A bare code right before the end of the title is a language too, the
+    /// `DE` of "Show.DE.S01E01".
+    fn extract_languages(&self, release_name: &str, tokens: &[Token], title_end: Option<usize>) -> Vec<(String, String, Range<usize>, Rule)> {
+        let mut languages = Vec::new();
         
         // First check for language codes in brackets: [DE], [JA], [Eng.Hard.Sub]
-        let lang_codes = vec![
-            ("DE", "German"), ("EN", "English"), ("Eng", "English"), ("FR", "French"),
-            ("ES", "Spanish"), ("IT", "Italian"), ("PT", "Portuguese"),
-            ("RU", "Russian"), ("NL", "Dutch"), ("PL", "Polish"),
-            ("SV", "Swedish"), ("NO", "Norwegian"), ("DA", "Danish"),
-            ("FI", "Finnish"), ("JA", "Japanese"), ("ZH", "Chinese"),
-            ("KO", "Korean"), ("AR", "Arabic"), ("TR", "Turkish"),
-        ];
-        
-        let eng_re = Regex::new(r"\[Eng(?:\.Hard\.Sub)?\]").ok();
-        for (code, name) in lang_codes {
-            // Match [CODE] but not [CODE-something] (unless it's a known pattern like Eng.Hard.Sub)
-            if code == "Eng" {
-                // Special handling for [Eng.Hard.Sub] or [Eng]
-                if let Some(ref re) = eng_re {
-                    if re.is_match(release_name) {
-                        languages.insert("en".to_string(), name.to_string());
-                    }
-                }
-            } else if let Ok(re) = Regex::new(&format!(r"\[{}\]", regex::escape(code))) {
-                if re.is_match(release_name) {
-                    languages.insert(code.to_lowercase(), name.to_string());
-                }
+        // Match [CODE] but not [CODE-something] (unless it's a known pattern like Eng.Hard.Sub)
+        for (index, found) in rules::LANGUAGE_CODE_TABLE.matches(release_name) {
+            let (code, name) = rules::LANGUAGE_CODES[index];
+            let key = if code == "Eng" { "en".to_string() } else { code.to_lowercase() };
+            // Only the code itself, "[Eng.Hard.Sub]" also carries a flag
+            let start = found.start() + 1;
+            languages.push((key, name.to_string(), start..start + code.len(), Rule("language.bracket_code", 0.9)));
+        }
+
+        // Not the first word though, that is the title: "DE.Show.S01E01"
+        let title_tokens = title_end.map_or(0, |end| tokens.iter().take_while(|token| token.span.end <= end).count());
+        if let Some(token) = tokens[..title_tokens].last().filter(|_| title_tokens > 1) {
+            if let Some((code, name)) = rules::LANGUAGE_CODES.iter().find(|(code, _)| code.len() == 2 && token.text == *code) {
+                languages.push((code.to_lowercase(), name.to_string(), token.span.clone(), Rule("language.code", 0.6)));
             }
         }
         
         // Fall back to full language names (including variations like NORDiC, SWEDiSH, NORWEGiAN)
-        let lang_map = vec![
+        let lang_map = [
             ("German", "de"), ("GERMAN", "de"), ("English", "en"), ("ENGLISH", "en"),
             ("French", "fr"), ("FRENCH", "fr"), ("Spanish", "es"), ("SPANISH", "es"),
             ("Italian", "it"), ("ITALIAN", "it"), ("iTALiAN", "it"), ("Portuguese", "pt"), ("PORTUGUESE", "pt"),
@@ -837,32 +1127,33 @@ impl ReleaseParser {
         ];
 
         for (lang_name, lang_code) in lang_map {
-            if release_name.contains(lang_name) && !languages.contains_key(lang_code) {
-                languages.insert(lang_code.to_string(), lang_name.to_string());
+            if let Some(span) = span_of(release_name, lang_name, 0) {
+                languages.push((lang_code.to_string(), lang_name.to_string(), span, Rule("language.name", 0.8)));
             }
         }
 
         // Check for multilingual
-        if release_name.contains("Multi") || release_name.contains("MULTI") || 
-           release_name.contains("MultiSub") || release_name.contains("Multi-Subs") {
-            languages.insert("multi".to_string(), "Multilingual".to_string());
+        for needle in ["Multi", "MULTI"] {
+            if let Some(span) = span_of(release_name, needle, 0) {
+                languages.push(("multi".to_string(), "Multilingual".to_string(), span, Rule("language.multi", 0.8)));
+                break;
+            }
         }
         
         // Handle country codes in parentheses: (CA) for Canada, etc.
         // Common country codes that might indicate language/region
-        let country_codes = vec![
+        let country_codes = [
             ("CA", "Canadian"), ("US", "US"), ("UK", "UK"), ("AU", "Australian"),
             ("DE", "German"), ("FR", "French"), ("ES", "Spanish"), ("IT", "Italian"),
             ("JP", "Japanese"), ("CN", "Chinese"), ("KR", "Korean"),
         ];
         
-        if let Ok(re) = Regex::new(r"\(([A-Z]{2})\)") {
-            for cap in re.captures_iter(release_name) {
-                let code = cap.get(1).unwrap().as_str();
-                for (cc, name) in &country_codes {
-                    if code.eq_ignore_ascii_case(cc) {
-                        languages.entry(cc.to_lowercase()).or_insert_with(|| name.to_string());
-                    }
+        let re = regex!(r"\(([A-Z]{2})\)");
+        for cap in re.captures_iter(release_name) {
+            let code = cap.get(1).unwrap().as_str();
+            for (cc, name) in &country_codes {
+                if code.eq_ignore_ascii_case(cc) {
+                    languages.push((cc.to_lowercase(), name.to_string(), cap.get(1).unwrap().range(), Rule("language.country_code", 0.5)));
                 }
             }
         }
@@ -870,948 +1161,564 @@ impl ReleaseParser {
         languages
     }
 
-    fn extract_flags(&self, release_name: &str) -> Vec<String> {
-        let mut flags = Vec::new();
-        
-        // Based on: https://en.wikipedia.org/wiki/Pirated_movie_release_types
-        let flag_patterns = vec![
-            // Release quality flags
-            ("READNFO", r"(?i)READ\.?NFO"),
-            ("READNFO", r"(?i)READNFO"),
-            ("PROPER", r"(?i)PROPER"),
-            ("REPACK", r"(?i)REPACK"),
-            ("RERIP", r"(?i)RERIP"),
-            ("INTERNAL", r"(?i)\bINTERNAL\b"),
-            ("iNTERNAL", r"(?i)\biNTERNAL\b"),
-            // Audio/Subtitle flags
-            ("TV Dubbed", r"(?i)TV\.?Dubbed"),
-            ("Dubbed", r"(?i)\bDubbed\b"),
-            ("Subbed", r"(?i)\bSubbed\b"),
-            ("Hard Sub", r"(?i)(?:Hard\.?Sub|HardSub)"),
-            ("MultiSub", r"(?i)MultiSub"),
-            ("Multi-Subs", r"(?i)Multi-Subs"),
-            // Edition flags
-            ("Uncut", r"(?i)\bUncut\b"),
-            ("Director's Cut", r"(?i)Director'?s\.?Cut"),
-            ("Extended", r"(?i)\bExtended\b"),
-            ("Limited", r"(?i)\bLimited\b"),
-            ("Limited Edition", r"(?i)Limited\.?Edition"),
-            ("Special Edition", r"(?i)Special\.?Edition"),
-            ("Collector's Edition", r"(?i)Collector'?s\.?Edition"),
-            ("Ultimate Edition", r"(?i)Ultimate\.?Edition"),
-            // Video quality flags
-            ("IMAX", r"(?i)\bIMAX\b"),
-            ("IMAX HYBRID", r"(?i)IMAX\s+HYBRID"),
-            ("3D", r"(?i)\[3D\]|(?i)\b3D\b"),
-            ("10bit", r"(?i)\[10bit\]|(?i)\b10bit\b"),
-            ("REMASTERED", r"(?i)REMASTERED"),
-            // Anime flags
-            ("ANiME", r"(?i)ANiME"),
-            // Additional common tags
-            ("NUKED", r"(?i)NUKED"),
-            ("DUPE", r"(?i)DUPE"),
-            ("RETAIL", r"(?i)RETAIL"),
-            ("RERIP", r"(?i)RERIP"),
-            ("NFOFIX", r"(?i)NFOFIX"),
-            ("COMPLETE", r"(?i)COMPLETE"),
-            ("FESTIVAL", r"(?i)FESTIVAL"),
-            ("STV", r"(?i)\bSTV\b"),
-            ("SUBBED", r"(?i)\bSUBBED\b"),
-            ("DUBBED", r"(?i)\bDUBBED\b"),
-        ];
-
-        for (flag_name, pattern) in flag_patterns {
-            if let Ok(re) = Regex::new(pattern) {
-                if re.is_match(release_name) {
-                    flags.push(flag_name.to_string());
-                }
+    fn extract_flags(&self, release_name: &str) -> Candidates<String> {
+        // All flag patterns are tested in one pass, flags keep their table order
+        rules::FLAG_TABLE
+            .matches(release_name)
+            .map(|(index, found)| (rules::FLAG_PATTERNS[index].0.to_string(), found.range(), Rule("flags.keyword", 0.8)))
+            .collect()
+    }
+
+    /// The title: the words no field claimed, from the start of the name up
+    /// to the first claimed one. Anime names in brackets,
+    /// `[Group][国漫][仙逆][Renegade Immortal][2023]`, keep the bracket of
+    /// English words.
+    fn extract_title(&self, release_name: &str, analysis: &Analysis) -> Option<(String, Range<usize>)> {
+        let leading: Vec<&Token> = analysis.leading_unclaimed().collect();
+        let brackets = leading.chunk_by(|a, b| b.separator == Separator::Bracket && !release_name[a.span.end..b.span.start].contains(']'));
+        let mut english = brackets.clone().filter(|bracket| bracket.iter().all(|token| token.separator == Separator::Bracket));
+        let english = english.find(|bracket| bracket.len() >= 2 && bracket.iter().all(|token| token.text.chars().any(|c| c.is_ascii_alphabetic())));
+        let tokens = match english {
+            Some(bracket) if self.release_type == "tv" && release_name.starts_with('[') => bracket,
+            _ => &leading[..],
+        };
+        let title = clean_title(&join_words(tokens));
+        (!title.is_empty()).then(|| (title, tokens[0].span.start..tokens[tokens.len() - 1].span.end))
+    }
+
+    /// The episode title: the words no field claimed right after the episode
+    /// marker, or the air date of a daily show, up to the first tag or bracket.
+    fn extract_episode_title(&self, analysis: &Analysis) -> Option<(String, Range<usize>)> {
+        let field = ["episode", "date"].into_iter().find(|field| analysis.field_span(field).is_some())?;
+        // The part of "S01E02.The.Reckoning.Part.2" is read but left in the episode title
+        let part = analysis.fields.iter().find(|report| report.field == "part").and_then(|report| report.span.clone());
+        let in_part = |token: &Token| part.as_ref().is_some_and(|part| token.is_within(part));
+        let tokens: Vec<&Token> = analysis
+            .unclaimed_after(field)
+            .take_while(|token| !matches!(token.separator, Separator::Bracket | Separator::Brace | Separator::Group) && (!analysis.is_read(token) || in_part(token)))
+            .collect();
+        let episode_title = clean_title(&join_words(&tokens));
+        (!episode_title.is_empty()).then(|| (episode_title, tokens[0].span.start..tokens[tokens.len() - 1].span.end))
+    }
+
+    fn extract_disc(&self, release_name: &str) -> Found<u8> {
+        // Match disc patterns like Disc1, Disc 1, CD1, etc.
+        let re = regex!(r"(?i)(?:Disc|CD|DVD)\s*(\d+)");
+        if let Some(cap) = re.captures(release_name) {
+            if let Ok(disc) = cap.get(1).unwrap().as_str().parse::<u8>() {
+                return Some((disc, cap.get(0).unwrap().range(), Rule("disc.keyword", 0.7)));
             }
         }
+        None
+    }
 
-        flags
+    /// `Part.3.of.6`, `Pt.2`, `Part.II`, German `Teil.2.von.3` and French
+    /// `Partie.2.sur.3`, with the number of parts when given.
+    fn extract_part(&self, release_name: &str) -> Found<(u16, Option<u16>)> {
+        let re = regex!(r"\b(?:(?i:Part|Teil|Partie)|Pt)[. _]?(\d{1,2}|[IVXL]{1,6})(?:[. _](?i:of|von|sur)[. _](\d{1,2}|[IVXL]{1,6}))?\b");
+        let caps = re.captures(release_name)?;
+        let part = numeral(&caps[1]).filter(|part| *part > 0)?;
+        let total = caps.get(2).and_then(|total| numeral(total.as_str())).filter(|total| *total >= part);
+        Some(((part, total), caps.get(0).unwrap().range(), Rule("part.keyword", 0.8)))
     }
 
-    fn extract_title(&self, release_name: &str, parsed: &ParsedRelease) -> (String, String) {
-        // Handle new format: Title (Year) {tmdb-xxx} - S01E01 - Episode Title [specs]-GROUP
-        // Or: Title (Year) {tmdb-xxx} [specs]-GROUP
-        
-        // Try to extract episode title for TV shows in new format: - S01E01 - Episode Title
-        // Also handle: - S01E01-E02 - Episode Title, - 2013-10-30 - Episode Title, - 001 - Episode Title
-        if self.release_type == "tv" {
-            // Handle dot-separated format: Title.S02E10.Episode.Title.German.2023...
-            // Extract episode title between season/episode and language/year/metadata
-            // First try with stop words that must follow immediately
-            if let Ok(re) = Regex::new(r"(?i)(.+?)\.(S\d{1,2}E\d{1,3})\.(.+?)(?:\.(?:German|English|French|Spanish|Italian|Portuguese|Russian|Dutch|Polish|Swedish|Norwegian|Danish|Finnish|Japanese|Chinese|Korean|Arabic|Turkish|NORDiC|SWEDiSH|NORWEGiAN|GERMAN|ANiME|DL|BluRay|BDRip|DVDRip|WEB-DL|HDTV|1080p|720p|480p|x264|x265|h264|h265|HEVC|AVC|\d{4}))") {
-                if let Some(caps) = re.captures(release_name) {
-                    let mut main_title = caps.get(1).unwrap().as_str().to_string();
-                    let episode_title = caps.get(3).unwrap().as_str();
-                    // Remove year from main title (e.g., "Ranma.1.2.2024" -> "Ranma.1.2")
-                    if let Ok(year_re) = Regex::new(r"\.(19|20|21)\d{2}(?:\.|$)") {
-                        main_title = year_re.replace_all(&main_title, ".").to_string();
-                    }
-                    // Also remove year at the end without dot separator
-                    if let Ok(year_re) = Regex::new(r"(19|20|21)\d{2}$") {
-                        main_title = year_re.replace_all(&main_title, "").trim().to_string();
-                    }
-                    // Clean up the episode title - remove dots and trim
-                    let cleaned_episode = episode_title.replace(".", " ").trim().to_string();
-                    // Clean up main title - remove dots and year
-                    let cleaned_main = main_title.replace(".", " ");
-                    // Remove year pattern from title with spaces
-                    let cleaned_main_no_year = if let Ok(year_re) = Regex::new(r"\b(19|20|21)\d{2}\b") {
-                        year_re.replace_all(&cleaned_main, "").trim().to_string()
-                    } else {
-                        cleaned_main.trim().to_string()
-                    };
-                    return (clean_title(&cleaned_main_no_year), clean_title(&cleaned_episode));
-                }
-            }
-            
-            // Also try a more flexible pattern for dot-separated formats
-            // Match: Title.S02E10.Episode.Title... (stop at known metadata)
-            if let Ok(re) = Regex::new(r"(?i)(.+?)\.(S\d{1,2}E\d{1,3})\.(.+?)(?:\.(?:German|English|French|Spanish|Italian|Portuguese|Russian|Dutch|Polish|Swedish|Norwegian|Danish|Finnish|Japanese|Chinese|Korean|Arabic|Turkish|NORDiC|SWEDiSH|NORWEGiAN|GERMAN|ANiME|DL|BluRay|BDRip|DVDRip|WEB-DL|HDTV|1080p|720p|480p|x264|x265|h264|h265|HEVC|AVC))") {
-                if let Some(caps) = re.captures(release_name) {
-                    let main_title = caps.get(1).unwrap().as_str();
-                    let episode_title = caps.get(3).unwrap().as_str();
-                    // Clean up the episode title - remove dots and trim
-                    let cleaned_episode = episode_title.replace(".", " ").trim().to_string();
-                    // Clean up main title - remove dots
-                    let cleaned_main = main_title.replace(".", " ").trim().to_string();
-                    return (clean_title(&cleaned_main), clean_title(&cleaned_episode));
-                }
-            }
-            
-            // Handle episode-only format with episode title: Title.E780.Episode.Title...
-            // Pattern: Running.Man.E780.This.is.the.Romance...
-            if let Ok(re) = Regex::new(r"(?i)(.+?)\.E\d{3,}\.(.+?)(?:\.(?:1080p|720p|480p|VIU|WEB-DL|WEBDL|WEBRip|H264|H265|H\.264|H\.265|x264|x265|h264|h265|HEVC|AVC|AAC|AC3|DTS))") {
-                if let Some(caps) = re.captures(release_name) {
-                    let main_title = caps.get(1).unwrap().as_str();
-                    let episode_title = caps.get(2).unwrap().as_str();
-                    // Clean up the episode title - remove dots and trim
-                    let cleaned_episode = episode_title.replace(".", " ").trim().to_string();
-                    // Clean up main title - remove dots
-                    let cleaned_main = main_title.replace(".", " ").trim().to_string();
-                    return (clean_title(&cleaned_main), clean_title(&cleaned_episode));
-                }
-            }
-            
-            // More general pattern: Title.S02E02.Episode.Title... (extract everything between SXXEXX and known metadata)
-            // This handles cases like "24.S02E02.9.00.Uhr.bis.10.00.Uhr.German..."
-            // Use a pattern that matches everything up to a known stop word
-            let stop_words = vec!["German", "English", "French", "Spanish", "Italian", "Portuguese", "Russian", "Dutch", "Polish", "Swedish", "Norwegian", "Danish", "Finnish", "Japanese", "Chinese", "Korean", "Arabic", "Turkish", "NORDiC", "SWEDiSH", "NORWEGiAN", "GERMAN", "DL", "TV", "Dubbed", "Subbed", "BluRay", "BDRip", "DVDRip", "WEB-DL", "HDTV", "1080p", "720p", "480p", "x264", "x265", "h264", "h265", "HEVC", "AVC", "SVCD", "VCD", "READ", "NFO"];
-            for stop_word in &stop_words {
-                let pattern = format!(r"(?i)(.+?)\.(S\d{{1,2}}E\d{{1,3}})\.(.+?)\.{}(?:\.|$)", regex::escape(stop_word));
-                if let Ok(re) = Regex::new(&pattern) {
-                    if let Some(caps) = re.captures(release_name) {
-                        let main_title = caps.get(1).unwrap().as_str();
-                        let episode_title = caps.get(3).unwrap().as_str();
-                        // Clean up the episode title - remove dots and trim
-                        let cleaned_episode = episode_title.replace(".", " ").trim().to_string();
-                        // Clean up main title - remove dots
-                        let cleaned_main = main_title.replace(".", " ").trim().to_string();
-                        if !cleaned_episode.is_empty() {
-                            return (clean_title(&cleaned_main), clean_title(&cleaned_episode));
-                        }
-                    }
-                }
-            }
-            
-            // Match pattern: - S01E01 or S01E01-E02 - Episode Title
-            if let Ok(re) = Regex::new(r"(?i)\s*-\s*S\d{1,2}E\d{1,3}(?:-E\d{1,3})?\s*-\s*([^-\[\{]+)") {
-                if let Some(caps) = re.captures(release_name) {
-                    let episode_title = caps.get(1).unwrap().as_str().trim().to_string();
-                    // Extract main title (everything before the first dash before season/episode)
-                    if let Ok(title_re) = Regex::new(r"^(.+?)\s*-\s*S\d{1,2}E\d{1,3}") {
-                        if let Some(title_caps) = title_re.captures(release_name) {
-                            let mut main_title = title_caps.get(1).unwrap().as_str().to_string();
-                            // Remove year in parentheses
-                            if let Ok(year_re) = Regex::new(r"\s*\(\d{4}\)\s*") {
-                                main_title = year_re.replace_all(&main_title, " ").to_string();
-                            }
-                            // Remove TMDB ID
-                            if let Ok(tmdb_re) = Regex::new(r"\s*\{tmdb-\d+\}\s*") {
-                                main_title = tmdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            // Remove TVDB ID
-                            if let Ok(tvdb_re) = Regex::new(r"\s*\{tvdb-\d+\}\s*") {
-                                main_title = tvdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            // Remove IMDB ID
-                            if let Ok(imdb_re) = Regex::new(r"\s*\{imdb-tt\d+\}\s*|\[imdb(?:id)?-tt\d+\]") {
-                                main_title = imdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            // Remove TMDB ID (in case it's in brackets)
-                            if let Ok(tmdb_re) = Regex::new(r"\[tmdb(?:id)?-\d+\]") {
-                                main_title = tmdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            // Remove edition
-                            if let Ok(edition_re) = Regex::new(r"\s*\{edition-[^}]+\}\s*") {
-                                main_title = edition_re.replace_all(&main_title, " ").to_string();
-                            }
-                            return (clean_title(&main_title), clean_title(&episode_title));
-                        }
-                    }
-                    // Even if main title extraction fails, return the episode title
-                    return (String::new(), clean_title(&episode_title));
-                }
-            }
-            // Match pattern: - 2013-10-30 - Episode Title (date-based)
-            if let Ok(re) = Regex::new(r"\s*-\s*\d{4}-\d{2}-\d{2}\s*-\s*([^-\[\{]+)") {
-                if let Some(caps) = re.captures(release_name) {
-                    let episode_title = caps.get(1).unwrap().as_str().trim().to_string();
-                    if let Ok(title_re) = Regex::new(r"^(.+?)\s*-\s*\d{4}-\d{2}-\d{2}") {
-                        if let Some(title_caps) = title_re.captures(release_name) {
-                            let mut main_title = title_caps.get(1).unwrap().as_str().to_string();
-                            if let Ok(year_re) = Regex::new(r"\s*\(\d{4}\)\s*") {
-                                main_title = year_re.replace_all(&main_title, " ").to_string();
-                            }
-                            if let Ok(tmdb_re) = Regex::new(r"\s*\{tmdb-\d+\}\s*") {
-                                main_title = tmdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            if let Ok(tvdb_re) = Regex::new(r"\s*\{tvdb-\d+\}\s*") {
-                                main_title = tvdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            if let Ok(imdb_re) = Regex::new(r"\s*\{imdb-tt\d+\}\s*|\[imdb(?:id)?-tt\d+\]") {
-                                main_title = imdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            if let Ok(tmdb_re) = Regex::new(r"\[tmdb(?:id)?-\d+\]") {
-                                main_title = tmdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            if let Ok(edition_re) = Regex::new(r"\s*\{edition-[^}]+\}\s*") {
-                                main_title = edition_re.replace_all(&main_title, " ").to_string();
-                            }
-                            return (clean_title(&main_title), clean_title(&episode_title));
-                        }
-                    }
-                }
-            }
-            // Match pattern: - 001 - Episode Title or - 001-003 - Episode Title (episode number)
-            if let Ok(re) = Regex::new(r"\s*-\s*\d{3}(?:-\d{3})?\s*-\s*([^-\[\{]+)") {
-                if let Some(caps) = re.captures(release_name) {
-                    let episode_title = caps.get(1).unwrap().as_str().trim().to_string();
-                    if let Ok(title_re) = Regex::new(r"^(.+?)\s*-\s*\d{3}(?:-\d{3})?") {
-                        if let Some(title_caps) = title_re.captures(release_name) {
-                            let mut main_title = title_caps.get(1).unwrap().as_str().to_string();
-                            if let Ok(year_re) = Regex::new(r"\s*\(\d{4}\)\s*") {
-                                main_title = year_re.replace_all(&main_title, " ").to_string();
-                            }
-                            if let Ok(tmdb_re) = Regex::new(r"\s*\{tmdb-\d+\}\s*") {
-                                main_title = tmdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            if let Ok(tvdb_re) = Regex::new(r"\s*\{tvdb-\d+\}\s*") {
-                                main_title = tvdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            if let Ok(imdb_re) = Regex::new(r"\s*\{imdb-tt\d+\}\s*|\[imdb(?:id)?-tt\d+\]") {
-                                main_title = imdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            if let Ok(tmdb_re) = Regex::new(r"\[tmdb(?:id)?-\d+\]") {
-                                main_title = tmdb_re.replace_all(&main_title, " ").to_string();
-                            }
-                            if let Ok(edition_re) = Regex::new(r"\s*\{edition-[^}]+\}\s*") {
-                                main_title = edition_re.replace_all(&main_title, " ").to_string();
-                            }
-                            return (clean_title(&main_title), clean_title(&episode_title));
-                        }
-                    }
-                }
-            }
-        }
-        
-        let mut working = release_name.to_string();
-        
-        // Special handling for anime format with brackets at start: [GM-Team][国漫][仙逆][Renegade Immortal]...
-        // Extract title from brackets if it looks like an anime format
-        // Pattern: [group][category][chinese][english title][year][episode][format][lang][resolution]
-        // Try to find bracket with English words (title) - look for bracket with 2+ English words
-        // But skip if the bracket content matches the extracted group
-        // Only use this for anime formats (when release starts with brackets)
-        // Skip this for movie formats that have titles before brackets (e.g., "The Movie Title (2010) [imdbid-...]")
-        if release_name.starts_with('[') && self.release_type == "tv" {
-            if let Ok(re) = Regex::new(r"\[([^\]]+)\]") {
-                let mut found_title = None;
-                for cap in re.captures_iter(release_name) {
-                    let bracket_content = cap.get(1).unwrap().as_str().trim();
-                    // Skip if this bracket matches the group
-                    if !parsed.group.is_empty() {
-                        let parsed_group_trimmed = parsed.group.trim();
-                        if bracket_content == parsed_group_trimmed || 
-                           bracket_content.replace(" ", "") == parsed_group_trimmed.replace(" ", "") {
-                            continue;
-                        }
-                    }
-                    // Check if this bracket contains English words (2+ words with spaces)
-                    let words: Vec<&str> = bracket_content.split_whitespace().collect();
-                    if words.len() >= 2 {
-                        // Check if all words contain English letters
-                        let has_english = words.iter().all(|w| w.chars().any(|c| c.is_alphabetic() && c.is_ascii()));
-                        // Make sure it's not metadata - exclude common metadata patterns
-                        let is_not_metadata = !bracket_content.eq_ignore_ascii_case("AVC") && 
-                                             !bracket_content.eq_ignore_ascii_case("GB") &&
-                                             !bracket_content.eq_ignore_ascii_case("1080P") &&
-                                             !bracket_content.eq_ignore_ascii_case("720p") &&
-                                             !bracket_content.eq_ignore_ascii_case("1080p") &&
-                                             !bracket_content.contains("WEB-DL") &&
-                                             !bracket_content.contains("WEBRip") &&
-                                             !bracket_content.contains("WEBDL") &&
-                                             !bracket_content.contains("MultiSub") &&
-                                             !bracket_content.contains("Multi-Subs") &&
-                                             !bracket_content.contains("Surround Sound") &&
-                                             !bracket_content.contains("x264") &&
-                                             !bracket_content.contains("x265") &&
-                                             !bracket_content.contains("h264") &&
-                                             !bracket_content.contains("h265") &&
-                                             !bracket_content.contains("HEVC") &&
-                                             !bracket_content.contains("AVC") &&
-                                             !bracket_content.chars().all(|c| c.is_ascii_digit());
-                        if has_english && is_not_metadata {
-                            found_title = Some(bracket_content.to_string());
-                            break;
-                        }
-                    }
-                }
-                if let Some(title) = found_title {
-                    return (clean_title(&title), String::new());
-                }
-            }
-        }
-        
-        // Remove group (if not already removed at the start)
-        if !parsed.group.is_empty() {
-            // If group is in brackets at start, remove it (if not already removed)
-            if working.starts_with('[') {
-                if let Ok(re) = Regex::new(r"^\[([^\]]+)\]") {
-                    if let Some(cap) = re.captures(&working) {
-                        let potential_group = cap.get(1).unwrap().as_str().trim();
-                        let parsed_group_trimmed = parsed.group.trim();
-                        // Compare trimmed versions - handle spaces in group names like "FSP DN"
-                        // Also do case-insensitive comparison for group names
-                        if potential_group.eq_ignore_ascii_case(parsed_group_trimmed) || 
-                           potential_group.replace(" ", "").eq_ignore_ascii_case(&parsed_group_trimmed.replace(" ", "")) {
-                            working = working[cap.get(0).unwrap().end()..].trim().to_string();
-                        }
-                    }
-                }
-            } else if let Some(pos) = working.rfind('-') {
-                // Group is typically at the end after the last dash
-                working = working[..pos].to_string();
-            }
-        }
+    /// `Vol.3`, `Volume 3`, `Vol.III`.
+    fn extract_volume(&self, release_name: &str) -> Found<u16> {
+        let re = regex!(r"\b(?i:Vol(?:ume)?)[. _]?(\d{1,3}|[IVXL]{1,6})\b");
+        let caps = re.captures(release_name)?;
+        let volume = numeral(&caps[1]).filter(|volume| *volume > 0)?;
+        Some((volume, caps.get(0).unwrap().range(), Rule("volume.keyword", 0.8)))
+    }
 
-        // Remove bracket content: [Remux-1080p][TrueHD 5.1][AVC]
-        // But preserve content that might be part of the title (like Chinese characters)
-        // Only remove brackets that contain metadata (format, resolution, audio, etc.)
-        if let Ok(bracket_re) = Regex::new(r"\[([^\]]+)\]") {
-            let mut brackets_to_remove = Vec::new();
-            for cap in bracket_re.captures_iter(&working) {
-                let bracket_content = cap.get(1).unwrap().as_str();
-                // Check if bracket contains known metadata keywords
-                let contains_metadata = bracket_content.contains("Remux") ||
-                    bracket_content.contains("TrueHD") ||
-                    bracket_content.contains("DTS-HD") ||
-                    bracket_content.contains("DTS HD") ||
-                    bracket_content.contains("EAC3") ||
-                    bracket_content.contains("WEB-DL") ||
-                    bracket_content.contains("WEBRip") ||
-                    bracket_content.contains("WEBDL") ||
-                    bracket_content.contains("BluRay") ||
-                    bracket_content.contains("Bluray") ||
-                    bracket_content.contains("x264") ||
-                    bracket_content.contains("x265") ||
-                    bracket_content.contains("h264") ||
-                    bracket_content.contains("h265") ||
-                    bracket_content.contains("HEVC") ||
-                    bracket_content.contains("AVC") ||
-                    bracket_content.contains("AAC") ||
-                    bracket_content.contains("AC3") ||
-                    bracket_content.contains("DTS") ||
-                    bracket_content.contains("MultiSub") ||
-                    bracket_content.contains("Multi-Subs") ||
-                    bracket_content.contains("HDR10") ||
-                    bracket_content.contains("DV HDR10") ||
-                    bracket_content.contains("HDR10Plus") ||
-                    bracket_content.contains("DV HDR10Plus") ||
-                    bracket_content.contains("1080p") ||
-                    bracket_content.contains("720p") ||
-                    bracket_content.contains("2160p") ||
-                    bracket_content.contains("480p") ||
-                    bracket_content.contains("GB") ||
-                    bracket_content.contains("Surround Sound") ||
-                    bracket_content.contains("imdbid") ||
-                    bracket_content.contains("imdb") ||
-                    bracket_content.contains("tmdb") ||
-                    bracket_content.eq_ignore_ascii_case("AVC") ||
-                    bracket_content.eq_ignore_ascii_case("GB") ||
-                    bracket_content.eq_ignore_ascii_case("1080P");
-                
-                // Also check if it's all metadata characters (digits, uppercase, spaces, dashes, dots)
-                let is_all_metadata_chars = bracket_content.chars().all(|c| {
-                    c.is_ascii_digit() || 
-                    c.is_ascii_uppercase() || 
-                    c == ' ' || c == '-' || c == '.'
-                });
-                
-                if contains_metadata || is_all_metadata_chars {
-                    brackets_to_remove.push(cap.get(0).unwrap().as_str().to_string());
-                }
-            }
-            // Remove the identified metadata brackets
-            for bracket in brackets_to_remove {
-                working = working.replace(&bracket, " ");
-            }
-        }
-        
-        // Remove any remaining empty brackets [ ] (handle both with and without spaces)
-        if let Ok(empty_bracket_re) = Regex::new(r"\[\s*\]") {
-            working = empty_bracket_re.replace_all(&working, " ").to_string();
-        }
-        // Also remove brackets that might have been partially emptied
-        if let Ok(empty_bracket_re2) = Regex::new(r"\[\s+\]") {
-            working = empty_bracket_re2.replace_all(&working, " ").to_string();
+    fn extract_tmdb_id(&self, release_name: &str) -> Found<String> {
+        // Match TMDB ID in curly braces: {tmdb-919207}
+        let re = regex!(r"\{tmdb-(\d+)\}");
+        if let Some(cap) = re.captures(release_name) {
+            return Some((cap.get(1).unwrap().as_str().to_string(), cap.get(0).unwrap().range(), Rule("tmdb_id.braces", 1.0)));
         }
-        
-        // Remove TMDB ID: {tmdb-919207}
-        if let Ok(tmdb_re) = Regex::new(r"\{tmdb-\d+\}") {
-            working = tmdb_re.replace_all(&working, " ").to_string();
+        // Also match in square brackets: [tmdb-1520211] or [tmdbid-1520211]
+        let re = regex!(r"\[tmdb(?:id)?-(\d+)\]");
+        if let Some(cap) = re.captures(release_name) {
+            return Some((cap.get(1).unwrap().as_str().to_string(), cap.get(0).unwrap().range(), Rule("tmdb_id.brackets", 1.0)));
         }
-        
-        // Remove TVDB ID: {tvdb-79169} or [tvdb-1520211] or [tvdbid-1520211]
-        if let Ok(tvdb_re) = Regex::new(r"\{tvdb-\d+\}|\[tvdb(?:id)?-\d+\]") {
-            working = tvdb_re.replace_all(&working, " ").to_string();
+        None
+    }
+
+    fn extract_tvdb_id(&self, release_name: &str) -> Found<String> {
+        // Match TVDB ID in curly braces: {tvdb-79169}
+        let re = regex!(r"\{tvdb-(\d+)\}");
+        if let Some(cap) = re.captures(release_name) {
+            return Some((cap.get(1).unwrap().as_str().to_string(), cap.get(0).unwrap().range(), Rule("tvdb_id.braces", 1.0)));
         }
-        
-        // Remove IMDB ID: {imdb-tt0066921} or [imdb-tt1520211] or [imdbid-tt1520211]
-        if let Ok(imdb_re) = Regex::new(r"\{imdb-tt\d+\}|\[imdb(?:id)?-tt\d+\]") {
-            working = imdb_re.replace_all(&working, " ").to_string();
+        // Also match in square brackets: [tvdb-1520211] or [tvdbid-1520211]
+        let re = regex!(r"\[tvdb(?:id)?-(\d+)\]");
+        if let Some(cap) = re.captures(release_name) {
+            return Some((cap.get(1).unwrap().as_str().to_string(), cap.get(0).unwrap().range(), Rule("tvdb_id.brackets", 1.0)));
         }
-        
-        // Remove edition: {edition-Ultimate Extended Edition}
-        if let Ok(edition_re) = Regex::new(r"\{edition-[^}]+\}") {
-            working = edition_re.replace_all(&working, " ").to_string();
+        None
+    }
+
+    fn extract_imdb_id(&self, release_name: &str) -> Found<String> {
+        // Match IMDB ID in curly braces: {imdb-tt0066921}
+        let re = regex!(r"\{imdb-(tt\d+)\}");
+        if let Some(cap) = re.captures(release_name) {
+            return Some((cap.get(1).unwrap().as_str().to_string(), cap.get(0).unwrap().range(), Rule("imdb_id.braces", 1.0)));
         }
-        
-        // Remove TMDB ID in brackets: [tmdb-1520211] or [tmdbid-1520211]
-        if let Ok(tmdb_re) = Regex::new(r"\[tmdb(?:id)?-\d+\]") {
-            working = tmdb_re.replace_all(&working, " ").to_string();
+        // Match in square brackets: [imdb-tt1520211] or [imdbid-tt1520211]
+        let re = regex!(r"\[imdb(?:id)?-(tt\d+)\]");
+        if let Some(cap) = re.captures(release_name) {
+            return Some((cap.get(1).unwrap().as_str().to_string(), cap.get(0).unwrap().range(), Rule("imdb_id.brackets", 1.0)));
         }
-        
-        // Remove edition: {edition-Ultimate Extended Edition}
-        if let Ok(edition_re) = Regex::new(r"\{edition-[^}]+\}") {
-            working = edition_re.replace_all(&working, " ").to_string();
+        None
+    }
+
+    fn extract_edition(&self, release_name: &str) -> Found<String> {
+        // Match edition in curly braces: {edition-Ultimate Extended Edition}
+        let re = regex!(r"\{edition-([^}]+)\}");
+        if let Some(cap) = re.captures(release_name) {
+            return Some((cap.get(1).unwrap().as_str().trim().to_string(), cap.get(0).unwrap().range(), Rule("edition.braces", 1.0)));
         }
-        
-        // Remove year in parentheses: (2023)
-        if let Ok(year_re) = Regex::new(r"\(\d{4}\)") {
-            working = year_re.replace_all(&working, " ").to_string();
+        // Match edition in square brackets: [U-Edition]
+        let re = regex!(r"\[([A-Z]-Edition)\]");
+        if let Some(cap) = re.captures(release_name) {
+            return Some((cap.get(1).unwrap().as_str().trim().to_string(), cap.get(0).unwrap().range(), Rule("edition.brackets", 0.9)));
         }
-        
-        // Remove year in square brackets: [2006]
-        if let Ok(year_re) = Regex::new(r"\[\d{4}\]") {
-            working = year_re.replace_all(&working, " ").to_string();
-        }
-
-        // Remove common patterns that are not part of the title
-        let patterns_to_remove = vec![
-            r"(?i)S\d{1,2}E\d{1,3}(?:-E\d{1,3})?",  // S01E01 or S01E01-E02
-            r"(?i)E\d{1,3}E\d{1,3}",  // E01E02 format (episode-only, no season)
-            r"(?i)\bE\d{3,}\b",  // E780 format (episode-only, 3+ digits, no season)
-            r"(?i)S\d{1,2}\s*-\s*\d{1,3}",  // S5 - 02 format
-            r"(?i)\d{1,2}x\d{1,3}",
-            r"(?i)Season\s*\d{1,2}\s*Episode\s*\d{1,3}",
-            r"\b(19|20|21)\d{2}\b",
-            r"(?i)(\d{3,4})[pi]",
-            r"\((\d{3,4})p\)",  // (1080p) format
-            r"\([^)]*(?:\d+p|WEB-DL|WEBRip|WEBDL|CR|NF|AMZN|H264|H265|H\.264|H\.265|AAC|DDP|2\.0|5\.1)[^)]*\)",  // Parentheses with metadata like (1080p CR WEB-DL H264 AAC 2.0)
-            r"(?i)READ\.?NFO",
-            r"(?i)PROPER",
-            r"(?i)REPACK",
-            r"\[Eng\.Hard\.Sub\]",
-            r"\[U-Edition\]",
-            r"(?i)DDP\d+\.\d+",  // DDP2.0, DDP5.1, etc.
-            r"(?i)\bDDP\d+\b",  // DDP2, DDP5 (without decimal)
-            r"(?i)Episode\s+\d+",  // Episode 61, Episode 1, etc.
-            r"(?i)\b(?:AAC|AC3|DTS|DDP)\s+\d+\.\d+\b",  // Audio format with version like "AAC 2.0", "AC3 5.1"
-            r"\.(mkv|mp4|avi|mov|wmv|flv|webm|m4v)$",  // File extensions
-        ];
+        None
+    }
 
-        for pattern in patterns_to_remove {
-            if let Ok(re) = Regex::new(pattern) {
-                working = re.replace_all(&working, " ").to_string();
+    fn extract_episode_number(&self, release_name: &str) -> Found<String> {
+        // Match episode number format: 001, 001-003
+        // Pattern: - 001 - or - 001-003 -
+        let re = regex!(r"-\s*(\d{3}(?:-\d{3})?)\s*-");
+        if let Some(cap) = re.captures(release_name) {
+            return Some((cap.get(1).unwrap().as_str().to_string(), cap.get(1).unwrap().range(), Rule("episode.dashed_number", 0.7)));
+        }
+        // Also handle episode numbers in brackets: [119]
+        // Need to check all brackets to find episode numbers (not just first match)
+        let re = regex!(r"\[(\d{1,4})\]");
+        for cap in re.captures_iter(release_name) {
+            let ep_num = cap.get(1).unwrap().as_str();
+            // Only return if it's not part of a year pattern [2023]
+            // Episode numbers are typically 1-3 digits, or 4 digits that don't start with 19/20
+            if ep_num.len() <= 3 {
+                return Some((ep_num.to_string(), cap.get(1).unwrap().range(), Rule("episode.bracket_number", 0.6)));
+            } else if ep_num.len() == 4 {
+                if let Ok(year) = ep_num.parse::<u16>() {
+                    // If it's a valid year (1900-2100), skip it
+                    if (1900..=2100).contains(&year) {
+                        continue;
+                    }
+                }
+                return Some((ep_num.to_string(), cap.get(1).unwrap().range(), Rule("episode.bracket_number", 0.4)));
             }
         }
+        None
+    }
 
-        // Remove source, format, resolution, etc.
-        // Based on: https://en.wikipedia.org/wiki/Pirated_movie_release_types
-        let remove_list = vec![
-            // DVD sources
-            "DVDRip", "DVD-Rip", "DVDR", "DVD5", "DVD9", "DVD-R", "DvDrip",
-            // Web sources
-            "WEB-DL", "WEBRip", "Web Rip", "Web Download", "WEB", "WEBDL", "AMZN WEBDL", "MA WEBDL",
-            // TV sources
-            "HDTV", "PDTV", "DSR", "SATRip", "TVRip", "iNTERNAL HDTV", "iNTERNAL", "INTERNAL",
-            // Blu-ray sources
-            "BluRay", "BDRip", "BRRip", "BD", "Remux", "Hybrid",
-            // Other sources
-            "VHSRip", "R5", "TC", "TS", "CAM", "SCR", "HDCAM", "TELESYNC", "TELECINE",
-            "Workprint", "WP", "PPV Rip", "PPVRip", "DDC", "VOD Rip", "VODRip",
-            "HC HD Rip", "HCHDRip", "Web Capture", "HDRip", "DCP", "Theatre", "Theater",
-            // Formats
-            "SVCD", "VCD", "XviD", "DivX", "x264", "x265", "h265", "h264", "HEVC", "AVC",
-            "H.264", "H.265", "H264", "H265", "MPEG2", "MPEG4",
-            // Audio
-            "AC3", "DTS", "AAC", "MP3", "TrueHD", "EAC3", "Atmos", "Surround Sound", "DDP", "DDP2.0", "DDP5.1", "DDP2", "DDP5", "Dolby Digital Plus",
-            "AAC 2.0", "AAC2.0", "AAC 5.1", "AAC5.1", "AC3 2.0", "AC3 5.1", "DTS 5.1", "DTS 2.0",
-            // Languages
-            "German", "English", "French", "Spanish", "Italian", "Eng",
-            "NORDiC", "SWEDiSH", "NORWEGiAN", "Swedish", "Norwegian", "Danish",
-            "Finnish", "Japanese", "Chinese", "Korean", "Arabic", "Turkish",
-            "Multi", "MULTI", "Dubbed", "Subbed", "Hard.Sub", "HardSub",
-            // Flags
-            "TV", "DL", "READNFO", "NFO", "HDR10", "DV", "HDR10Plus",
-            "3D", "10bit", "IMAX", "HYBRID", "REMASTERED", "Proper",
-            "Uncut", "Extended", "Limited", "Special", "Collector",
-            "Ultimate", "Edition", "U-Edition", "Director", "Cut",
-            "ANiME", "MultiSub", "Multi-Subs",
-            // Streaming providers (comprehensive list)
-            "9NOW", "A3P", "AE", "ABC", "AJAZ", "ALL4", "AMC", "AMZN", "Amazon", "Prime Video", "Prime",
-            "ANLB", "ANPL", "APPS", "ARD", "AS", "ATVP", "Apple TV+", "AppleTV", "Apple",
-            "AUBC", "BCORE", "BK", "BNGE", "BOOM", "BRAV", "CBC", "CBS", "CC", "CHGD", "CLBI",
-            "CMAX", "Cinemax", "CMOR", "CMT", "CN", "CNBC", "CNLP", "COOK", "CR", "Crunchyroll",
-            "CRAV", "CRIT", "CRKL", "CRKI", "CSPN", "CTV", "CUR", "CW", "CWS", "DCU", "DDY",
-            "DEST", "DF", "DISC", "Discovery", "Discovery+", "Discovery Plus", "DIY", "DPLY",
-            "DRPO", "DRTV", "DSCP", "DSNP", "Disney+", "DisneyPlus", "Disney", "DTV",
-            "DW", "DLWP", "EPIX", "ESPN", "ESPN+", "ESPN Plus", "ESQ", "ETTV", "ETV",
-            "FAH", "FAM", "FBWatch", "FJR", "FOOD", "FOX", "FPT", "FREE", "FTV", "FUNI", "Funimation",
-            "FXTL", "FYI", "GC", "GLBL", "GLOB", "GLBO", "GO90", "GPLAY", "Google Play", "PLAY",
-            "HBO", "HBO Max", "HMAX", "MAX", "Max", "HGTV", "HIDI", "HIDIVE", "HIST", "HLMK",
-            "HPLAY", "HTSR", "HS", "HULU", "Hulu", "iP", "BBC iPlayer", "BBC", "iQIYI",
-            "iT", "iTunes", "ITV", "ITVX", "JC", "KAYO", "KNOW", "KNPY", "KS", "LGP", "LIFE",
-            "LN", "MA", "Movies Anywhere", "MBC", "MMAX", "MNBC", "MS", "Microsoft Store",
-            "MTOD", "MTV", "MUBI", "MY5", "NATG", "NBA", "NBC", "NBLA", "NF", "Netflix",
-            "NFL", "NFLN", "NICK", "NOW", "NRK", "ODK", "OPTO", "OSN", "OXGN", "PBS", "PBSK",
-            "PCOK", "Peacock", "PLUZ", "PMNT", "PMTP", "Paramount+", "Paramount Plus", "Paramount",
-            "POGO", "PSN", "PlayStation Network", "PUHU", "QIBI", "RED", "YouTube Premium", "YouTube Red",
-            "RKTN", "ROKU", "RSTR", "RTE", "RTP", "RTPPLAY", "SAINA", "SP", "SBS", "SESO",
-            "SHDR", "SHMI", "SHO", "Showtime", "Showtime Anytime", "SKST", "SkyShowtime",
-            "SLNG", "SNET", "SNXT", "SPIK", "SPRT", "SS", "STAN", "STRP", "STZ", "STARZ", "Starz",
-            "SVT", "SYFY", "TEN", "TIMV", "TK", "TLC", "TOU", "TRVL", "TUBI", "TV2", "TV3", "TV4",
-            "TVING", "TVL", "TVNZ", "UFC", "UKTV", "UNIV", "USAN", "VH1", "VIAP", "Viaplay",
-            "VICE", "VIKI", "VIU", "VLCT", "VMEO", "Vimeo", "VRV", "VTRN", "WAVVE", "WNET", "WTCH", "WWEN",
-            "WWE Network", "XBOX", "Xbox Video", "YT", "YouTube", "YouTube Movies", "YouTube TV",
-            "ZDF",
-            // Japanese streaming services
-            "ABMA", "ADN", "ANIMAX", "AO", "AT-X", "ATX", "Baha", "B-Global", "Bstation", "BSP",
-            "NHK-BSP", "BS4", "BS5", "EX-BS", "BS-EX", "BS6", "BS7", "BSJ", "BS-TX", "BS8",
-            "BS-Fuji", "BS11", "BS12", "CS-Fuji ONE", "CX", "DMM", "EX", "CS3", "EX-CS1",
-            "CS-EX1", "CSA", "FOD", "FUNi", "KBC", "M-ON!", "MX", "NHKG", "NHKE", "NTV", "TBS",
-            "TX", "UNXT", "U-NEXT", "WAKA", "Wakanim", "WOWOW", "Wowow", "YTV",
-        ];
+    fn extract_date(&self, release_name: &str, tokens: &[Token]) -> Candidates<AirDate> {
+        // Other types only take the unmistakable "- 2013-10-30 -"
+        find_token_dates(release_name, tokens)
+            .into_iter()
+            .filter(|(_, _, rule, _)| self.release_type == "tv" || *rule == "date.dashed")
+            .map(|(date, span, rule, confidence)| (date, span, Rule(rule, confidence)))
+            .collect()
+    }
 
-        for item in &remove_list {
-            // Use word boundaries to avoid partial matches (e.g., "SKST" shouldn't match "ASKST" or "SKSTX")
-            // But also handle cases where it's separated by dots or spaces
-            let pattern = format!(r"(?i)\b{}\b|\.{}\b|\b{}\.", regex::escape(item), regex::escape(item), regex::escape(item));
-            if let Ok(re) = Regex::new(&pattern) {
-                working = re.replace_all(&working, " ").to_string();
-            } else {
-                // Fallback to simple replace if regex fails
-                working = working.replace(item, " ");
-            }
-        }
-        
-        // Also remove streaming provider if it was extracted (to handle cases where it's not in remove_list)
-        if !parsed.streaming_provider.is_empty() {
-            let provider = &parsed.streaming_provider;
-            let pattern = format!(r"(?i)\b{}\b|\.{}\b|\b{}\.", regex::escape(provider), regex::escape(provider), regex::escape(provider));
-            if let Ok(re) = Regex::new(&pattern) {
-                working = re.replace_all(&working, " ").to_string();
-            } else {
-                working = working.replace(provider, " ");
+    fn extract_hdr(&self, release_name: &str) -> Found<String> {
+        // Match HDR information: HDR10, DV HDR10, HDR10Plus, DV HDR10Plus
+        // Check for DV HDR10Plus first (longest match)
+        let hdr_formats = [
+            ("DV HDR10Plus", "DV HDR10Plus"), ("DV.HDR10Plus", "DV HDR10Plus"), ("HDR10Plus", "HDR10Plus"),
+            ("DV HDR10", "DV HDR10"), ("DV.HDR10", "DV HDR10"), ("HDR10", "HDR10"),
+        ];
+        for (needle, hdr) in hdr_formats {
+            if let Some(span) = span_of(release_name, needle, 0) {
+                return Some((hdr.to_string(), span, Rule("hdr.keyword", 0.9)));
             }
         }
+        None
+    }
 
-        // Remove file extensions
-        if let Ok(ext_re) = Regex::new(r"\.(mkv|mp4|avi|mov|wmv|flv|webm|m4v)$") {
-            working = ext_re.replace_all(&working, "").to_string();
-        }
-        
-        // Remove numeric IDs in brackets (like [66856162])
-        if let Ok(id_re) = Regex::new(r"\[\d{6,}\]") {
-            working = id_re.replace_all(&working, " ").to_string();
-        }
-        
-        // Remove empty parentheses
-        if let Ok(empty_paren_re) = Regex::new(r"\(\s*\)") {
-            working = empty_paren_re.replace_all(&working, " ").to_string();
+    fn extract_streaming_provider(&self, release_name: &str) -> Candidates<String> {
+        let mut candidates = Vec::new();
+        // Check for AMZN in "AMZN WEBDL" format first (before other patterns)
+        let re = regex!(r"(?i)\[(AMZN)\s+WEBDL");
+        if let Some(cap) = re.captures(release_name) {
+            candidates.push(("AMZN".to_string(), cap.get(1).unwrap().range(), Rule("streaming_provider.amzn_webdl", 0.95)));
         }
-        
-        // Remove DDP patterns that might have been split by dots (e.g., "DDP2 0" after dot replacement)
-        if let Ok(ddp_re) = Regex::new(r"(?i)\bDDP\d+\s+\d+\b") {
-            working = ddp_re.replace_all(&working, " ").to_string();
+
+        // Pattern: resolution (1080p/720p) -> provider -> WEB-DL/WEBRip
+        // Match: 1080p.SKST.WEB-DL or 720p.MAX.WEB-DL
+        let re = regex!(r"(?i)(\d+p)\.([A-Z0-9]+)\.(?:WEB-DL|WEBRip|WEBDL)");
+        if let Some(caps) = re.captures(release_name) {
+            let found = caps.get(2).unwrap();
+            let provider = found.as_str();
+            // Check if it's a known provider, otherwise a short uppercase word (2-6 chars) is likely a provider
+            if let Some(known_provider) = rules::STREAMING_PROVIDERS.iter().find(|known| provider.eq_ignore_ascii_case(known)) {
+                candidates.push((known_provider.to_string(), found.range(), Rule("streaming_provider.before_web", 0.9)));
+            } else if provider.len() >= 2 && provider.len() <= 6 && provider.chars().all(|c| c.is_uppercase() || c.is_ascii_digit()) {
+                candidates.push((provider.to_string(), found.range(), Rule("streaming_provider.unknown_before_web", 0.5)));
+            }
         }
         
-        // Remove any remaining empty brackets [ ] after all processing
-        if let Ok(empty_bracket_re) = Regex::new(r"\[\s*\]") {
-            working = empty_bracket_re.replace_all(&working, " ").to_string();
+        // Pattern: CR WEB-DL (Crunchyroll)
+        let re = regex!(r"(?i)\b(CR)\s+(?:WEB-DL|WEBRip|WEBDL)");
+        if let Some(cap) = re.captures(release_name) {
+            candidates.push(("CR".to_string(), cap.get(1).unwrap().range(), Rule("streaming_provider.before_web", 0.9)));
         }
         
-        // Clean up extra dots, dashes, and spaces
-        working = working.replace(".", " ");
-        working = working.replace("-", " ");
-        working = working.split_whitespace().collect::<Vec<_>>().join(" ");
-        working = working.trim().to_string();
-        
-        // Final cleanup: remove any remaining empty brackets [ ] and empty parentheses () after space normalization
-        if let Ok(empty_bracket_re) = Regex::new(r"\[\s*\]") {
-            working = empty_bracket_re.replace_all(&working, " ").to_string();
+        // Pattern: NF WEB-DL (Netflix)
+        let re = regex!(r"(?i)\b(NF)\s+(?:WEB-DL|WEBRip|WEBDL)");
+        if let Some(cap) = re.captures(release_name) {
+            candidates.push(("NF".to_string(), cap.get(1).unwrap().range(), Rule("streaming_provider.before_web", 0.9)));
         }
-        // Also remove empty parentheses that might have been left after processing
-        if let Ok(empty_paren_re) = Regex::new(r"\(\s*\)") {
-            working = empty_paren_re.replace_all(&working, " ").to_string();
+
+        // Also check for providers mentioned elsewhere in the release
+        for (index, found) in rules::STREAMING_PROVIDER_TABLE.matches(release_name) {
+            candidates.push((rules::STREAMING_PROVIDERS[index].to_string(), found.range(), Rule("streaming_provider.bare_word", 0.5)));
         }
-        working = working.trim().to_string();
         
-        // Remove group from title if it was extracted (for formats without dash)
-        if !parsed.group.is_empty() {
-            // Remove group from start if it was in brackets (handle spaces in group name)
-            let parsed_group_trimmed = parsed.group.trim();
-            let group_in_brackets = format!("[{}]", parsed_group_trimmed);
-            if working.starts_with(&group_in_brackets) {
-                working = working[group_in_brackets.len()..].trim().to_string();
-            }
-            // Also try with the original group (in case of whitespace differences)
-            let group_in_brackets_orig = format!("[{}]", parsed.group);
-            if working.starts_with(&group_in_brackets_orig) {
-                working = working[group_in_brackets_orig.len()..].trim().to_string();
-            }
-            // Remove group from end of title (handle both with and without spaces)
-            let group_with_space = format!(" {}", parsed_group_trimmed);
-            if working.ends_with(&group_with_space) {
-                working = working[..working.len() - group_with_space.len()].trim().to_string();
-            } else if working.ends_with(parsed_group_trimmed) {
-                working = working[..working.len() - parsed_group_trimmed.len()].trim().to_string();
+        // Check for AMZN in various formats
+        for needle in ["AMZN WEBDL", "AMZN.WEBDL"] {
+            if let Some(span) = span_of(release_name, needle, 0) {
+                candidates.push(("AMZN".to_string(), span.start..span.start + "AMZN".len(), Rule("streaming_provider.amzn_webdl", 0.9)));
             }
-            // Also remove if it appears as a separate word
-            working = working.replace(&format!(" {}", parsed_group_trimmed), " ");
-            working = working.replace(parsed_group_trimmed, " ");
-            working = working.trim().to_string();
         }
 
-        // For TV shows, try to extract title and extra (old format)
-        if self.release_type == "tv" {
-            // Remove group in brackets at start if present (before extracting title)
-            let mut working_for_split = release_name.to_string();
-            if !parsed.group.is_empty() && release_name.starts_with('[') {
-                if let Ok(re) = Regex::new(r"^\[([^\]]+)\]") {
-                    if let Some(cap) = re.captures(release_name) {
-                        let potential_group = cap.get(1).unwrap().as_str().trim();
-                        let parsed_group_trimmed = parsed.group.trim();
-                        if potential_group.eq_ignore_ascii_case(parsed_group_trimmed) || 
-                           potential_group.replace(" ", "").eq_ignore_ascii_case(&parsed_group_trimmed.replace(" ", "")) {
-                            working_for_split = release_name[cap.get(0).unwrap().end()..].trim().to_string();
-                        }
-                    }
-                }
-            }
-            
-            // Try to split on season/episode pattern (handle both dots and spaces)
-            // Pattern: S01E01 or S1E1
-            if let Ok(re) = Regex::new(r"(?i)(.+?)[.\s]+S\d{1,2}E\d{1,3}[.\s]+(.+)") {
-                if let Some(caps) = re.captures(&working_for_split) {
-                    let mut title = caps.get(1).unwrap().as_str().trim().to_string();
-                    let extra = caps.get(2).unwrap().as_str().trim().to_string();
-                    // Remove year from title before cleaning (e.g., "Ranma.1.2.2024" -> "Ranma.1.2")
-                    // Remove year with dot separator: .2024
-                    if let Ok(year_re) = Regex::new(r"\.(19|20|21)\d{2}(?:\.|$)") {
-                        title = year_re.replace_all(&title, ".").to_string();
-                    }
-                    // Also remove year at the end without dot separator: 2024
-                    if let Ok(year_re) = Regex::new(r"(19|20|21)\d{2}$") {
-                        title = year_re.replace_all(&title, "").trim().to_string();
-                    }
-                    // Clean up the title - replace dots with spaces, then remove year pattern
-                    let title_with_spaces = title.replace(".", " ");
-                    // Remove year pattern from title with spaces
-                    let title_no_year = if let Ok(year_re) = Regex::new(r"\b(19|20|21)\d{2}\b") {
-                        year_re.replace_all(&title_with_spaces, "").trim().to_string()
-                    } else {
-                        title_with_spaces
-                    };
-                    let title_cleaned = clean_title(&title_no_year);
-                    let extra_cleaned = clean_title(&extra.replace(".", " "));
-                    return (title_cleaned, extra_cleaned);
-                }
-            }
-            
-            // Handle anime format: "Title 5 - 01" (season - episode)
-            // Extract title before the season/episode pattern
-            if let Ok(re) = Regex::new(r"(.+?)\s+(\d{1,2})\s*-\s*(\d{1,3})") {
-                if let Some(caps) = re.captures(&working_for_split) {
-                    let title = caps.get(1).unwrap().as_str().trim().to_string();
-                    // Remove country codes like (CA), (US), etc. from title
-                    let title_cleaned = if let Ok(country_re) = Regex::new(r"\s*\([A-Z]{2}\)\s*$") {
-                        country_re.replace_all(&title, "").trim().to_string()
-                    } else {
-                        title
-                    };
-                    return (clean_title(&title_cleaned), String::new());
-                }
-            }
-        }
+        candidates
+    }
+}
 
-        // Default: use cleaned string as title
-        (clean_title(&working), String::new())
+/// Episodes of a multi-episode marker: the first one, then each next one
+/// with whether it ends a range (`-E03`) or is listed (`E03`, `+E03`).
+/// `None` when they don't ascend or a range spans over [`MAX_EPISODE_RANGE`].
+fn multi_episodes(first: u128, rest: impl IntoIterator<Item = (bool, u128)>) -> Option<Vec<u128>> {
+    let mut episodes = vec![first];
+    for (range, episode) in rest {
+        let last = *episodes.last()?;
+        if episode <= last || (range && episode - last > MAX_EPISODE_RANGE) {
+            return None;
+        }
+        if range {
+            episodes.extend(last + 1..=episode);
+        } else {
+            episodes.push(episode);
+        }
     }
+    Some(episodes)
+}
 
-    fn extract_disc(&self, release_name: &str) -> Option<u8> {
-        // Match disc patterns like Disc1, Disc 1, CD1, etc.
-        if let Ok(re) = Regex::new(r"(?i)(?:Disc|CD|DVD)\s*(\d+)") {
-            if let Some(cap) = re.captures(release_name) {
-                if let Ok(disc) = cap.get(1).unwrap().as_str().parse::<u8>() {
-                    return Some(disc);
-                }
-            }
+/// Words of the tokens, with the dashes of "GBRB - Joy Pops" and the "1.2"
+/// of "Ranma.1.2" as two words.
+fn join_words(tokens: &[&Token]) -> String {
+    let mut words = String::new();
+    for token in tokens {
+        if !words.is_empty() {
+            words.push_str(if token.separator == Separator::Dash { " - " } else { " " });
         }
-        None
+        words.push_str(&token.text.replace('.', " "));
     }
+    words
+}
 
-    fn extract_tmdb_id(&self, release_name: &str) -> Option<String> {
-        // Match TMDB ID in curly braces: {tmdb-919207}
-        if let Ok(re) = Regex::new(r"\{tmdb-(\d+)\}") {
-            if let Some(cap) = re.captures(release_name) {
-                return Some(cap.get(1).unwrap().as_str().to_string());
-            }
+/// Whether a source ends the title wherever it is: a source in brackets, or
+/// a keyword written as in [`rules::SOURCES`] that is no common word
+/// (`BluRay`, but not `Theater` or the `Cam` of "Cam.2018").
+fn is_strong_source(release_name: &str, source: &str, span: &Range<usize>, Rule(rule, _): &Rule) -> bool {
+    *rule != "source.keyword" || (&release_name[span.clone()] == source && !rules::TITLE_WORD_SOURCES.contains(&source))
+}
+
+/// Value of an Arabic or Roman numeral: `3` or `III`.
+fn numeral(text: &str) -> Option<u16> {
+    if let Ok(number) = text.parse() {
+        return Some(number);
+    }
+    let digits: Vec<i32> = text
+        .chars()
+        .map(|c| match c {
+            'I' => Some(1),
+            'V' => Some(5),
+            'X' => Some(10),
+            'L' => Some(50),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    // A digit before a larger one is subtracted: IV, IX, XL
+    let value: i32 = digits.iter().enumerate().map(|(i, digit)| if digits.get(i + 1).is_some_and(|next| next > digit) { -digit } else { *digit }).sum();
+    let value = u16::try_from(value).ok()?;
+    // Only as the number is written, not IIII or VX
+    (roman(value) == text).then_some(value)
+}
+
+/// A number up to 89 as a Roman numeral: `4` is `IV`.
+fn roman(mut value: u16) -> String {
+    let mut numeral = String::new();
+    for (step, digits) in [(50, "L"), (40, "XL"), (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I")] {
+        while value >= step {
+            numeral.push_str(digits);
+            value -= step;
         }
-        // Also match in square brackets: [tmdb-1520211] or [tmdbid-1520211]
-        if let Ok(re) = Regex::new(r"\[tmdb(?:id)?-(\d+)\]") {
-            if let Some(cap) = re.captures(release_name) {
-                return Some(cap.get(1).unwrap().as_str().to_string());
-            }
+    }
+    numeral
+}
+
+/// Byte span of `needle` in `haystack`, where `haystack` starts at `offset` in the release name.
+fn span_of(haystack: &str, needle: &str, offset: usize) -> Option<Range<usize>> {
+    haystack.find(needle).map(|start| offset + start..offset + start + needle.len())
+}
+
+/// Span of a match without its surrounding whitespace.
+fn trimmed_span(found: regex::Match) -> Range<usize> {
+    let text = found.as_str();
+    let start = found.start() + (text.len() - text.trim_start().len());
+    start..start + text.trim().len()
+}
+
+fn clean_title(title: &str) -> String {
+    let mut cleaned = title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    
+    // Remove any remaining empty parentheses or brackets
+    let empty_paren_re = regex!(r"\(\s*\)");
+    cleaned = empty_paren_re.replace_all(&cleaned, " ").to_string();
+    let empty_bracket_re = regex!(r"\[\s*\]");
+    cleaned = empty_bracket_re.replace_all(&cleaned, " ").to_string();
+    
+    cleaned.trim().to_string()
+}
+
+/// Last component of a directory path, `None` if there is none or it's not UTF-8.
+fn directory_name(path: &Path) -> Option<&str> {
+    path.file_name()?.to_str()
+}
+
+/// A value found in the release name, with the byte span it was read from
+/// and the rule that matched.
+type Found<T> = Option<(T, Range<usize>, Rule)>;
+
+/// Every match of an extractor, best first.
+type Candidates<T> = Vec<(T, Range<usize>, Rule)>;
+
+/// The result of [`ReleaseParser::analyze`]: the parsed release, its tokens
+/// and the field each token was assigned to.
+pub(crate) struct Analysis {
+    pub(crate) parsed: ParsedRelease,
+    pub(crate) tokens: Vec<Token>,
+    /// Field owning each token, `None` for tokens no field claimed
+    pub(crate) owners: Vec<Option<&'static str>>,
+    /// Values used for the fields of `parsed`
+    pub(crate) fields: Vec<FieldReport>,
+    /// Values found but not used
+    pub(crate) alternatives: Vec<FieldReport>,
+}
+
+impl Analysis {
+    fn new(release_name: &str) -> Self {
+        let tokens = tokenize(release_name);
+        Self {
+            parsed: ParsedRelease::default(),
+            owners: vec![None; tokens.len()],
+            tokens,
+            fields: Vec::new(),
+            alternatives: Vec::new(),
         }
-        None
     }
 
-    fn extract_tvdb_id(&self, release_name: &str) -> Option<String> {
-        // Match TVDB ID in curly braces: {tvdb-79169}
-        if let Ok(re) = Regex::new(r"\{tvdb-(\d+)\}") {
-            if let Some(cap) = re.captures(release_name) {
-                return Some(cap.get(1).unwrap().as_str().to_string());
-            }
+    /// Give the tokens inside `span` to `field`.
+    ///
+    /// Returns `false` if every token in the span already belongs to another
+    /// field. A span inside a single word (e.g. `x264` in `x264-GROUP` before
+    /// the group is split off) covers no token and is always accepted.
+    fn claim(&mut self, field: &'static str, span: &Range<usize>) -> bool {
+        let inside: Vec<usize> = (0..self.tokens.len()).filter(|&i| self.tokens[i].is_within(span)).collect();
+        if !inside.is_empty() && inside.iter().all(|&i| self.owners[i].is_some_and(|owner| owner != field)) {
+            return false;
         }
-        // Also match in square brackets: [tvdb-1520211] or [tvdbid-1520211]
-        if let Ok(re) = Regex::new(r"\[tvdb(?:id)?-(\d+)\]") {
-            if let Some(cap) = re.captures(release_name) {
-                return Some(cap.get(1).unwrap().as_str().to_string());
-            }
+        for i in inside {
+            self.owners[i].get_or_insert(field);
         }
-        None
+        true
     }
 
-    fn extract_imdb_id(&self, release_name: &str) -> Option<String> {
-        // Match IMDB ID in curly braces: {imdb-tt0066921}
-        if let Ok(re) = Regex::new(r"\{imdb-(tt\d+)\}") {
-            if let Some(cap) = re.captures(release_name) {
-                return Some(cap.get(1).unwrap().as_str().to_string());
-            }
-        }
-        // Match in square brackets: [imdb-tt1520211] or [imdbid-tt1520211]
-        if let Ok(re) = Regex::new(r"\[imdb(?:id)?-(tt\d+)\]") {
-            if let Some(cap) = re.captures(release_name) {
-                return Some(cap.get(1).unwrap().as_str().to_string());
-            }
-        }
-        None
+    /// Claim a found value, dropping it if its tokens are already taken.
+    fn take<T: Describe>(&mut self, field: &'static str, found: Found<T>) -> Option<T> {
+        self.take_first(field, found.into_iter().collect())
     }
 
-    fn extract_edition(&self, release_name: &str) -> Option<String> {
-        // Match edition in curly braces: {edition-Ultimate Extended Edition}
-        if let Ok(re) = Regex::new(r"\{edition-([^}]+)\}") {
-            if let Some(cap) = re.captures(release_name) {
-                return Some(cap.get(1).unwrap().as_str().trim().to_string());
-            }
-        }
-        // Match edition in square brackets: [U-Edition]
-        if let Ok(re) = Regex::new(r"\[([A-Z]-Edition)\]") {
-            if let Some(cap) = re.captures(release_name) {
-                return Some(cap.get(1).unwrap().as_str().trim().to_string());
+    /// Claim the first candidate whose tokens are still free. The other
+    /// candidates are kept as alternatives, once per value; matches inside a
+    /// word (`TS` in `DTS`) are not worth mentioning and are left out.
+    fn take_first<T: Describe>(&mut self, field: &'static str, candidates: Candidates<T>) -> Option<T> {
+        let mut chosen = None;
+        let mut others: Vec<FieldReport> = Vec::new();
+        for (value, span, rule) in candidates {
+            let accepted = chosen.is_none() && self.claim(field, &span);
+            let covers_tokens = self.tokens.iter().any(|token| token.is_within(&span));
+            let reports = value.describe(field, span, rule);
+            if accepted {
+                self.fields.extend(reports);
+                chosen = Some(value);
+            } else if covers_tokens {
+                others.extend(reports);
             }
         }
-        None
+        self.add_alternatives(others);
+        chosen
     }
 
-    fn extract_episode_number(&self, release_name: &str) -> Option<String> {
-        // Match episode number format: 001, 001-003
-        // Pattern: - 001 - or - 001-003 -
-        if let Ok(re) = Regex::new(r"-\s*(\d{3}(?:-\d{3})?)\s*-") {
-            if let Some(cap) = re.captures(release_name) {
-                return Some(cap.get(1).unwrap().as_str().to_string());
-            }
-        }
-        // Also handle episode numbers in brackets: [119]
-        // Need to check all brackets to find episode numbers (not just first match)
-        if let Ok(re) = Regex::new(r"\[(\d{1,4})\]") {
-            for cap in re.captures_iter(release_name) {
-                let ep_num = cap.get(1).unwrap().as_str();
-                // Only return if it's not part of a year pattern [2023]
-                // Episode numbers are typically 1-3 digits, or 4 digits that don't start with 19/20
-                if ep_num.len() <= 3 {
-                    return Some(ep_num.to_string());
-                } else if ep_num.len() == 4 {
-                    if let Ok(year) = ep_num.parse::<u16>() {
-                        // If it's a valid year (1900-2100), skip it
-                        if (1900..=2100).contains(&year) {
-                            continue;
-                        }
-                    }
-                    return Some(ep_num.to_string());
-                }
+    /// Keep values that were found but not used, once per value.
+    fn add_alternatives(&mut self, reports: Vec<FieldReport>) {
+        for other in reports {
+            let known = self.fields.iter().chain(&self.alternatives).any(|report| report.field == other.field && report.value.eq_ignore_ascii_case(&other.value));
+            if !known {
+                self.alternatives.push(other);
             }
         }
-        None
     }
 
-    fn extract_date(&self, release_name: &str) -> Option<String> {
-        // Match date format: 2013-10-30
-        // Pattern: - 2013-10-30 -
-        if let Ok(re) = Regex::new(r"-\s*(\d{4}-\d{2}-\d{2})\s*-") {
-            if let Some(cap) = re.captures(release_name) {
-                return Some(cap.get(1).unwrap().as_str().to_string());
-            }
-        }
-        None
+    /// Record a value that was not read through [`take`](Self::take).
+    fn record(&mut self, field: &'static str, value: impl ToString, span: Option<Range<usize>>, Rule(rule, confidence): Rule) {
+        self.fields.push(FieldReport {
+            field: field.to_string(),
+            value: value.to_string(),
+            rule: rule.to_string(),
+            confidence,
+            span,
+        });
     }
 
-    fn extract_hdr(&self, release_name: &str) -> String {
-        // Match HDR information: HDR10, DV HDR10, HDR10Plus, DV HDR10Plus
-        // Check for DV HDR10Plus first (longest match)
-        if release_name.contains("DV HDR10Plus") || release_name.contains("DV.HDR10Plus") {
-            return "DV HDR10Plus".to_string();
-        }
-        if release_name.contains("HDR10Plus") {
-            return "HDR10Plus".to_string();
-        }
-        if release_name.contains("DV HDR10") || release_name.contains("DV.HDR10") {
-            return "DV HDR10".to_string();
-        }
-        if release_name.contains("HDR10") {
-            return "HDR10".to_string();
-        }
-        String::new()
-    }
-
-    fn extract_streaming_provider(&self, release_name: &str) -> String {
-        // Complete list of streaming providers
-        // Based on: https://en.wikipedia.org/wiki/Pirated_movie_release_types
-        let providers = vec![
-            // International streaming services
-            "9NOW", "A3P", "AE", "ABC", "AJAZ", "ALL4", "AMC", "AMZN", "Amazon", "Prime Video", "Prime",
-            "ANLB", "ANPL", "APPS", "ARD", "AS", "ATVP", "Apple TV+", "AppleTV", "Apple",
-            "AUBC", "BCORE", "BK", "BNGE", "BOOM", "BRAV", "CBC", "CBS", "CC", "CHGD", "CLBI",
-            "CMAX", "Cinemax", "CMOR", "CMT", "CN", "CNBC", "CNLP", "COOK", "CR", "Crunchyroll",
-            "CRAV", "CRIT", "CRKL", "CRKI", "CSPN", "CTV", "CUR", "CW", "CWS", "DCU", "DDY",
-            "DEST", "DF", "DISC", "Discovery", "Discovery+", "Discovery Plus", "DIY", "DPLY",
-            "DRPO", "DRTV", "DSCP", "DSNP", "Disney+", "DisneyPlus", "Disney", "DTV",
-            "DW", "DLWP", "EPIX", "ESPN", "ESPN+", "ESPN Plus", "ESQ", "ETTV", "ETV",
-            "FAH", "FAM", "FBWatch", "FJR", "FOOD", "FOX", "FPT", "FREE", "FTV", "FUNI", "Funimation",
-            "FXTL", "FYI", "GC", "GLBL", "GLOB", "GLBO", "GO90", "GPLAY", "Google Play", "PLAY",
-            "HBO", "HBO Max", "HMAX", "MAX", "Max", "HGTV", "HIDI", "HIDIVE", "HIST", "HLMK",
-            "HPLAY", "HTSR", "HS", "HULU", "Hulu", "iP", "BBC iPlayer", "BBC", "iQIYI",
-            "iT", "iTunes", "ITV", "ITVX", "JC", "KAYO", "KNOW", "KNPY", "KS", "LGP", "LIFE",
-            "LN", "MA", "Movies Anywhere", "MBC", "MMAX", "MNBC", "MS", "Microsoft Store",
-            "MTOD", "MTV", "MUBI", "MY5", "NATG", "NBA", "NBC", "NBLA", "NF", "Netflix",
-            "NFL", "NFLN", "NICK", "NOW", "NRK", "ODK", "OPTO", "OSN", "OXGN", "PBS", "PBSK",
-            "PCOK", "Peacock", "PLUZ", "PMNT", "PMTP", "Paramount+", "Paramount Plus", "Paramount",
-            "POGO", "PSN", "PlayStation Network", "PUHU", "QIBI", "RED", "YouTube Premium", "YouTube Red",
-            "RKTN", "ROKU", "RSTR", "RTE", "RTP", "RTPPLAY", "SAINA", "SP", "SBS", "SESO",
-            "SHDR", "SHMI", "SHO", "Showtime", "Showtime Anytime", "SKST", "SkyShowtime",
-            "SLNG", "SNET", "SNXT", "SPIK", "SPRT", "SS", "STAN", "STRP", "STZ", "STARZ", "Starz",
-            "SVT", "SYFY", "TEN", "TIMV", "TK", "TLC", "TOU", "TRVL", "TUBI", "TV2", "TV3", "TV4",
-            "TVING", "TVL", "TVNZ", "UFC", "UKTV", "UNIV", "USAN", "VH1", "VIAP", "Viaplay",
-            "VICE", "VIKI", "VIU", "VLCT", "VMEO", "Vimeo", "VRV", "VTRN", "WAVVE", "WNET", "WTCH", "WWEN",
-            "WWE Network", "XBOX", "Xbox Video", "YT", "YouTube", "YouTube Movies", "YouTube TV",
-            "ZDF",
-            // Japanese streaming services
-            "ABMA", "ADN", "ANIMAX", "AO", "AT-X", "ATX", "Baha", "B-Global", "Bstation", "BSP",
-            "NHK-BSP", "BS4", "BS5", "EX-BS", "BS-EX", "BS6", "BS7", "BSJ", "BS-TX", "BS8",
-            "BS-Fuji", "BS11", "BS12", "CS-Fuji ONE", "CX", "DMM", "EX", "CS3", "EX-CS1",
-            "CS-EX1", "CSA", "FOD", "FUNi", "KBC", "M-ON!", "MX", "NHKG", "NHKE", "NTV", "TBS",
-            "TX", "UNXT", "U-NEXT", "WAKA", "Wakanim", "WOWOW", "Wowow", "YTV",
-        ];
-        
-        // Check for AMZN in "AMZN WEBDL" format first (before other patterns)
-        if let Ok(re) = Regex::new(r"(?i)\[AMZN\s+WEBDL") {
-            if re.is_match(release_name) {
-                return "AMZN".to_string();
-            }
-        }
+    /// Unclaimed tokens from the first one up to the next claimed token, or
+    /// the next token a value was read from.
+    fn leading_unclaimed(&self) -> impl Iterator<Item = &Token> {
+        self.tokens
+            .iter()
+            .zip(&self.owners)
+            .skip_while(|(_, owner)| owner.is_some())
+            .take_while(|(token, owner)| owner.is_none() && !self.is_read(token))
+            .map(|(token, _)| token)
+    }
 
-        // Pattern: resolution (1080p/720p) -> provider -> WEB-DL/WEBRip
-        // Match: 1080p.SKST.WEB-DL or 720p.MAX.WEB-DL
-        if let Ok(re) = Regex::new(r"(?i)(\d+p)\.([A-Z0-9]+)\.(?:WEB-DL|WEBRip|WEBDL)") {
-            if let Some(caps) = re.captures(release_name) {
-                let provider = caps.get(2).unwrap().as_str();
-                // Check if it's a known provider
-                for known_provider in &providers {
-                    if provider.eq_ignore_ascii_case(known_provider) {
-                        return known_provider.to_string();
-                    }
-                }
-                // If it's a short uppercase word (2-6 chars), it's likely a provider
-                if provider.len() >= 2 && provider.len() <= 6 && provider.chars().all(|c| c.is_uppercase() || c.is_ascii_digit()) {
-                    return provider.to_string();
-                }
-            }
-        }
-        
-        // Pattern: CR WEB-DL (Crunchyroll)
-        if let Ok(re) = Regex::new(r"(?i)\bCR\s+(?:WEB-DL|WEBRip|WEBDL)") {
-            if re.is_match(release_name) {
-                return "CR".to_string();
-            }
-        }
-        
-        // Pattern: NF WEB-DL (Netflix)
-        if let Ok(re) = Regex::new(r"(?i)\bNF\s+(?:WEB-DL|WEBRip|WEBDL)") {
-            if re.is_match(release_name) {
-                return "NF".to_string();
-            }
-        }
+    /// Tokens no field claimed and no value was read from.
+    fn unparsed(&self) -> impl Iterator<Item = &Token> {
+        self.tokens.iter().zip(&self.owners).filter_map(|(token, owner)| (owner.is_none() && !self.is_read(token)).then_some(token))
+    }
 
-        // Also check for providers mentioned elsewhere in the release
-        for provider in &providers {
-            if let Ok(re) = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(provider))) {
-                if re.is_match(release_name) {
-                    return provider.to_string();
-                }
+    /// Whether a value was read from inside the token, like `x264` from `DD5.1x264`.
+    fn is_read(&self, token: &Token) -> bool {
+        self.fields.iter().filter_map(|report| report.span.as_ref()).any(|span| span.start < token.span.end && token.span.start < span.end)
+    }
+
+    /// Span from the first to the last token owned by `field`.
+    fn field_span(&self, field: &str) -> Option<Range<usize>> {
+        let mut owned = self.tokens.iter().zip(&self.owners).filter(|(_, owner)| **owner == Some(field)).map(|(token, _)| &token.span);
+        let first = owned.next()?;
+        let last = owned.next_back().unwrap_or(first);
+        Some(first.start..last.end)
+    }
+
+    /// Unclaimed tokens right after the tokens of `field`.
+    fn unclaimed_after<'a>(&'a self, field: &'a str) -> impl Iterator<Item = &'a Token> {
+        self.tokens
+            .iter()
+            .zip(&self.owners)
+            .skip_while(move |(_, owner)| **owner != Some(field))
+            .skip_while(move |(_, owner)| **owner == Some(field))
+            .take_while(|(_, owner)| owner.is_none())
+            .map(|(token, _)| token)
+    }
+
+    /// Span of an episode title set off by a dash, up to the first bracket:
+    /// the "Multi Episode Title" of `S01E01-E03 - Multi Episode Title [WEBDL-1080p]`.
+    fn dashed_episode_title(&self) -> Option<Range<usize>> {
+        let field = ["episode", "date"].into_iter().find(|field| self.field_span(field).is_some())?;
+        let mut tokens = self.unclaimed_after(field).take_while(|token| !matches!(token.separator, Separator::Bracket | Separator::Brace | Separator::Group));
+        let first = tokens.next().filter(|token| token.separator == Separator::Dash)?;
+        let last = tokens.last().unwrap_or(first);
+        Some(first.span.start..last.span.end)
+    }
+
+    /// Start of the first token a field other than the group claimed, where the title ends.
+    fn title_end(&self) -> Option<usize> {
+        self.tokens.iter().zip(&self.owners).find(|(_, owner)| owner.is_some_and(|owner| owner != "group")).map(|(token, _)| token.span.start)
+    }
+}
+
+/// Turns an extracted value into the field reports it stands for.
+trait Describe {
+    fn describe(&self, field: &'static str, span: Range<usize>, rule: Rule) -> Vec<FieldReport>;
+}
+
+fn field_report(field: &str, value: String, span: Range<usize>, Rule(rule, confidence): Rule) -> FieldReport {
+    FieldReport {
+        field: field.to_string(),
+        value,
+        rule: rule.to_string(),
+        confidence,
+        span: Some(span),
+    }
+}
+
+macro_rules! describe_with_to_string {
+    ($($ty:ty),*) => {
+        $(impl Describe for $ty {
+            fn describe(&self, field: &'static str, span: Range<usize>, rule: Rule) -> Vec<FieldReport> {
+                vec![field_report(field, self.to_string(), span, rule)]
             }
+        })*
+    };
+}
+
+describe_with_to_string!(String, u8, u16, u128, AirDate, PackKind);
+
+/// `(kind, number)`: a special and, when it has one, its number as the episode.
+impl Describe for (SpecialKind, Option<u128>) {
+    fn describe(&self, field: &'static str, span: Range<usize>, rule: Rule) -> Vec<FieldReport> {
+        let (kind, number) = self;
+        let mut reports = vec![field_report(field, kind.to_string(), span.clone(), rule)];
+        if let Some(number) = number {
+            reports.push(field_report("episode", number.to_string(), span, rule));
         }
-        
-        // Check for AMZN in various formats
-        if release_name.contains("AMZN WEBDL") || release_name.contains("AMZN.WEBDL") {
-            return "AMZN".to_string();
-        }
+        reports
+    }
+}
 
-        String::new()
+/// `(part, part_total)`: a part and, when given, the number of parts.
+impl Describe for (u16, Option<u16>) {
+    fn describe(&self, field: &'static str, span: Range<usize>, rule: Rule) -> Vec<FieldReport> {
+        let (part, total) = self;
+        let mut reports = vec![field_report(field, part.to_string(), span.clone(), rule)];
+        if let Some(total) = total {
+            reports.push(field_report("part_total", total.to_string(), span, rule));
+        }
+        reports
     }
 }
 
-fn clean_title(title: &str) -> String {
-    let mut cleaned = title
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ");
-    
-    // Remove any remaining empty parentheses or brackets
-    if let Ok(empty_paren_re) = Regex::new(r"\(\s*\)") {
-        cleaned = empty_paren_re.replace_all(&cleaned, " ").to_string();
+/// Seasons of a pack.
+impl Describe for Vec<u128> {
+    fn describe(&self, field: &'static str, span: Range<usize>, rule: Rule) -> Vec<FieldReport> {
+        vec![field_report(field, self.iter().map(|season| season.to_string()).collect::<Vec<_>>().join(","), span, rule)]
     }
-    if let Ok(empty_bracket_re) = Regex::new(r"\[\s*\]") {
-        cleaned = empty_bracket_re.replace_all(&cleaned, " ").to_string();
+}
+
+/// `(season, episode, episodes)`: the season (when there is one) and the episode list.
+impl Describe for (Option<u128>, u128, Vec<u128>) {
+    fn describe(&self, field: &'static str, span: Range<usize>, rule: Rule) -> Vec<FieldReport> {
+        let (season, _, episodes) = self;
+        let episodes = episodes.iter().map(|episode| episode.to_string()).collect::<Vec<_>>().join(",");
+        let mut reports = Vec::new();
+        if let Some(season) = season {
+            reports.push(field_report("season", season.to_string(), span.clone(), rule));
+        }
+        reports.push(field_report(field, episodes, span, rule));
+        reports
     }
-    
-    cleaned.trim().to_string()
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -2153,7 +2060,9 @@ mod tests {
         let parsed = parser.parse(release);
 
         assert!(parsed.flags.contains(&"10bit".to_string()));
-        assert_eq!(parsed.source, "iNTERNAL");
+        // iNTERNAL is a flag; as a source too its token would have two fields
+        assert_eq!(parsed.source, "HDTV");
+        assert!(parsed.flags.contains(&"INTERNAL".to_string()));
         assert_eq!(parsed.resolution, "720p");
         assert!(parsed.language.contains_key("ja"));
         // Episode number "001" is set in episode field
@@ -2166,10 +2075,210 @@ mod tests {
         let release = "The Series Title! (2010) - 2013-10-30 - Episode Title 1 [AMZN WEBDL-1080p Proper][DV HDR10][DTS 5.1][x264]-RlsGrp";
         let parsed = parser.parse(release);
 
-        assert_eq!(parsed.date, Some("2013-10-30".to_string()));
+        assert_eq!(parsed.date, AirDate::new(2013, 10, 30));
         assert_eq!(parsed.episode_title.contains("Episode Title 1"), true);
     }
 
+    #[test]
+    fn test_multi_episode_syntax() {
+        let parser = ReleaseParser::new("tv");
+        let releases = [
+            ("Show.S01E01E02E03.720p.HDTV.x264-GRP", vec![1, 2, 3]),
+            ("Show.S01E01-02.720p.HDTV.x264-GRP", vec![1, 2]),
+            ("Show.S01E01-E03.720p.HDTV.x264-GRP", vec![1, 2, 3]),
+            ("Show.1x01-1x03.720p.HDTV.x264-GRP", vec![1, 2, 3]),
+            ("Show.1x01x02.720p.HDTV.x264-GRP", vec![1, 2]),
+            ("Show.S01E01+E02.720p.HDTV.x264-GRP", vec![1, 2]),
+            ("Show.S01E01.E02.720p.HDTV.x264-GRP", vec![1, 2]),
+            ("Show.S01E01E03.720p.HDTV.x264-GRP", vec![1, 3]),
+        ];
+        for (release, episodes) in releases {
+            let parsed = parser.parse(release);
+            assert_eq!((parsed.title.as_str(), parsed.season, parsed.episode), ("Show", Some(1), None), "{}", release);
+            assert_eq!(parsed.episodes, episodes, "{}", release);
+        }
+        assert_eq!(parser.parse("Show.E01E02.720p.HDTV.x264-GRP").episodes, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_rejected_episode_ranges() {
+        let parser = ReleaseParser::new("tv");
+        // Descending and absurd ranges fall back to their first episode
+        for release in ["Show.S01E05-E02.720p.HDTV.x264-GRP", "Show.S01E05-E999.720p.HDTV.x264-GRP", "Show.S01E05E04.720p.HDTV.x264-GRP"] {
+            let parsed = parser.parse(release);
+            assert_eq!((parsed.season, parsed.episode, parsed.episodes), (Some(1), Some(5), vec![5]), "{}", release);
+        }
+        // A range may not cross seasons
+        assert_eq!(parser.parse("Show.1x24-2x01.720p.HDTV.x264-GRP").episodes, vec![24]);
+        let parsed = parser.parse("Show - 010-001 - Title [HDTV-720p]-GRP");
+        assert!(parsed.episodes.len() <= 1, "{:?}", parsed.episodes);
+    }
+
+    #[test]
+    fn test_specials() {
+        let parser = ReleaseParser::new("tv");
+        // Season 0 is kept, apart from "no season"
+        let parsed = parser.parse("Show.S00E05.720p.HDTV.x264-GRP");
+        assert_eq!((parsed.season, parsed.episode, parsed.special_kind), (Some(0), Some(5), Some(SpecialKind::Special)));
+        let parsed = parser.parse("Show.E05.720p.HDTV.x264-GRP");
+        assert_eq!((parsed.season, parsed.special_kind), (None, None));
+
+        let releases = [
+            ("Show.Special.720p.HDTV.x264-GRP", "Show", SpecialKind::Special, None),
+            ("[Group] Frieren - OVA 02 [1080p]", "Frieren", SpecialKind::Ova, Some(2)),
+            ("[Group] Frieren - NCOP [1080p]", "Frieren", SpecialKind::CreditlessOpening, None),
+            ("[Group] Frieren - NCED2 [1080p]", "Frieren", SpecialKind::CreditlessEnding, Some(2)),
+            ("Frieren - SP3 [1080p]", "Frieren", SpecialKind::Special, Some(3)),
+            ("Frieren.ONA.720p.WEB.x264-GRP", "Frieren", SpecialKind::Ona, None),
+            ("Show.S01E00.Pilot.720p.HDTV.x264-GRP", "Show", SpecialKind::Pilot, Some(0)),
+        ];
+        for (release, title, kind, episode) in releases {
+            let parsed = parser.parse(release);
+            assert_eq!((parsed.title.as_str(), parsed.special_kind, parsed.episode), (title, Some(kind), episode), "{}", release);
+        }
+        let parsed = parser.parse("Show.Christmas.Special.2023.720p.HDTV.x264-GRP");
+        assert_eq!((parsed.special_kind, parsed.year), (Some(SpecialKind::Special), Some(2023)));
+
+        // A word of the title before the episode marker
+        let parsed = parser.parse("Law.and.Order.Special.Victims.Unit.S01E01.720p.HDTV.x264-GRP");
+        assert_eq!((parsed.title.as_str(), parsed.special_kind), ("Law and Order Special Victims Unit", None));
+
+        // A word of the episode title of a regular episode
+        let parsed = parser.parse("Show.S01E01.The.Special.One.720p.HDTV.x264-GRP");
+        assert_eq!((parsed.episode_title.as_str(), parsed.special_kind), ("The Special One", None));
+        let parsed = parser.parse("Show.S03E04.Return.of.the.Pilot.720p.HDTV.x264-GRP");
+        assert_eq!((parsed.episode_title.as_str(), parsed.special_kind), ("Return of the Pilot", None));
+        assert_eq!(parser.parse("Show.S01E02.Pilot.720p.HDTV.x264-GRP").special_kind, None);
+    }
+
+    #[test]
+    fn test_scene_audio_and_episode_title() {
+        let parser = ReleaseParser::new("tv");
+        // The MA of DTS-HD.MA is audio, not a streaming provider
+        let parsed = parser.parse("Show.S01E02.1080p.WEB-DL.DTS-HD.MA.5.1.H.265-GRP");
+        assert_eq!((parsed.audio.as_str(), parsed.streaming_provider.as_str(), parsed.episode_title.as_str()), ("DTS-HD MA 5.1", "", ""));
+        let parsed = parser.parse("Show.S01E02.1080p.AMZN.WEB-DL.DDP.Atmos.5.1.H.264-GRP");
+        assert_eq!((parsed.audio.as_str(), parsed.streaming_provider.as_str()), ("DDP Atmos 5.1", "AMZN"));
+
+        // The episode title of a multi-episode release ends where the tags start
+        let parsed = parser.parse("Show.S01E01-E02.Rising.Part.One.720p.HDTV.x264-GRP");
+        assert_eq!((parsed.episodes, parsed.episode_title.as_str()), (vec![1, 2], "Rising Part One"));
+    }
+
+    #[test]
+    fn test_parts_and_volumes() {
+        let parser = ReleaseParser::new("tv");
+        let releases = [
+            ("Planet.Earth.Part.3.of.6.720p.BluRay.x264-GRP", "Planet Earth", Some(3), Some(6), None),
+            ("Show.Pt.2.720p.WEB.h264-GRP", "Show", Some(2), None, None),
+            ("Documentary.Part.II.1080p.BluRay.x264-GRP", "Documentary", Some(2), None, None),
+            ("Doku.Teil.2.von.3.German.720p.HDTV.x264-GRP", "Doku", Some(2), Some(3), None),
+            ("Docu.Partie.2.sur.3.FRENCH.720p.HDTV.x264-GRP", "Docu", Some(2), Some(3), None),
+            ("[Group] Frieren Vol.3 [1080p]", "Frieren", None, None, Some(3)),
+            ("Anime Vol.3", "Anime", None, None, Some(3)),
+            ("Show.Part.XLIX.720p.HDTV.x264-GRP", "Show", Some(49), None, None),
+            // Not numerals as they are written
+            ("Show.Part.IIII.720p.HDTV.x264-GRP", "Show Part IIII", None, None, None),
+            ("Show.Part.VX.720p.HDTV.x264-GRP", "Show Part VX", None, None, None),
+        ];
+        for (release, title, part, total, volume) in releases {
+            let parsed = parser.parse(release);
+            assert_eq!((parsed.title.as_str(), parsed.part, parsed.part_total, parsed.volume), (title, part, total, volume), "{}", release);
+        }
+        assert_eq!(parser.parse("Planet.Earth.Part.3.of.6.720p.BluRay.x264-GRP").episode, Some(3));
+
+        // Left in the episode title after an episode marker
+        let parsed = parser.parse("Show.S01E02.The.Reckoning.Part.2.720p.HDTV.x264-GRP");
+        assert_eq!((parsed.episode, parsed.part, parsed.episode_title.as_str()), (Some(2), Some(2), "The Reckoning Part 2"));
+
+        // In a movie, a part before the year belongs to the title
+        let movies = ReleaseParser::new("movie");
+        let parsed = movies.parse("Harry.Potter.and.the.Deathly.Hallows.Part.2.2011.1080p.BluRay.x264-GRP");
+        assert_eq!((parsed.title.as_str(), parsed.part), ("Harry Potter and the Deathly Hallows Part 2", None));
+        assert_eq!(movies.parse("Vanilla.Sky.2001.Part.2.1080p.BluRay.x264-GRP").part, Some(2));
+        assert_eq!(ReleaseParser::auto().parse("Planet.Earth.Part.3.of.6.720p.BluRay.x264-GRP").part, Some(3));
+
+        // Before the year a part without a total belongs to the title, in TV too
+        let parsed = parser.parse("A Nightmare on Elm Street Part 2 Freddys Revenge (1985) {tmdb-10014}");
+        assert_eq!((parsed.title.as_str(), parsed.part, parsed.episode), ("A Nightmare on Elm Street Part 2 Freddys Revenge", None, None));
+        let parsed = parser.parse("Show (2010) - Part 2 [WEBDL-720p]-GRP");
+        assert_eq!((parsed.title.as_str(), parsed.part, parsed.episode), ("Show", Some(2), Some(2)));
+    }
+
+    #[test]
+    fn test_season_packs() {
+        let parser = ReleaseParser::new("tv");
+        let parsed = parser.parse("Show.S02.1080p.BluRay.x264-GRP");
+        assert_eq!((parsed.title.as_str(), parsed.season, parsed.seasons, parsed.pack), ("Show", Some(2), vec![2], Some(PackKind::Season)));
+        assert_eq!(parsed.episodes, Vec::<u128>::new());
+
+        let parsed = parser.parse("Show.Season.1-3.Complete.720p.HDTV.x264-GRP");
+        assert_eq!((parsed.title.as_str(), parsed.season, parsed.seasons, parsed.pack), ("Show", None, vec![1, 2, 3], Some(PackKind::SeasonRange)));
+        assert_eq!(parsed.episode, None);
+
+        let parsed = parser.parse("Show.S01-S05.COMPLETE.1080p.BluRay.x264-GRP");
+        assert_eq!((parsed.seasons, parsed.pack), (vec![1, 2, 3, 4, 5], Some(PackKind::SeasonRange)));
+        assert_eq!(parsed.flags, vec!["COMPLETE"]);
+
+        let parsed = ReleaseParser::auto().parse("Show.Complete.Series.1080p.BluRay.x264-GRP");
+        assert_eq!((parsed.release_type.as_str(), parsed.title.as_str(), parsed.pack), ("tv", "Show", Some(PackKind::CompleteSeries)));
+        assert!(parsed.seasons.is_empty());
+
+        // The names as written, with the pack at the end
+        let releases = [
+            ("Show.S02.1080p.BluRay.x264-GRP", vec![2], Some(PackKind::Season), "GRP"),
+            ("Show.Season.1-3.Complete", vec![1, 2, 3], Some(PackKind::SeasonRange), ""),
+            ("Show.S01-S05.COMPLETE", vec![1, 2, 3, 4, 5], Some(PackKind::SeasonRange), ""),
+            ("Show.Complete.Series", vec![], Some(PackKind::CompleteSeries), ""),
+        ];
+        for (release, seasons, pack, group) in releases {
+            let parsed = ReleaseParser::auto().parse(release);
+            assert_eq!((parsed.release_type.as_str(), parsed.title.as_str(), parsed.seasons, parsed.pack, parsed.group.as_str()), ("tv", "Show", seasons, pack, group), "{}", release);
+        }
+
+        // A range counting down is no pack, and stays out of the title
+        for release in ["Show.Season.3-1.720p-GRP", "Show.S03-S01.720p-GRP"] {
+            let parsed = parser.parse(release);
+            assert_eq!((parsed.title.as_str(), parsed.season, parsed.episode, parsed.pack), ("Show", None, None, None), "{}", release);
+        }
+
+        // Episodes are not packs
+        for release in ["Show.S01E01.720p.HDTV.x264-GRP", "Show Season 1 Episode 2 720p", "[Sub] Show S2 - 05 [1080p]"] {
+            let parsed = parser.parse(release);
+            assert_eq!((parsed.pack, parsed.seasons.len()), (None, 0), "{}", release);
+            assert!(parsed.episode.is_some(), "{}", release);
+        }
+    }
+
+    #[test]
+    fn test_daily_episode_layouts() {
+        let parser = ReleaseParser::auto();
+        let parsed = parser.parse("The.Daily.Show.2023.10.30.Guest.Name.720p.WEB.h264-GRP");
+        assert_eq!(parsed.release_type, "tv");
+        assert_eq!(parsed.title, "The Daily Show");
+        assert_eq!(parsed.date, AirDate::new(2023, 10, 30));
+        assert_eq!(parsed.episode_title, "Guest Name");
+        assert_eq!(parsed.year, None);
+
+        for release in ["Show.30.10.2023.720p.HDTV.x264-GRP", "Show.231030.720p.WEB-GRP", "Show.Oct.30.2023.720p.HDTV.x264-GRP", "Show 20231030 720p HDTV x264-GRP"] {
+            let parsed = parser.parse(release);
+            assert_eq!((parsed.title.as_str(), parsed.date), ("Show", AirDate::new(2023, 10, 30)), "{}", release);
+            assert_eq!(parsed.season, None, "{}", release);
+        }
+
+        // Not on the calendar
+        let parsed = ReleaseParser::new("tv").parse("Show.2023.02.30.720p.HDTV.x264-GRP");
+        assert_eq!(parsed.date, None);
+
+        // Numbers inside an ID or after the group are no air dates
+        let parsed = ReleaseParser::new("tv").parse("Show (2020) {tvdb-201231} - S01E01");
+        assert_eq!((parsed.date, parsed.season, parsed.episode, parsed.tvdb_id.as_deref()), (None, Some(1), Some(1), Some("201231")));
+        let parsed = ReleaseParser::new("tv").parse("Show.S02E05.720p.HDTV.x264-GRP.101231");
+        assert_eq!((parsed.date, parsed.season, parsed.episode, parsed.group.as_str()), (None, Some(2), Some(5), "GRP"));
+        let parsed = parser.parse("Movie (2019) {tmdb-191231} [Bluray-1080p]-GRP");
+        assert_eq!((parsed.release_type.as_str(), parsed.date, parsed.year), ("movie", None, Some(2019)));
+    }
+
     #[test]
     fn test_episode_number_range() {
         let parser = ReleaseParser::new("tv");
@@ -3607,4 +3716,265 @@ mod tests {
         assert_eq!(parsed.audio, "AAC");
         assert_eq!(parsed.group, "MMR");
     }
+
+    #[test]
+    fn test_tokens_assigned_to_one_field() {
+        let parser = ReleaseParser::new("movie");
+        let analysis = parser.analyze("The.Matrix.1999.1080p.BluRay.x264-GROUP");
+
+        let owners: Vec<_> = analysis.owners.iter().map(|owner| owner.unwrap_or("")).collect();
+        assert_eq!(owners, vec!["title", "title", "year", "resolution", "source", "format", "group"]);
+        assert_eq!(analysis.parsed.title, "The Matrix");
+    }
+
+    #[test]
+    fn test_edition_words_are_not_flags() {
+        let parser = ReleaseParser::new("movie");
+        let release = "The Movie Title (2010) - {edition-Ultimate Extended Edition} [Bluray-1080p Remux Proper][DTS 5.1][AVC]-RlsGrp";
+        let parsed = parser.parse(release);
+
+        assert_eq!(parsed.edition, Some("Ultimate Extended Edition".to_string()));
+        assert!(!parsed.flags.contains(&"Extended".to_string()));
+        assert!(parsed.flags.contains(&"PROPER".to_string()));
+    }
+
+    #[test]
+    fn test_language_code_and_flag_share_bracket() {
+        let parser = ReleaseParser::new("movie");
+        let analysis = parser.analyze("Letters.From.Iwo.Jima[2006]DvDrip[Eng.Hard.Sub]-aXXo");
+        let owner = |text: &str| {
+            let index = analysis.tokens.iter().position(|token| token.text == text).unwrap();
+            analysis.owners[index]
+        };
+
+        assert_eq!(owner("Eng"), Some("language"));
+        assert_eq!(owner("Hard"), Some("flags"));
+        assert_eq!(owner("Sub"), Some("flags"));
+    }
+
+    #[test]
+    fn test_title_words_are_not_providers() {
+        let parser = ReleaseParser::new("movie");
+        let parsed = parser.parse("The.Daily.Life.of.Pi.2012.1080p.BluRay.x264-GROUP");
+
+        assert_eq!(parsed.title, "The Daily Life of Pi");
+        assert_eq!(parsed.streaming_provider, "");
+    }
+
+    #[test]
+    fn test_title_from_leftover_tokens() {
+        let parser = ReleaseParser::new("movie");
+        let parsed = parser.parse("Ranma.1.2.2024");
+        assert_eq!((parsed.title.as_str(), parsed.year), ("Ranma 1 2", Some(2024)));
+        assert!(parsed.unparsed_tokens.is_empty());
+
+        let parsed = parser.parse("Spider-Man.No.Way.Home.2021.1080p.BluRay.x264-GROUP");
+        assert_eq!(parsed.title, "Spider-Man No Way Home");
+    }
+
+    #[test]
+    fn test_bracket_source_leaves_flags() {
+        let parser = ReleaseParser::new("movie");
+        let parsed = parser.parse("The Movie Title (2010) [iNTERNAL HDTV-720p v2]-RlsGrp");
+
+        assert_eq!(parsed.source, "HDTV");
+        assert!(parsed.flags.contains(&"INTERNAL".to_string()));
+        assert_eq!(parsed.resolution, "720p");
+        assert!(parsed.unparsed_tokens.is_empty());
+    }
+
+    #[test]
+    fn test_title_words_are_not_tags() {
+        let parser = ReleaseParser::new("tv");
+        let releases = [
+            ("Anime.Crimes.Division.S01E01", "Anime Crimes Division"),
+            ("Retail.Therapy.S01E01", "Retail Therapy"),
+            ("Limited.Partners.S01E01", "Limited Partners"),
+            ("Mad.Men.S01E01.720p.HDTV.x264-GRP", "Mad Men"),
+        ];
+        for (release, title) in releases {
+            let parsed = parser.parse(release);
+            assert_eq!((parsed.title.as_str(), parsed.flags.len(), parsed.episode), (title, 0, Some(1)), "{}", release);
+        }
+
+        let movies = ReleaseParser::new("movie");
+        let releases = [
+            ("The.Complete.Works.2010", "The Complete Works", 2010),
+            ("Nuked.2010", "Nuked", 2010),
+            ("Festival.Express.2003", "Festival Express", 2003),
+            ("Theater.Camp.2023.1080p.WEB-DL.x264-GRP", "Theater Camp", 2023),
+            ("2001.A.Space.Odyssey.1968", "2001 A Space Odyssey", 1968),
+            ("1917.2019", "1917", 2019),
+        ];
+        for (release, title, year) in releases {
+            let parsed = movies.parse(release);
+            assert_eq!((parsed.title.as_str(), parsed.flags.len(), parsed.year), (title, 0, Some(year)), "{}", release);
+        }
+        // After the year the same words are tags
+        let parsed = movies.parse("Festival.Express.2003.LIMITED.NUKED.1080p.BluRay.x264-GRP");
+        assert_eq!(parsed.flags, vec!["Limited", "NUKED"]);
+
+        // A bare language code between the title and the episode
+        let parsed = parser.parse("Show.DE.S01E01");
+        assert_eq!((parsed.title.as_str(), parsed.language.get("de").map(String::as_str)), ("Show", Some("German")));
+
+        // No dash is left dangling before the anime numbering
+        assert_eq!(parser.parse("[SubsPlease] Anime - 5 - 01 [1080p]").title, "Anime");
+    }
+
+    #[test]
+    fn test_typed_accessors() {
+        let parser = ReleaseParser::new("tv");
+        let release = "The Acolyte (2024) - S01E07 - Choice [WEBDL-2160p][DV HDR10][EAC3 Atmos 5.1][h265]";
+        let parsed = parser.parse(release);
+
+        assert_eq!(parsed.source(), Some(crate::Source::WebDl));
+        assert_eq!(parsed.resolution(), Some(crate::Resolution::P2160));
+        assert_eq!(parsed.video_codec(), Some(crate::VideoCodec::H265));
+        assert_eq!(parsed.hdr_format(), Some(crate::HdrFormat::DolbyVisionHdr10));
+        assert_eq!(parsed.audio_codec(), Some(crate::AudioCodec::Eac3));
+
+        let parsed = parser.parse("Some.Show.S01E01.720p");
+        assert_eq!(parsed.source(), None);
+        assert_eq!(parsed.hdr_format(), None);
+    }
+
+    #[test]
+    fn test_auto_release_type() {
+        let parser = ReleaseParser::auto();
+
+        let parsed = parser.parse("Breaking.Bad.S05E14.Ozymandias.1080p.WEB-DL.DD5.1.H.264-GROUP");
+        assert_eq!(parsed.release_type, "tv");
+        assert_eq!(parsed.season, Some(5));
+        assert_eq!(parsed.episode, Some(14));
+
+        let parsed = parser.parse("The.Matrix.1999.1080p.BluRay.x264-GROUP");
+        assert_eq!(parsed.release_type, "movie");
+        assert_eq!(parsed.title, "The Matrix");
+
+        let classification = parser.classify("Some.Game.NSW-VENOM");
+        assert_eq!(classification.release_type, "game");
+        assert!(classification.confidence > 0.5);
+    }
+
+    #[test]
+    fn test_classify_fixed_release_type() {
+        let classification = ReleaseParser::new("tv").classify("The.Matrix.1999.1080p.BluRay.x264-GROUP");
+        assert_eq!(classification.release_type, "tv");
+        assert_eq!(classification.confidence, 1.0);
+    }
+
+    #[test]
+    fn test_report_rules_and_confidence() {
+        let report = ReleaseParser::new("tv").parse_report("Breaking.Bad.S05E14.1080p.WEB-DL.DD5.1.H.264-GROUP");
+
+        let episode = report.field("episode").unwrap();
+        assert_eq!(episode.value, "14");
+        assert_eq!(episode.rule, "season_episode.sxxexx");
+        assert!(episode.confidence > 0.9);
+        assert_eq!(episode.span, Some(13..19));
+        assert_eq!(report.field("season").unwrap().value, "5");
+        assert_eq!(report.field("title").unwrap().span, Some(0..12));
+        assert_eq!(report.field("type").unwrap().rule, "type.configured");
+        assert_eq!(report.confidence("year"), None);
+
+        // The bare "Title - 5 - 01" anime numbering is a guess
+        let report = ReleaseParser::new("tv").parse_report("[SubsPlease] Show - 5 - 01 (1080p)");
+        assert_eq!(report.field("episode").unwrap().rule, "season_episode.anime_dash");
+        assert!(report.confidence("episode").unwrap() < 0.5);
+        // The absolute episode 5 it was weighed against
+        let episodes: Vec<&str> = report.alternatives("episode").map(|alternative| alternative.value.as_str()).collect();
+        assert_eq!(episodes, vec!["5"]);
+        assert_eq!(report.alternatives("episode").next().unwrap().span, Some(20..26));
+
+        // A provider name in the title is not the provider, but is noted
+        let report = ReleaseParser::new("movie").parse_report("Mad.Max.Fury.Road.2015.1080p.BluRay.x264-GRP");
+        assert_eq!(report.parsed.streaming_provider, "");
+        let providers: Vec<&str> = report.alternatives("streaming_provider").map(|alternative| alternative.value.as_str()).collect();
+        assert_eq!(providers, vec!["Max"]);
+    }
+
+    #[test]
+    fn test_report_alternatives() {
+        let report = ReleaseParser::new("movie").parse_report("2012.2009.1080p.BluRay.x264-GRP");
+        assert_eq!(report.parsed.year, Some(2009));
+        let years: Vec<&str> = report.alternatives("year").map(|alternative| alternative.value.as_str()).collect();
+        assert_eq!(years, vec!["2012"]);
+
+        // The losing source of a bracket and its span
+        let report = ReleaseParser::new("tv").parse_report("Arrow (2012) - S05E04 - Penance [Bluray-1080p Remux][DTS-HD MA 5.1][AVC]-EPSiLON");
+        assert_eq!(report.parsed.source, "Remux");
+        let source = report.alternatives("source").next().unwrap();
+        assert_eq!(source.value, "BluRay");
+        assert_eq!(source.span, Some(33..39));
+        // "TS" inside "DTS-HD" is not an alternative
+        assert_eq!(report.alternatives("source").count(), 1);
+    }
+
+    #[test]
+    fn test_report_detected_type() {
+        let release = "Some.Show.E05.720p-GRP";
+        let report = ReleaseParser::auto().parse_report(release);
+        let release_type = report.field("type").unwrap();
+        assert_eq!(release_type.value, "tv");
+        assert_eq!(release_type.rule, "type.detected");
+        assert_eq!(release_type.confidence, ReleaseParser::auto().classify(release).confidence);
+        assert_eq!(release_type.span, None);
+    }
+
+    #[test]
+    fn test_unparsed_tokens() {
+        let parser = ReleaseParser::new("tv");
+        let parsed = parser.parse("The.Show.S01E01.Pilot.1080p.WEB-DL.NEWTAG.x264-GRP");
+        assert_eq!(parsed.unparsed_tokens, vec!["NEWTAG"]);
+        assert_eq!(parsed.unparsed_spans, vec![35..41]);
+        assert_eq!(parsed.get("unparsed_tokens"), Some("NEWTAG".to_string()));
+
+        // A value read from inside a word uses the word
+        let parsed = ReleaseParser::new("movie").parse("Movie.2020.DD5.1x264-GRP");
+        assert!(parsed.unparsed_tokens.is_empty());
+        assert_eq!(parsed.get("unparsed_tokens"), None);
+    }
+
+    #[test]
+    fn test_try_parse() {
+        let parser = ReleaseParser::new("movie");
+        assert_eq!(parser.try_parse("  "), Err(ParseError::Empty));
+        assert_eq!(parser.try_parse("Movie.2020.1080p").unwrap().title, "Movie");
+
+        let strict = ReleaseParser::new("movie").strict(true);
+        assert_eq!(strict.try_parse("Movie.2020.1080p"), Err(ParseError::MissingField("group")));
+        assert_eq!(strict.try_parse("2020.1080p.BluRay-GRP"), Err(ParseError::MissingField("title")));
+        assert_eq!(strict.try_parse("Movie.2020.1080p.BluRay-GRP").unwrap().group, "GRP");
+        // parse() is never strict
+        assert_eq!(strict.parse("Movie.2020.1080p").title, "Movie");
+    }
+
+    #[test]
+    fn test_try_parse_path_errors() {
+        let parser = ReleaseParser::new("tv");
+        assert_eq!(parser.try_parse_path(""), Err(ParseError::Empty));
+        assert_eq!(parser.try_parse_path("Show.S01E01.mkv"), Err(ParseError::NoParentDirectory));
+        assert_eq!(parser.try_parse_path("/tv/Show/.."), Err(ParseError::NoFileName));
+        assert!(matches!(parser.try_parse_path("Season 01/Show.S01E01.mkv"), Err(ParseError::UnrecognizedLayout(_))));
+
+        let path_info = parser.try_parse_path("/tv/Show (2020)/Season 01/Show.S01E01.720p.mkv").unwrap();
+        assert_eq!(path_info.season, Some(1));
+        assert_eq!(path_info.directory.unwrap().title, "Show");
+
+        let strict = ReleaseParser::new("tv").strict(true);
+        assert_eq!(strict.try_parse_path("/tv/Show (2020)/Season 01/Show.S01E01.720p.mkv"), Err(ParseError::MissingField("group")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_try_parse_path_non_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"/tv/Show\xff/Season 01/Show.S01E01.mkv"));
+        let error = ReleaseParser::new("tv").try_parse_path(path).unwrap_err();
+        assert_eq!(error, ParseError::NonUtf8Component("Show\u{FFFD}".to_string()));
+        assert_eq!(error.to_string(), "path component \"Show\u{FFFD}\" is not valid UTF-8");
+    }
 }
@@ -0,0 +1,465 @@
+//! Renaming a directory of releases into a library.
+//!
+//! [`RenamePlan::new`] walks a directory, parses every media file with
+//! [`ReleaseParser::try_parse_path`] and works out where each one goes under
+//! a target root. Nothing is touched until the plan, which serializes to
+//! JSON for review, is [applied](RenamePlan::apply). Applying writes a
+//! [`Journal`] of every step, so a bad run can be [undone](Journal::undo).
+//!
+//! Subtitles, `.nfo` files and artwork named after a media file
+//! (`Movie.mkv`, `Movie.en.srt`) move with it. Samples are left out.
+
+use crate::naming::LibraryNaming;
+use crate::parser::ReleaseParser;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Extensions of video files.
+pub(crate) const MEDIA_EXTENSIONS: &[&str] = &["mkv", "mp4", "m4v", "avi", "mov", "wmv", "mpg", "mpeg", "ts", "m2ts", "webm", "flv"];
+
+/// Extensions of files that belong to a video file with the same name.
+pub(crate) const SIDECAR_EXTENSIONS: &[&str] = &["srt", "ass", "ssa", "sub", "idx", "vtt", "sup", "nfo", "jpg", "jpeg", "png", "tbn"];
+
+/// How files get to their destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strategy {
+    Move,
+    Copy,
+    Hardlink,
+    Symlink,
+}
+
+/// A file to put at a new path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Action {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+}
+
+/// Files that can't go where the plan wants them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Conflict {
+    pub destination: PathBuf,
+    pub sources: Vec<PathBuf>,
+    pub reason: String,
+}
+
+/// A file left where it is.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Skipped {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// What renaming a directory would do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenamePlan {
+    pub source_root: PathBuf,
+    pub target_root: PathBuf,
+    /// Files to rename, in destination order
+    pub actions: Vec<Action>,
+    /// Files left out because their destination is taken
+    pub conflicts: Vec<Conflict>,
+    pub skipped: Vec<Skipped>,
+}
+
+/// A step of an applied plan.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub strategy: Strategy,
+    pub source: PathBuf,
+    pub destination: PathBuf,
+}
+
+/// Record of an applied plan, enough to undo it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Journal {
+    /// Steps in the order they were done
+    pub entries: Vec<JournalEntry>,
+    /// Directories created for the destinations, parents first
+    pub created_dirs: Vec<PathBuf>,
+}
+
+impl RenamePlan {
+    /// Plan the renaming of every media file under `source_root` to the
+    /// path `naming` gives it under `target_root`.
+    pub fn new(parser: &ReleaseParser, naming: &impl LibraryNaming, source_root: impl AsRef<Path>, target_root: impl AsRef<Path>) -> io::Result<Self> {
+        let (source_root, target_root) = (source_root.as_ref(), target_root.as_ref());
+        let files = walk_files(source_root)?;
+        let sidecars: Vec<&PathBuf> = files.iter().filter(|path| has_extension(path, SIDECAR_EXTENSIONS)).collect();
+        let media: Vec<&PathBuf> = files.iter().filter(|path| has_extension(path, MEDIA_EXTENSIONS) && !is_sample(path)).collect();
+        let owners = sidecar_owners(&media, &sidecars);
+        let mut attached: HashSet<&PathBuf> = HashSet::new();
+        let mut skipped: Vec<Skipped> = Vec::new();
+        let mut wanted: Vec<Action> = Vec::new();
+
+        for path in &files {
+            if has_extension(path, SIDECAR_EXTENSIONS) {
+                continue;
+            }
+            if !has_extension(path, MEDIA_EXTENSIONS) {
+                skipped.push(Skipped::new(path, "not a media file"));
+                continue;
+            }
+            if is_sample(path) {
+                skipped.push(Skipped::new(path, "sample"));
+                continue;
+            }
+            let mut path_info = match parser.try_parse_path(path) {
+                Ok(path_info) => path_info,
+                Err(error) => {
+                    skipped.push(Skipped::new(path, &error.to_string()));
+                    continue;
+                }
+            };
+            // A download folder named after the release says nothing more about the series
+            if path_info.directory.as_ref().is_some_and(|directory| is_release_name(parser, &directory.release)) {
+                path_info.directory = None;
+            }
+            let named = naming.name_path(&path_info);
+            if named.file.is_empty() || named.folder.is_empty() {
+                skipped.push(Skipped::new(path, "no title"));
+                continue;
+            }
+            let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or("");
+            let destination = target_root.join(named.to_path(extension));
+
+            for sidecar in sidecars.iter().filter(|sidecar| owners.get(*sidecar) == Some(&path)) {
+                attached.insert(sidecar);
+                let suffix = &file_name(sidecar)[file_stem(path).len()..];
+                wanted.push(Action {
+                    source: sidecar.to_path_buf(),
+                    destination: destination.with_file_name(format!("{}{}", named.file, suffix)),
+                });
+            }
+            wanted.push(Action {
+                source: path.clone(),
+                destination,
+            });
+        }
+        for sidecar in sidecars {
+            if !attached.contains(sidecar) {
+                skipped.push(Skipped::new(sidecar, "no media file with the same name"));
+            }
+        }
+
+        let mut by_destination: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+        for action in wanted {
+            if action.source == action.destination {
+                skipped.push(Skipped::new(&action.source, "already in place"));
+            } else {
+                by_destination.entry(action.destination).or_default().push(action.source);
+            }
+        }
+        let mut actions: Vec<Action> = Vec::new();
+        let mut conflicts: Vec<Conflict> = Vec::new();
+        for (destination, sources) in by_destination {
+            let reason = if sources.len() > 1 {
+                "several files have this destination"
+            } else if fs::symlink_metadata(&destination).is_ok() {
+                "destination already exists"
+            } else {
+                let source = sources.into_iter().next().expect("grouped by destination");
+                actions.push(Action { source, destination });
+                continue;
+            };
+            conflicts.push(Conflict {
+                destination,
+                sources,
+                reason: reason.to_string(),
+            });
+        }
+        skipped.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Self {
+            source_root: source_root.to_path_buf(),
+            target_root: target_root.to_path_buf(),
+            actions,
+            conflicts,
+            skipped,
+        })
+    }
+
+    /// Carry out the actions, writing the journal to `journal_path` after
+    /// every step. Stops at the first error, leaving a journal of what was
+    /// done; a destination that appeared since planning is an error.
+    pub fn apply(&self, strategy: Strategy, journal_path: impl AsRef<Path>) -> io::Result<Journal> {
+        let journal_path = journal_path.as_ref();
+        let mut journal = Journal::default();
+        journal.save(journal_path)?;
+        for action in &self.actions {
+            if fs::symlink_metadata(&action.destination).is_ok() {
+                return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", action.destination.display())));
+            }
+            if let Some(parent) = action.destination.parent() {
+                create_dirs(parent, &mut journal.created_dirs)?;
+            }
+            transfer(strategy, &action.source, &action.destination)?;
+            journal.entries.push(JournalEntry {
+                strategy,
+                source: action.source.clone(),
+                destination: action.destination.clone(),
+            });
+            journal.save(journal_path)?;
+        }
+        Ok(journal)
+    }
+}
+
+impl Skipped {
+    pub(crate) fn new(path: &Path, reason: &str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl Journal {
+    /// Read a journal written by [`RenamePlan::apply`].
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        serde_json::from_str(&fs::read_to_string(path)?).map_err(io::Error::other)
+    }
+
+    fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self).map_err(io::Error::other)?)
+    }
+
+    /// Reverse the steps, last first: moved files go back, copies and links
+    /// are removed, then the directories the plan created if they are empty.
+    pub fn undo(&self) -> io::Result<()> {
+        for entry in self.entries.iter().rev() {
+            match entry.strategy {
+                Strategy::Move => {
+                    if let Some(parent) = entry.source.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    transfer(Strategy::Move, &entry.destination, &entry.source)?;
+                }
+                Strategy::Copy | Strategy::Hardlink | Strategy::Symlink => fs::remove_file(&entry.destination)?,
+            }
+        }
+        for dir in self.created_dirs.iter().rev() {
+            // Anything else put there since is kept, with its directory
+            let _ = fs::remove_dir(dir);
+        }
+        Ok(())
+    }
+}
+
+/// Every file under `root`, sorted, without following symlinked directories.
+pub(crate) fn walk_files(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                dirs.push(entry.path());
+            } else if file_type.is_file() || (file_type.is_symlink() && entry.path().is_file()) {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Whether a directory name is a release name rather than a series or movie.
+pub(crate) fn is_release_name(parser: &ReleaseParser, name: &str) -> bool {
+    let parsed = parser.parse(name);
+    parsed.episode.is_some() || !parsed.resolution.is_empty() || !parsed.source.is_empty() || !parsed.format.is_empty()
+}
+
+pub(crate) fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extensions.iter().any(|known| known.eq_ignore_ascii_case(extension)))
+}
+
+/// Whether `sidecar` is named after `media` and next to it: `Movie.srt`,
+/// `Movie.en.forced.srt` or `Movie-poster.jpg` for `Movie.mkv`.
+pub(crate) fn is_sidecar_of(sidecar: &Path, media: &Path) -> bool {
+    let stem = file_stem(media);
+    sidecar.parent() == media.parent()
+        && file_name(sidecar)
+            .strip_prefix(stem)
+            .is_some_and(|rest| rest.starts_with('.') || rest.starts_with('-'))
+}
+
+/// The media file each sidecar belongs to. A sidecar named after several,
+/// like `Movie.Part.2.en.srt` next to `Movie.mkv` and `Movie.Part.2.mkv`,
+/// goes with the longest name.
+fn sidecar_owners<'a>(media: &[&'a PathBuf], sidecars: &[&'a PathBuf]) -> HashMap<&'a PathBuf, &'a PathBuf> {
+    sidecars
+        .iter()
+        .filter_map(|sidecar| {
+            let owner = media.iter().filter(|media| is_sidecar_of(sidecar, media)).max_by_key(|media| file_stem(media).len())?;
+            Some((*sidecar, *owner))
+        })
+        .collect()
+}
+
+/// Whether a video is the sample of a release: `Movie-sample.mkv`,
+/// `sample.mkv` or anything in a `Sample` folder.
+pub(crate) fn is_sample(path: &Path) -> bool {
+    let stem = file_stem(path).to_lowercase();
+    let in_sample_folder = path.parent().is_some_and(|folder| file_name(folder).eq_ignore_ascii_case("sample"));
+    in_sample_folder || stem == "sample" || stem.ends_with("-sample") || stem.ends_with(".sample")
+}
+
+pub(crate) fn file_name(path: &Path) -> &str {
+    path.file_name().and_then(|name| name.to_str()).unwrap_or("")
+}
+
+pub(crate) fn file_stem(path: &Path) -> &str {
+    path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("")
+}
+
+/// Create `dir` and its missing parents, recording the ones created.
+fn create_dirs(dir: &Path, created: &mut Vec<PathBuf>) -> io::Result<()> {
+    let missing: Vec<&Path> = dir.ancestors().take_while(|ancestor| !ancestor.as_os_str().is_empty() && !ancestor.exists()).collect();
+    for ancestor in missing.into_iter().rev() {
+        fs::create_dir(ancestor)?;
+        created.push(ancestor.to_path_buf());
+    }
+    Ok(())
+}
+
+fn transfer(strategy: Strategy, source: &Path, destination: &Path) -> io::Result<()> {
+    match strategy {
+        // `rename` can't cross file systems, copy and delete instead
+        Strategy::Move => fs::rename(source, destination).or_else(|_| {
+            fs::copy(source, destination)?;
+            fs::remove_file(source)
+        }),
+        Strategy::Copy => fs::copy(source, destination).map(|_| ()),
+        Strategy::Hardlink => fs::hard_link(source, destination),
+        Strategy::Symlink => {
+            let source = fs::canonicalize(source)?;
+            #[cfg(unix)]
+            return std::os::unix::fs::symlink(source, destination);
+            #[cfg(windows)]
+            return std::os::windows::fs::symlink_file(source, destination);
+            #[cfg(not(any(unix, windows)))]
+            return Err(io::Error::new(io::ErrorKind::Unsupported, format!("can't link {} here", source.display())));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MediaServer, ServerLayout};
+
+    /// An empty directory for one test.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("scene_release_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, path.to_string_lossy().as_bytes()).unwrap();
+    }
+
+    fn downloads(dir: &Path) -> PathBuf {
+        let source = dir.join("downloads");
+        touch(&source.join("Show.S01E01.720p.HDTV.x264-GRP/Show.S01E01.720p.HDTV.x264-GRP.mkv"));
+        touch(&source.join("Show.S01E01.720p.HDTV.x264-GRP/Show.S01E01.720p.HDTV.x264-GRP.en.srt"));
+        touch(&source.join("Show.S01E01.720p.HDTV.x264-GRP/info.txt"));
+        touch(&source.join("Show.S01E02.1080p.WEB-DL.x264-AAA/Show.S01E02.1080p.WEB-DL.x264-AAA.mkv"));
+        touch(&source.join("Show.S01E02.720p.HDTV.x264-BBB/Show.S01E02.720p.HDTV.x264-BBB.mkv"));
+        source
+    }
+
+    #[test]
+    fn test_plan() {
+        let dir = scratch_dir("plan");
+        let source = downloads(&dir);
+        let target = dir.join("library");
+        let plan = RenamePlan::new(&ReleaseParser::new("tv"), &ServerLayout::new(MediaServer::Plex), &source, &target).unwrap();
+
+        let destinations: Vec<PathBuf> = plan.actions.iter().map(|action| action.destination.strip_prefix(&target).unwrap().to_path_buf()).collect();
+        assert_eq!(destinations, vec![PathBuf::from("Show/Season 01/Show - S01E01.en.srt"), PathBuf::from("Show/Season 01/Show - S01E01.mkv")]);
+        assert_eq!(plan.conflicts.len(), 1);
+        assert_eq!(plan.conflicts[0].sources.len(), 2);
+        assert_eq!(plan.skipped.len(), 1);
+        assert_eq!(plan.skipped[0].reason, "not a media file");
+
+        let json = serde_json::to_string(&plan).unwrap();
+        assert_eq!(serde_json::from_str::<RenamePlan>(&json).unwrap(), plan);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_sidecars_and_samples() {
+        let dir = scratch_dir("sidecars");
+        let source = dir.join("downloads");
+        let release = source.join("Movie.2010.1080p.BluRay.x264-GRP");
+        touch(&release.join("Movie.2010.mkv"));
+        touch(&release.join("Movie.2010.Part.2.mkv"));
+        touch(&release.join("Movie.2010.Part.2.en.srt"));
+        touch(&release.join("Movie.2010-sample.mkv"));
+        let target = dir.join("library");
+        let plan = RenamePlan::new(&ReleaseParser::new("movie"), &ServerLayout::new(MediaServer::Plex), &source, &target).unwrap();
+
+        let subtitles: Vec<&Action> = plan.actions.iter().filter(|action| has_extension(&action.source, &["srt"])).collect();
+        assert_eq!(subtitles.len(), 1);
+        assert_eq!(subtitles[0].destination, target.join("Movie (2010)/Movie (2010) - pt2.en.srt"));
+        assert_eq!(plan.actions.len(), 3);
+        assert!(plan.conflicts.is_empty(), "{:?}", plan.conflicts);
+        assert_eq!(plan.skipped, vec![Skipped::new(&release.join("Movie.2010-sample.mkv"), "sample")]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_apply_and_undo() {
+        let dir = scratch_dir("apply");
+        let source = downloads(&dir);
+        let target = dir.join("library");
+        let plan = RenamePlan::new(&ReleaseParser::new("tv"), &ServerLayout::new(MediaServer::Plex), &source, &target).unwrap();
+        let journal_path = dir.join("journal.json");
+
+        let mut strategies = vec![Strategy::Move, Strategy::Copy, Strategy::Hardlink];
+        if cfg!(unix) {
+            strategies.push(Strategy::Symlink);
+        }
+        for strategy in strategies {
+            let journal = plan.apply(strategy, &journal_path).unwrap();
+            assert_eq!(Journal::load(&journal_path).unwrap(), journal);
+            for action in &plan.actions {
+                assert!(action.destination.exists());
+                assert_eq!(action.source.exists(), strategy != Strategy::Move);
+            }
+
+            journal.undo().unwrap();
+            for action in &plan.actions {
+                assert!(action.source.exists());
+                assert!(!action.destination.exists());
+            }
+            assert!(!target.exists());
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_existing_destination() {
+        let dir = scratch_dir("existing");
+        let source = downloads(&dir);
+        let target = dir.join("library");
+        touch(&target.join("Show/Season 01/Show - S01E01.mkv"));
+        let plan = RenamePlan::new(&ReleaseParser::new("tv"), &ServerLayout::new(MediaServer::Plex), &source, &target).unwrap();
+
+        assert!(plan.conflicts.iter().any(|conflict| conflict.reason == "destination already exists"));
+        assert_eq!(plan.actions.len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
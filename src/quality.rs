@@ -0,0 +1,342 @@
+//! Comparable quality of a release.
+//!
+//! A [`Quality`] combines the source and resolution of a release into one
+//! tier, like `WEBDL-1080p` or `Remux-2160p`, the way Sonarr and Radarr do.
+//! A [`Revision`] tells apart releases of the same thing in the same
+//! quality: `v2`, PROPER, REPACK and REAL. Both are totally ordered, and
+//! [`QualityModel`] orders by quality, then revision, to answer whether a
+//! release is an upgrade over another.
+
+use crate::media::Source;
+use crate::rules::regex;
+use crate::types::ParsedRelease;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Where a release comes from, worst to best within a resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualitySource {
+    Unknown,
+    // Pre-release tiers, below any retail release whatever their resolution
+    Workprint,
+    Cam,
+    Telesync,
+    Telecine,
+    /// R5 and other regional retail releases
+    Regional,
+    Screener,
+    // Retail tiers
+    /// SDTV and HDTV captures
+    Television,
+    /// Raw HDTV transport stream
+    RawHd,
+    Dvd,
+    WebRip,
+    WebDl,
+    Bluray,
+    Remux,
+    /// Untouched Blu-ray disc
+    BrDisk,
+}
+
+/// Source and resolution of a release.
+///
+/// Pre-release sources (CAM, TELESYNC, TELECINE, R5, SCR) rank below all
+/// others, in that order, and only a release with neither source nor
+/// resolution ranks below them. Other qualities rank by resolution first,
+/// then source: `HDTV-1080p` > `Bluray-720p` > `WEBDL-720p` > `WEBRip-720p`.
+/// Remux, BR-DISK and Raw-HD without a resolution rank as 1080p.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Quality {
+    pub source: QualitySource,
+    /// Vertical resolution, 0 when unknown
+    pub resolution: u32,
+}
+
+/// Version of a release among the releases of the same thing.
+///
+/// `REAL` fixes a bad PROPER by another group and beats any version
+/// without it; otherwise the higher version wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Revision {
+    /// 1 for a first release, one more per PROPER, REPACK or RERIP, or the
+    /// `v2`-style version of anime releases
+    pub version: u32,
+    /// Number of times REAL is given before PROPER, REPACK or RERIP
+    pub real: u32,
+}
+
+/// Quality and revision, ordered by quality first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct QualityModel {
+    pub quality: Quality,
+    pub revision: Revision,
+}
+
+impl Quality {
+    /// Quality of a parsed release.
+    pub fn of(parsed: &ParsedRelease) -> Self {
+        let resolution = parsed.resolution().and_then(|resolution| resolution.height()).unwrap_or(0);
+        let release = parsed.release.as_str();
+        let source = if regex!(r"(?i)\b(?:BDMV|BD25|BD50|BD66|BD100|COMPLETE[. _-]BLURAY|BR-?DISK)\b").is_match(release) {
+            QualitySource::BrDisk
+        } else if regex!(r"(?i)\bRaw[. _-]?HD\b").is_match(release) {
+            QualitySource::RawHd
+        } else if regex!(r"(?i)\b(?:BD)?REMUX\b").is_match(release) {
+            QualitySource::Remux
+        } else {
+            match parsed.source() {
+                None => QualitySource::Unknown,
+                Some(source) => match source {
+                    Source::Workprint => QualitySource::Workprint,
+                    Source::Cam => QualitySource::Cam,
+                    Source::Telesync => QualitySource::Telesync,
+                    Source::Telecine => QualitySource::Telecine,
+                    Source::R5 => QualitySource::Regional,
+                    Source::Screener => QualitySource::Screener,
+                    Source::Hdtv | Source::Pdtv | Source::Dsr | Source::TvRip | Source::Ppv | Source::Vhs => QualitySource::Television,
+                    Source::Dvd | Source::DvdRip => QualitySource::Dvd,
+                    Source::WebRip | Source::Vod | Source::HdRip => QualitySource::WebRip,
+                    Source::WebDl | Source::Web => QualitySource::WebDl,
+                    Source::BluRay | Source::BdRip => QualitySource::Bluray,
+                    Source::Remux => QualitySource::Remux,
+                    Source::Other(_) => QualitySource::Unknown,
+                },
+            }
+        };
+        Self { source, resolution }
+    }
+
+    /// Whether the source is a pre-release one (CAM to SCR).
+    pub fn is_pre_release(&self) -> bool {
+        (QualitySource::Workprint..=QualitySource::Screener).contains(&self.source)
+    }
+
+    /// Sort key: unknown, then pre-release by source, then by resolution and source.
+    fn key(&self) -> (u8, u32, u32) {
+        // Blu-ray discs and HDTV streams are HD when the name doesn't say
+        let resolution = match self.source {
+            QualitySource::Remux | QualitySource::BrDisk | QualitySource::RawHd if self.resolution == 0 => 1080,
+            _ => self.resolution,
+        };
+        match self.source {
+            QualitySource::Unknown if resolution == 0 => (0, 0, 0),
+            source if self.is_pre_release() => (1, source as u32, resolution),
+            source => (2, resolution, source as u32),
+        }
+    }
+}
+
+impl Ord for Quality {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl PartialOrd for Quality {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Quality {
+    /// Names as Sonarr and Radarr write them: `WEBDL-1080p`, `SDTV`, `BR-DISK`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.source {
+            QualitySource::Unknown => "Unknown",
+            QualitySource::Workprint => "WORKPRINT",
+            QualitySource::Cam => "CAM",
+            QualitySource::Telesync => "TELESYNC",
+            QualitySource::Telecine => "TELECINE",
+            QualitySource::Regional => "REGIONAL",
+            QualitySource::Screener => "DVDSCR",
+            QualitySource::Television if self.resolution < 720 => "SDTV",
+            QualitySource::Television => "HDTV",
+            QualitySource::Dvd => "DVD",
+            QualitySource::WebRip => "WEBRip",
+            QualitySource::WebDl => "WEBDL",
+            QualitySource::Bluray => "Bluray",
+            QualitySource::Remux => "Remux",
+            QualitySource::BrDisk => "BR-DISK",
+            QualitySource::RawHd => "Raw-HD",
+        };
+        let with_resolution = !self.is_pre_release()
+            && !matches!(self.source, QualitySource::Unknown | QualitySource::Dvd | QualitySource::BrDisk | QualitySource::RawHd)
+            && name != "SDTV";
+        if with_resolution && self.resolution > 0 {
+            write!(f, "{}-{}p", name, self.resolution)
+        } else {
+            f.write_str(name)
+        }
+    }
+}
+
+impl Revision {
+    /// Revision of a parsed release, read from its name.
+    pub fn of(parsed: &ParsedRelease) -> Self {
+        let release = parsed.release.as_str();
+        // REPACK2 is the second repack
+        let fixes: u32 = regex!(r"(?i)\b(?:PROPER|REPACK|RERIP)(\d)?\b")
+            .captures_iter(release)
+            .map(|captures| captures.get(1).and_then(|count| count.as_str().parse().ok()).unwrap_or(1))
+            .sum();
+        let explicit = regex!(r"(?i)(?:\bS\d{1,2}E\d{1,4}|\bE\d{1,4}|\b\d{1,4})v(\d)\b")
+            .captures(release)
+            .and_then(|captures| captures[1].parse().ok())
+            .unwrap_or(1);
+        // Capitals only and next to the fix, as for the REAL flag
+        let real = regex!(r"\bREAL[. _-](?:PROPER|REPACK|RERIP)").find_iter(release).count() as u32;
+        Self {
+            version: explicit.max(1 + fixes),
+            real,
+        }
+    }
+}
+
+impl Default for Revision {
+    fn default() -> Self {
+        Self { version: 1, real: 0 }
+    }
+}
+
+impl Ord for Revision {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.real, self.version).cmp(&(other.real, other.version))
+    }
+}
+
+impl PartialOrd for Revision {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Revision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for _ in 0..self.real {
+            f.write_str("REAL ")?;
+        }
+        write!(f, "v{}", self.version)
+    }
+}
+
+impl QualityModel {
+    pub fn of(parsed: &ParsedRelease) -> Self {
+        Self {
+            quality: Quality::of(parsed),
+            revision: Revision::of(parsed),
+        }
+    }
+
+    /// Whether a release of this quality and revision should replace one of `other`.
+    pub fn is_upgrade_over(&self, other: &Self) -> bool {
+        self > other
+    }
+}
+
+impl fmt::Display for QualityModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.quality, self.revision)
+    }
+}
+
+impl ParsedRelease {
+    /// [`Quality`] of the release.
+    pub fn quality(&self) -> Quality {
+        Quality::of(self)
+    }
+
+    /// [`Revision`] of the release.
+    pub fn revision(&self) -> Revision {
+        Revision::of(self)
+    }
+
+    /// Whether this release is better than `current`: a higher quality, or
+    /// the same quality in a later revision.
+    pub fn is_upgrade_over(&self, current: &ParsedRelease) -> bool {
+        QualityModel::of(self).is_upgrade_over(&QualityModel::of(current))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReleaseParser;
+
+    fn quality(release: &str) -> Quality {
+        ReleaseParser::new("movie").parse(release).quality()
+    }
+
+    #[test]
+    fn test_quality_names() {
+        assert_eq!(quality("Movie.2020.1080p.WEB-DL.DDP5.1.H.264-GRP").to_string(), "WEBDL-1080p");
+        assert_eq!(quality("Movie.2020.720p.WEBRip.x264-GRP").to_string(), "WEBRip-720p");
+        assert_eq!(quality("Movie.2020.1080p.BluRay.REMUX.AVC-GRP").to_string(), "Remux-1080p");
+        assert_eq!(quality("Movie.2020.COMPLETE.BLURAY-GRP").to_string(), "BR-DISK");
+        assert_eq!(quality("Movie.2020.HDTV.x264-GRP").to_string(), "SDTV");
+        assert_eq!(quality("Movie.2020.HDCAM.x264-GRP").to_string(), "CAM");
+        assert_eq!(quality("Movie.2020.1080p.Raw-HD.x264-GRP").to_string(), "Raw-HD");
+        assert_eq!(quality("Movie.2020.x264-GRP").to_string(), "Unknown");
+    }
+
+    #[test]
+    fn test_quality_order() {
+        let ordered = [
+            "Movie.2020.x264-GRP",
+            "Movie.2020.1080p.HDCAM.x264-GRP",
+            "Movie.2020.TS.x264-GRP",
+            "Movie.2020.SCR.x264-GRP",
+            "Movie.2020.HDTV.x264-GRP",
+            "Movie.2020.DVDRip.x264-GRP",
+            "Movie.2020.720p.HDTV.x264-GRP",
+            "Movie.2020.720p.WEBRip.x264-GRP",
+            "Movie.2020.720p.WEB-DL.x264-GRP",
+            "Movie.2020.720p.BluRay.x264-GRP",
+            "Movie.2020.1080p.HDTV.x264-GRP",
+            "Movie.2020.1080p.BluRay.REMUX.AVC-GRP",
+            "Movie.2020.2160p.WEB-DL.x265-GRP",
+        ];
+        for pair in ordered.windows(2) {
+            assert!(quality(pair[0]) < quality(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+
+        let pairs = [
+            ("Movie.2020.480p.WEBRip.x264-GRP", "Movie.2020.COMPLETE.BLURAY-GRP"),
+            ("Movie.2020.720p.BluRay.x264-GRP", "Movie.2020.BluRay.REMUX.AVC-GRP"),
+            ("Movie.2020.Raw-HD.x264-GRP", "Movie.2020.1080p.BluRay.REMUX.AVC-GRP"),
+            ("Movie.2020.1080p.Raw-HD.x264-GRP", "Movie.2020.1080p.WEBRip.x264-GRP"),
+            ("Movie.2020.1080p.HDTV.x264-GRP", "Movie.2020.1080p.Raw-HD.x264-GRP"),
+            ("Movie.2020.HDCAM.x264-GRP", "Movie.2020.1080p.x264-GRP"),
+            ("Movie.2020.1080p.x264-GRP", "Movie.2020.1080p.HDTV.x264-GRP"),
+        ];
+        for (worse, better) in pairs {
+            assert!(quality(worse) < quality(better), "{} < {}", worse, better);
+        }
+    }
+
+    #[test]
+    fn test_revision() {
+        let parser = ReleaseParser::new("tv");
+        let revision = |release: &str| parser.parse(release).revision();
+        assert_eq!(revision("Show.S01E01.720p.HDTV.x264-GRP"), Revision::default());
+        assert_eq!(revision("Show.S01E01.PROPER.720p.HDTV.x264-GRP"), Revision { version: 2, real: 0 });
+        assert_eq!(revision("Show.S01E01.REPACK2.720p.HDTV.x264-GRP").version, 3);
+        assert_eq!(revision("[Grp] Show - 01v2 [1080p]").version, 2);
+        assert_eq!(revision("Show.S01E01.REAL.PROPER.720p.HDTV.x264-GRP"), Revision { version: 2, real: 1 });
+        assert!(revision("Show.S01E01.REAL.REPACK.720p.HDTV.x264-GRP") > revision("Show.S01E01.REPACK2.720p.HDTV.x264-GRP"));
+        assert_eq!(revision("Show.S01E01v3.720p.HDTV.x264-GRP").version, 3);
+        assert_eq!(revision("The.Real.Show.S01E01.720p.HDTV.x264-GRP").real, 0);
+    }
+
+    #[test]
+    fn test_is_upgrade() {
+        let parser = ReleaseParser::new("tv");
+        let current = parser.parse("Show.S01E01.720p.WEB-DL.x264-GRP");
+        assert!(parser.parse("Show.S01E01.PROPER.720p.WEB-DL.x264-GRP").is_upgrade_over(&current));
+        assert!(parser.parse("Show.S01E01.1080p.HDTV.x264-GRP").is_upgrade_over(&current));
+        assert!(!parser.parse("Show.S01E01.REPACK.720p.WEBRip.x264-GRP").is_upgrade_over(&current));
+        assert!(!parser.parse("Show.S01E01.720p.WEB-DL.x264-OTHER").is_upgrade_over(&current));
+    }
+}
@@ -0,0 +1,53 @@
+//! Parse results with the evidence behind every field.
+//!
+//! [`ReleaseParser::parse_report`](crate::ReleaseParser::parse_report) records,
+//! for each field it fills, the rule that matched, how much that rule is
+//! trusted and where in the release name the value was read. Values that
+//! matched but lost to another interpretation are kept as alternatives.
+
+use crate::types::ParsedRelease;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// One value found for a field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldReport {
+    /// Field name as used by [`ParsedRelease::get`]
+    pub field: String,
+    pub value: String,
+    /// Name of the rule that matched, e.g. `"year.parentheses"`
+    pub rule: String,
+    /// From 0.0 (a guess) to 1.0 (unambiguous)
+    pub confidence: f32,
+    /// Byte span in the release name, `None` for values not read from it (the release type)
+    pub span: Option<Range<usize>>,
+}
+
+/// A [`ParsedRelease`] with the evidence for each of its fields.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParseReport {
+    pub parsed: ParsedRelease,
+    /// Values that were used, in the order they were found
+    pub fields: Vec<FieldReport>,
+    /// Values that matched but were not used, because another value won or
+    /// their tokens already belonged to another field
+    pub alternatives: Vec<FieldReport>,
+}
+
+impl ParseReport {
+    /// Report of the value used for `field`, the first one for list fields
+    /// like `flags` and `language`.
+    pub fn field(&self, field: &str) -> Option<&FieldReport> {
+        self.fields.iter().find(|report| report.field == field)
+    }
+
+    /// Confidence of the value used for `field`, `None` if the field is empty.
+    pub fn confidence(&self, field: &str) -> Option<f32> {
+        self.field(field).map(|report| report.confidence)
+    }
+
+    /// Values `field` could also have had.
+    pub fn alternatives<'a>(&'a self, field: &'a str) -> impl Iterator<Item = &'a FieldReport> {
+        self.alternatives.iter().filter(move |report| report.field == field)
+    }
+}
@@ -0,0 +1,220 @@
+//! Compiled rule tables shared by every [`ReleaseParser`](crate::ReleaseParser).
+//!
+//! Every regex used while parsing is compiled exactly once, on first use, and
+//! then reused across parser instances and threads. Large keyword tables are
+//! matched in a single pass with a [`RegexSet`] instead of one regex per entry.
+
+use regex::{Regex, RegexSet};
+use std::sync::LazyLock;
+
+/// Compile a regex literal once and hand out a `&'static Regex`.
+macro_rules! regex {
+    ($re:literal $(,)?) => {{
+        static RE: std::sync::LazyLock<regex::Regex> =
+            std::sync::LazyLock::new(|| regex::Regex::new($re).unwrap());
+        &*RE
+    }};
+}
+pub(crate) use regex;
+
+/// Name and confidence (0.0 to 1.0) of the rule that produced a value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Rule(pub(crate) &'static str, pub(crate) f32);
+
+// Based on: https://en.wikipedia.org/wiki/Pirated_movie_release_types
+pub(crate) const SOURCES: &[&str] = &[
+    // DVD sources
+    "DVDRip", "DVD-Rip", "DVDR", "DVD5", "DVD9", "DVD-R", "DvDrip",
+    // Web sources
+    "WEB-DL", "WEBRip", "Web Rip", "Web Download", "WEB", "WEBDL", "AMZN WEBDL", "MA WEBDL",
+    // TV sources
+    // iNTERNAL is a release flag, not a source, see FLAG_PATTERNS
+    "HDTV", "PDTV", "DSR", "SATRip", "TVRip",
+    // Blu-ray sources
+    "BluRay", "BDRip", "BRRip", "BD",
+    // Other sources
+    "VHSRip", "R5", "TC", "TS", "CAM", "SCR",
+    "HDCAM", "TELESYNC", "TELECINE", "Remux",
+    // Additional sources from Wikipedia
+    "Workprint", "WP", "PPV Rip", "PPVRip", "DDC",
+    "VOD Rip", "VODRip", "HC HD Rip", "HCHDRip",
+    "Web Capture", "HDRip", "DCP", "Theatre", "Theater",
+];
+
+/// Sources that are also common words, so they don't end a title ("Theater.Camp").
+pub(crate) const TITLE_WORD_SOURCES: &[&str] = &["Workprint", "Theatre", "Theater"];
+
+/// Complete list of streaming providers.
+// Based on: https://en.wikipedia.org/wiki/Pirated_movie_release_types
+pub(crate) const STREAMING_PROVIDERS: &[&str] = &[
+    // International streaming services
+    "9NOW", "A3P", "AE", "ABC", "AJAZ", "ALL4", "AMC", "AMZN", "Amazon", "Prime Video", "Prime",
+    "ANLB", "ANPL", "APPS", "ARD", "AS", "ATVP", "Apple TV+", "AppleTV", "Apple",
+    "AUBC", "BCORE", "BK", "BNGE", "BOOM", "BRAV", "CBC", "CBS", "CC", "CHGD", "CLBI",
+    "CMAX", "Cinemax", "CMOR", "CMT", "CN", "CNBC", "CNLP", "COOK", "CR", "Crunchyroll",
+    "CRAV", "CRIT", "CRKL", "CRKI", "CSPN", "CTV", "CUR", "CW", "CWS", "DCU", "DDY",
+    "DEST", "DF", "DISC", "Discovery", "Discovery+", "Discovery Plus", "DIY", "DPLY",
+    "DRPO", "DRTV", "DSCP", "DSNP", "Disney+", "DisneyPlus", "Disney", "DTV",
+    "DW", "DLWP", "EPIX", "ESPN", "ESPN+", "ESPN Plus", "ESQ", "ETTV", "ETV",
+    "FAH", "FAM", "FBWatch", "FJR", "FOOD", "FOX", "FPT", "FREE", "FTV", "FUNI", "Funimation",
+    "FXTL", "FYI", "GC", "GLBL", "GLOB", "GLBO", "GO90", "GPLAY", "Google Play", "PLAY",
+    "HBO", "HBO Max", "HMAX", "MAX", "Max", "HGTV", "HIDI", "HIDIVE", "HIST", "HLMK",
+    "HPLAY", "HTSR", "HS", "HULU", "Hulu", "iP", "BBC iPlayer", "BBC", "iQIYI",
+    "iT", "iTunes", "ITV", "ITVX", "JC", "KAYO", "KNOW", "KNPY", "KS", "LGP", "LIFE",
+    "LN", "MA", "Movies Anywhere", "MBC", "MMAX", "MNBC", "MS", "Microsoft Store",
+    "MTOD", "MTV", "MUBI", "MY5", "NATG", "NBA", "NBC", "NBLA", "NF", "Netflix",
+    "NFL", "NFLN", "NICK", "NOW", "NRK", "ODK", "OPTO", "OSN", "OXGN", "PBS", "PBSK",
+    "PCOK", "Peacock", "PLUZ", "PMNT", "PMTP", "Paramount+", "Paramount Plus", "Paramount",
+    "POGO", "PSN", "PlayStation Network", "PUHU", "QIBI", "RED", "YouTube Premium", "YouTube Red",
+    "RKTN", "ROKU", "RSTR", "RTE", "RTP", "RTPPLAY", "SAINA", "SP", "SBS", "SESO",
+    "SHDR", "SHMI", "SHO", "Showtime", "Showtime Anytime", "SKST", "SkyShowtime",
+    "SLNG", "SNET", "SNXT", "SPIK", "SPRT", "SS", "STAN", "STRP", "STZ", "STARZ", "Starz",
+    "SVT", "SYFY", "TEN", "TIMV", "TK", "TLC", "TOU", "TRVL", "TUBI", "TV2", "TV3", "TV4",
+    "TVING", "TVL", "TVNZ", "UFC", "UKTV", "UNIV", "USAN", "VH1", "VIAP", "Viaplay",
+    "VICE", "VIKI", "VIU", "VLCT", "VMEO", "Vimeo", "VRV", "VTRN", "WAVVE", "WNET", "WTCH", "WWEN",
+    "WWE Network", "XBOX", "Xbox Video", "YT", "YouTube", "YouTube Movies", "YouTube TV",
+    "ZDF",
+    // Japanese streaming services
+    "ABMA", "ADN", "ANIMAX", "AO", "AT-X", "ATX", "Baha", "B-Global", "Bstation", "BSP",
+    "NHK-BSP", "BS4", "BS5", "EX-BS", "BS-EX", "BS6", "BS7", "BSJ", "BS-TX", "BS8",
+    "BS-Fuji", "BS11", "BS12", "CS-Fuji ONE", "CX", "DMM", "EX", "CS3", "EX-CS1",
+    "CS-EX1", "CSA", "FOD", "FUNi", "KBC", "M-ON!", "MX", "NHKG", "NHKE", "NTV", "TBS",
+    "TX", "UNXT", "U-NEXT", "WAKA", "Wakanim", "WOWOW", "Wowow", "YTV",
+];
+
+pub(crate) const AUDIO_FORMAT_NAMES: &[&str] = &["TrueHD", "DTS-HD MA", "DTS-HD", "EAC3 Atmos", "EAC3", "AC3", "AAC", "MP3", "FLAC", "DDP", "Dolby Digital Plus"];
+
+/// Language codes matched in brackets: `[DE]`, `[JA]`, `[Eng.Hard.Sub]`.
+pub(crate) const LANGUAGE_CODES: &[(&str, &str)] = &[
+    ("DE", "German"), ("EN", "English"), ("Eng", "English"), ("FR", "French"),
+    ("ES", "Spanish"), ("IT", "Italian"), ("PT", "Portuguese"),
+    ("RU", "Russian"), ("NL", "Dutch"), ("PL", "Polish"),
+    ("SV", "Swedish"), ("NO", "Norwegian"), ("DA", "Danish"),
+    ("FI", "Finnish"), ("JA", "Japanese"), ("ZH", "Chinese"),
+    ("KO", "Korean"), ("AR", "Arabic"), ("TR", "Turkish"),
+];
+
+// Based on: https://en.wikipedia.org/wiki/Pirated_movie_release_types
+pub(crate) const FLAG_PATTERNS: &[(&str, &str)] = &[
+    // Release quality flags
+    ("READNFO", r"(?i)READ\.?NFO"),
+    ("READNFO", r"(?i)READNFO"),
+    ("PROPER", r"(?i)PROPER"),
+    ("REPACK", r"(?i)REPACK"),
+    ("RERIP", r"(?i)RERIP"),
+    // Only in capitals and before the fix it marks, "Real" is a common title word
+    ("REAL", r"\bREAL[. _-](?:PROPER|REPACK|RERIP)"),
+    ("INTERNAL", r"(?i)\bINTERNAL\b"),
+    ("iNTERNAL", r"(?i)\biNTERNAL\b"),
+    // Audio/Subtitle flags
+    ("TV Dubbed", r"(?i)TV\.?Dubbed"),
+    ("Dubbed", r"(?i)\bDubbed\b"),
+    ("Subbed", r"(?i)\bSubbed\b"),
+    ("Hard Sub", r"(?i)(?:Hard\.?Sub|HardSub)"),
+    ("MultiSub", r"(?i)MultiSub"),
+    ("Multi-Subs", r"(?i)Multi-Subs"),
+    // Edition flags
+    ("Uncut", r"(?i)\bUncut\b"),
+    ("Director's Cut", r"(?i)Director'?s\.?Cut"),
+    ("Extended", r"(?i)\bExtended\b"),
+    ("Limited", r"(?i)\bLimited\b"),
+    ("Limited Edition", r"(?i)Limited\.?Edition"),
+    ("Special Edition", r"(?i)Special\.?Edition"),
+    ("Collector's Edition", r"(?i)Collector'?s\.?Edition"),
+    ("Ultimate Edition", r"(?i)Ultimate\.?Edition"),
+    // Video quality flags
+    ("IMAX", r"(?i)\bIMAX\b"),
+    ("IMAX HYBRID", r"(?i)IMAX\s+HYBRID"),
+    ("3D", r"(?i)\[3D\]|(?i)\b3D\b"),
+    ("10bit", r"(?i)\[10bit\]|(?i)\b10bit\b"),
+    ("REMASTERED", r"(?i)REMASTERED"),
+    // Anime flags
+    ("ANiME", r"(?i)ANiME"),
+    // Additional common tags
+    ("NUKED", r"(?i)NUKED"),
+    ("DUPE", r"(?i)DUPE"),
+    ("RETAIL", r"(?i)RETAIL"),
+    ("RERIP", r"(?i)RERIP"),
+    ("NFOFIX", r"(?i)NFOFIX"),
+    ("COMPLETE", r"(?i)COMPLETE"),
+    ("FESTIVAL", r"(?i)FESTIVAL"),
+    ("STV", r"(?i)\bSTV\b"),
+    ("SUBBED", r"(?i)\bSUBBED\b"),
+    ("DUBBED", r"(?i)\bDUBBED\b"),
+];
+
+/// Flags naming an edition of a movie, as the bracket style writes `{edition-…}`.
+pub(crate) const EDITION_FLAGS: &[&str] = &[
+    "Uncut", "Director's Cut", "Extended", "Limited Edition", "Special Edition", "Collector's Edition", "Ultimate Edition", "IMAX", "REMASTERED",
+];
+
+/// A keyword table matched in one pass.
+///
+/// The [`RegexSet`] tells which entries match; the per-entry regex is only run
+/// for those entries to locate the match.
+pub(crate) struct RuleTable {
+    set: RegexSet,
+    patterns: Vec<Regex>,
+}
+
+impl RuleTable {
+    fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let patterns: Vec<String> = patterns.into_iter().map(|pattern| pattern.as_ref().to_string()).collect();
+        Self {
+            set: RegexSet::new(&patterns).unwrap(),
+            patterns: patterns.iter().map(|pattern| Regex::new(pattern).unwrap()).collect(),
+        }
+    }
+
+    /// Every matching entry as `(index, match)`, in table order.
+    pub(crate) fn matches<'a, 't>(&'a self, text: &'t str) -> impl Iterator<Item = (usize, regex::Match<'t>)> + use<'a, 't> {
+        self.set
+            .matches(text)
+            .into_iter()
+            .filter_map(move |index| self.patterns[index].find(text).map(|found| (index, found)))
+    }
+}
+
+pub(crate) static SOURCE_TABLE: LazyLock<RuleTable> =
+    LazyLock::new(|| RuleTable::new(SOURCES.iter().map(|source| source_pattern(source))));
+
+/// Sources of up to three letters (`TS`, `TC`, `BD`) only match whole words, so
+/// the `TS` of `DTS` is not a telesync.
+fn source_pattern(source: &str) -> String {
+    if source.len() <= 3 {
+        format!(r"(?i)\b{}\b", regex::escape(source))
+    } else {
+        format!(r"(?i){}", regex::escape(source))
+    }
+}
+
+/// Bare provider words are matched case-sensitively so title words like "Life" or "Now" are not taken for `LIFE`/`NOW`.
+pub(crate) static STREAMING_PROVIDER_TABLE: LazyLock<RuleTable> = LazyLock::new(|| {
+    RuleTable::new(STREAMING_PROVIDERS.iter().map(|provider| format!(r"\b{}\b", regex::escape(provider))))
+});
+
+/// `FORMAT CHANNELS` matchers (e.g. `TrueHD 5.1`), one per [`AUDIO_FORMAT_NAMES`] entry.
+pub(crate) static AUDIO_CHANNEL_RES: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    AUDIO_FORMAT_NAMES
+        .iter()
+        .map(|name| Regex::new(&format!(r"({})\s+(\d+\.\d+)", regex::escape(name))).unwrap())
+        .collect()
+});
+
+pub(crate) static LANGUAGE_CODE_TABLE: LazyLock<RuleTable> = LazyLock::new(|| {
+    RuleTable::new(LANGUAGE_CODES.iter().map(|(code, _)| {
+        // Special handling for [Eng.Hard.Sub] or [Eng]
+        if *code == "Eng" {
+            r"\[Eng(?:\.Hard\.Sub)?\]".to_string()
+        } else {
+            format!(r"\[{}\]", regex::escape(code))
+        }
+    }))
+});
+
+pub(crate) static FLAG_TABLE: LazyLock<RuleTable> =
+    LazyLock::new(|| RuleTable::new(FLAG_PATTERNS.iter().map(|(_, pattern)| pattern)));
@@ -0,0 +1,361 @@
+//! Inventory of a media library on disk.
+//!
+//! [`ReleaseParser::scan`](crate::ReleaseParser::scan) walks a library root
+//! and sorts every media file into a series (by season) or a movie (as one
+//! of its versions). Series and movie folders are read with
+//! [`parse_series_directory`](crate::ReleaseParser::parse_series_directory)
+//! and [`parse_movie_directory`](crate::ReleaseParser::parse_movie_directory);
+//! files outside such a folder are grouped by their parsed title.
+//!
+//! Subtitles, `.nfo` files and artwork named after a media file are attached
+//! to it, other ones to the series or movie whose folder they are in.
+
+use crate::parser::ReleaseParser;
+use crate::plan::{file_name, file_stem, has_extension, is_release_name, is_sidecar_of, walk_files, Skipped, MEDIA_EXTENSIONS, SIDECAR_EXTENSIONS};
+use crate::types::ParsedRelease;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// What a sidecar file holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SidecarKind {
+    Subtitle,
+    /// `.nfo` metadata
+    Info,
+    Artwork,
+}
+
+/// A file that belongs to a media file, series or movie.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sidecar {
+    pub path: PathBuf,
+    pub kind: SidecarKind,
+    /// Language code of a subtitle, `en` for `Movie.en.srt`
+    pub language: Option<String>,
+}
+
+/// A media file with its parse and sidecars.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaFile {
+    pub path: PathBuf,
+    pub parsed: ParsedRelease,
+    pub sidecars: Vec<Sidecar>,
+}
+
+/// Episode files of one season.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Season {
+    /// Season number; the year for episodes known only by air date
+    pub season: u128,
+    /// Files ordered by episode number, a multi-episode file once
+    pub episodes: Vec<MediaFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Series {
+    /// Series folder, `None` for files grouped by title
+    pub directory: Option<PathBuf>,
+    /// Title, year and IDs read from the folder name
+    pub info: ParsedRelease,
+    /// Seasons in order
+    pub seasons: Vec<Season>,
+    /// Files with no season or episode, like extras
+    pub other: Vec<MediaFile>,
+    /// Sidecars of the series or its seasons, like `tvshow.nfo` and `poster.jpg`
+    pub sidecars: Vec<Sidecar>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Movie {
+    /// Movie folder, `None` for files grouped by title
+    pub directory: Option<PathBuf>,
+    /// Title, year and IDs read from the folder name
+    pub info: ParsedRelease,
+    /// Every file of the movie: editions, qualities and parts
+    pub versions: Vec<MediaFile>,
+    pub sidecars: Vec<Sidecar>,
+}
+
+/// Everything found under a library root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Inventory {
+    pub root: PathBuf,
+    /// Series ordered by folder, then title
+    pub series: Vec<Series>,
+    pub movies: Vec<Movie>,
+    /// Files that are not media, can't be parsed or belong to nothing
+    pub unmatched: Vec<Skipped>,
+}
+
+impl Series {
+    /// The season numbered `season`.
+    pub fn season(&self, season: u128) -> Option<&Season> {
+        self.seasons.iter().find(|candidate| candidate.season == season)
+    }
+}
+
+impl Movie {
+    /// Distinct editions of the versions, in order of appearance.
+    pub fn editions(&self) -> Vec<&str> {
+        let mut editions: Vec<&str> = Vec::new();
+        for edition in self.versions.iter().filter_map(|version| version.parsed.edition.as_deref()) {
+            if !editions.contains(&edition) {
+                editions.push(edition);
+            }
+        }
+        editions
+    }
+}
+
+impl Inventory {
+    pub(crate) fn scan(parser: &ReleaseParser, root: &Path) -> io::Result<Self> {
+        let files = walk_files(root)?;
+        let sidecars: Vec<&PathBuf> = files.iter().filter(|path| has_extension(path, SIDECAR_EXTENSIONS)).collect();
+        let mut inventory = Self {
+            root: root.to_path_buf(),
+            series: Vec::new(),
+            movies: Vec::new(),
+            unmatched: Vec::new(),
+        };
+        // Index of each series or movie by folder and, for files outside one, title
+        let mut series_index: HashMap<(Option<PathBuf>, String), usize> = HashMap::new();
+        let mut movie_index: HashMap<(Option<PathBuf>, String), usize> = HashMap::new();
+        let mut attached: Vec<&PathBuf> = Vec::new();
+
+        for path in &files {
+            if has_extension(path, SIDECAR_EXTENSIONS) {
+                continue;
+            }
+            if !has_extension(path, MEDIA_EXTENSIONS) {
+                inventory.unmatched.push(Skipped::new(path, "not a media file"));
+                continue;
+            }
+            let path_info = match parser.try_parse_path(path) {
+                Ok(path_info) => path_info,
+                Err(error) => {
+                    inventory.unmatched.push(Skipped::new(path, &error.to_string()));
+                    continue;
+                }
+            };
+
+            let file_sidecars: Vec<Sidecar> = sidecars
+                .iter()
+                .filter(|sidecar| is_sidecar_of(sidecar, path))
+                .map(|sidecar| {
+                    attached.push(sidecar);
+                    Sidecar::new(sidecar, Some(path))
+                })
+                .collect();
+            let file = path_info.file;
+            let is_episode = path_info.season.is_some() || file.season.is_some() || file.episode.is_some() || file.date.is_some();
+
+            // The library folder: the parent of a season folder, or of the file
+            let season_dir = path.parent().filter(|_| path_info.season.is_some());
+            let folder = season_dir.unwrap_or(path).parent().filter(|folder| *folder != root && !folder.as_os_str().is_empty());
+            let directory = match (folder, path_info.directory) {
+                (Some(folder), Some(info)) if !is_release_name(parser, &info.release) => Some((folder.to_path_buf(), info)),
+                _ => None,
+            };
+            let key = match &directory {
+                Some((folder, _)) => (Some(folder.clone()), String::new()),
+                None => (None, file.title.to_lowercase()),
+            };
+            let info = match &directory {
+                Some((_, info)) => info.clone(),
+                None => ParsedRelease {
+                    title: file.title.clone(),
+                    year: file.year,
+                    tmdb_id: file.tmdb_id.clone(),
+                    tvdb_id: file.tvdb_id.clone(),
+                    imdb_id: file.imdb_id.clone(),
+                    release_type: if is_episode { "series" } else { "movie" }.to_string(),
+                    ..Default::default()
+                },
+            };
+
+            let season = file.season.or(path_info.season).or_else(|| file.date.map(|date| u128::from(date.year)));
+            let media = MediaFile {
+                path: path.clone(),
+                parsed: file,
+                sidecars: file_sidecars,
+            };
+            if is_episode {
+                let index = *series_index.entry(key.clone()).or_insert_with(|| {
+                    inventory.series.push(Series {
+                        directory: key.0.clone(),
+                        info,
+                        seasons: Vec::new(),
+                        other: Vec::new(),
+                        sidecars: Vec::new(),
+                    });
+                    inventory.series.len() - 1
+                });
+                inventory.series[index].add(season, media);
+            } else {
+                let index = *movie_index.entry(key.clone()).or_insert_with(|| {
+                    inventory.movies.push(Movie {
+                        directory: key.0.clone(),
+                        info,
+                        versions: Vec::new(),
+                        sidecars: Vec::new(),
+                    });
+                    inventory.movies.len() - 1
+                });
+                inventory.movies[index].versions.push(media);
+            }
+        }
+
+        for sidecar in sidecars.into_iter().filter(|sidecar| !attached.contains(sidecar)) {
+            let inside = |directory: &Option<PathBuf>| directory.as_ref().is_some_and(|directory| sidecar.starts_with(directory));
+            if let Some(series) = inventory.series.iter_mut().find(|series| inside(&series.directory)) {
+                series.sidecars.push(Sidecar::new(sidecar, None));
+            } else if let Some(movie) = inventory.movies.iter_mut().find(|movie| inside(&movie.directory)) {
+                movie.sidecars.push(Sidecar::new(sidecar, None));
+            } else {
+                inventory.unmatched.push(Skipped::new(sidecar, "no media file with the same name"));
+            }
+        }
+
+        for series in &mut inventory.series {
+            series.seasons.sort_by_key(|season| season.season);
+            for season in &mut series.seasons {
+                season.episodes.sort_by(|a, b| {
+                    let order = |file: &MediaFile| (first_episode(&file.parsed), file.parsed.date, file.path.clone());
+                    order(a).cmp(&order(b))
+                });
+            }
+        }
+        inventory.series.sort_by(|a, b| (&a.directory, &a.info.title).cmp(&(&b.directory, &b.info.title)));
+        inventory.movies.sort_by(|a, b| (&a.directory, &a.info.title).cmp(&(&b.directory, &b.info.title)));
+        inventory.unmatched.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(inventory)
+    }
+}
+
+impl Series {
+    fn add(&mut self, season: Option<u128>, file: MediaFile) {
+        let Some(season) = season else {
+            self.other.push(file);
+            return;
+        };
+        match self.seasons.iter_mut().find(|candidate| candidate.season == season) {
+            Some(existing) => existing.episodes.push(file),
+            None => self.seasons.push(Season { season, episodes: vec![file] }),
+        }
+    }
+}
+
+impl Sidecar {
+    /// A sidecar, with the language of a subtitle named after `media`.
+    fn new(path: &Path, media: Option<&Path>) -> Self {
+        let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or("").to_lowercase();
+        let kind = match extension.as_str() {
+            "nfo" => SidecarKind::Info,
+            "jpg" | "jpeg" | "png" | "tbn" => SidecarKind::Artwork,
+            _ => SidecarKind::Subtitle,
+        };
+        // `Movie.en.forced.srt`: the tags between the media name and the extension
+        let language = media.filter(|_| kind == SidecarKind::Subtitle).and_then(|media| {
+            let name = file_name(path);
+            let tags = name.get(file_stem(media).len()..name.len() - extension.len())?;
+            tags.split('.').find(|tag| (2..=3).contains(&tag.len()) && tag.chars().all(|c| c.is_ascii_alphabetic())).map(str::to_lowercase)
+        });
+        Self {
+            path: path.to_path_buf(),
+            kind,
+            language,
+        }
+    }
+}
+
+fn first_episode(parsed: &ParsedRelease) -> Option<u128> {
+    parsed.episodes.first().copied().or(parsed.episode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn touch(root: &Path, path: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"").unwrap();
+    }
+
+    fn library(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("scene_release_scan_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for path in [
+            "Seinfeld (1989) {tvdb-79169}/tvshow.nfo",
+            "Seinfeld (1989) {tvdb-79169}/Season 01/Seinfeld (1989) - S01E02 - The Stake Out [HDTV-720p]-GRP.mkv",
+            "Seinfeld (1989) {tvdb-79169}/Season 01/Seinfeld (1989) - S01E01 - The Seinfeld Chronicles [HDTV-720p]-GRP.mkv",
+            "Seinfeld (1989) {tvdb-79169}/Season 01/Seinfeld (1989) - S01E01 - The Seinfeld Chronicles [HDTV-720p]-GRP.en.srt",
+            "Seinfeld (1989) {tvdb-79169}/Season 02/Seinfeld (1989) - S02E01-E02 - The Ex-Girlfriend [HDTV-720p]-GRP.mkv",
+            "Vanilla Sky (2001) {tmdb-1903}/Vanilla Sky (2001) {edition-Directors Cut} [Bluray-1080p]-GRP.mkv",
+            "Vanilla Sky (2001) {tmdb-1903}/Vanilla Sky (2001) [WEBDL-720p]-GRP.mkv",
+            "Vanilla Sky (2001) {tmdb-1903}/Vanilla Sky (2001) [WEBDL-720p]-GRP.nfo",
+            "Vanilla Sky (2001) {tmdb-1903}/poster.jpg",
+            "notes.txt",
+        ] {
+            touch(&root, path);
+        }
+        root
+    }
+
+    #[test]
+    fn test_scan_series() {
+        let root = library("series");
+        let inventory = ReleaseParser::new("tv").scan(&root).unwrap();
+
+        assert_eq!(inventory.series.len(), 1);
+        let series = &inventory.series[0];
+        assert_eq!(series.info.title, "Seinfeld");
+        assert_eq!(series.info.tvdb_id.as_deref(), Some("79169"));
+        assert_eq!(series.sidecars.len(), 1);
+        assert_eq!(series.sidecars[0].kind, SidecarKind::Info);
+
+        let season = series.season(1).unwrap();
+        let episodes: Vec<Option<u128>> = season.episodes.iter().map(|file| file.parsed.episode).collect();
+        assert_eq!(episodes, vec![Some(1), Some(2)]);
+        assert_eq!(season.episodes[0].sidecars[0].language.as_deref(), Some("en"));
+        assert_eq!(series.season(2).unwrap().episodes[0].parsed.episodes, vec![1, 2]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_scan_movies() {
+        let root = library("movies");
+        let inventory = ReleaseParser::new("tv").scan(&root).unwrap();
+
+        assert_eq!(inventory.movies.len(), 1);
+        let movie = &inventory.movies[0];
+        assert_eq!(movie.info.title, "Vanilla Sky");
+        assert_eq!(movie.versions.len(), 2);
+        assert_eq!(movie.editions(), vec!["Directors Cut"]);
+        assert_eq!(movie.sidecars[0].kind, SidecarKind::Artwork);
+        let with_nfo = movie.versions.iter().find(|version| version.parsed.resolution == "720p").unwrap();
+        assert_eq!(with_nfo.sidecars[0].kind, SidecarKind::Info);
+
+        assert_eq!(inventory.unmatched.len(), 1);
+        assert_eq!(inventory.unmatched[0].path, root.join("notes.txt"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_scan_loose_files() {
+        let root = std::env::temp_dir().join(format!("scene_release_scan_loose_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        touch(&root, "Show.S01E01.720p.HDTV.x264-GRP/Show.S01E01.720p.HDTV.x264-GRP.mkv");
+        touch(&root, "Show.S01E02.720p.HDTV.x264-GRP.mkv");
+
+        let inventory = ReleaseParser::new("tv").scan(&root).unwrap();
+        assert_eq!(inventory.series.len(), 1);
+        assert_eq!(inventory.series[0].directory, None);
+        assert_eq!(inventory.series[0].season(1).unwrap().episodes.len(), 2);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,343 @@
+//! Custom-format scoring of parsed releases.
+//!
+//! [`ScoringRules`] are preference rules in the spirit of Sonarr and Radarr
+//! custom formats, read from TOML or JSON: each [`ScoringRule`] adds its
+//! score when all of its conditions hold, and a blocking rule rejects the
+//! release outright. A condition tests a field, a regex on the release
+//! name, the group against a list, or the presence of a flag.
+//!
+//! ```toml
+//! [[rules]]
+//! name = "DV HDR10"
+//! score = 100
+//! conditions = [{ kind = "field", field = "hdr", values = "DV HDR10" }]
+//!
+//! [[rules]]
+//! name = "x265 at 720p"
+//! score = -1000
+//! conditions = [
+//!     { kind = "field", field = "format", values = "x265" },
+//!     { kind = "field", field = "resolution", values = "720p" },
+//! ]
+//! ```
+
+use crate::types::ParsedRelease;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::error::Error;
+use std::fmt;
+
+/// A set of rules, scored together.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoringRules {
+    #[serde(default)]
+    pub rules: Vec<ScoringRule>,
+}
+
+/// Points given to a release that meets every condition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoringRule {
+    pub name: String,
+    #[serde(default)]
+    pub score: i64,
+    /// Reject the release when the rule matches, whatever its score
+    #[serde(default)]
+    pub block: bool,
+    /// All must hold; a rule without conditions matches every release
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+}
+
+/// A test on a release, optionally negated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Condition {
+    #[serde(flatten)]
+    pub test: Test,
+    /// Hold when the test fails instead
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub negate: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Test {
+    /// A field, by its [`get`](ParsedRelease::get) name, holds one of the
+    /// values. Technical fields compare by canonical name: `x265` is `HEVC`.
+    Field {
+        field: String,
+        #[serde(deserialize_with = "one_or_many")]
+        values: Vec<String>,
+    },
+    /// The regex matches the release name, ignoring case
+    Release { pattern: Pattern },
+    /// The group is in the list, ignoring case: an allow list, or a deny
+    /// list on a blocking rule
+    Group { groups: Vec<String> },
+    /// One of the flags is present, like `NUKED` or `PROPER`
+    Flag {
+        #[serde(deserialize_with = "one_or_many")]
+        flags: Vec<String>,
+    },
+}
+
+/// A regex compiled when the rules are read.
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+/// A rule that matched a release.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchedRule {
+    pub name: String,
+    pub score: i64,
+    pub block: bool,
+}
+
+/// Outcome of scoring a release.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    /// Sum of the scores of the matched rules
+    pub total: i64,
+    /// Matched rules, in rule order
+    pub matched: Vec<MatchedRule>,
+    /// Whether a blocking rule matched
+    pub blocked: bool,
+}
+
+/// Why scoring rules could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScoringError {
+    /// The TOML or JSON is malformed, or a regex doesn't compile
+    Syntax(String),
+    /// A field condition names a field [`get`](ParsedRelease::get) doesn't know
+    UnknownField { rule: String, field: String },
+}
+
+impl ScoringRules {
+    pub fn from_json(json: &str) -> Result<Self, ScoringError> {
+        serde_json::from_str::<Self>(json).map_err(|error| ScoringError::Syntax(error.to_string()))?.validated()
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, ScoringError> {
+        toml::from_str::<Self>(toml).map_err(|error| ScoringError::Syntax(error.to_string()))?.validated()
+    }
+
+    /// Score a release against every rule.
+    pub fn score(&self, parsed: &ParsedRelease) -> Score {
+        let mut score = Score::default();
+        for rule in self.rules.iter().filter(|rule| rule.matches(parsed)) {
+            score.total = score.total.saturating_add(rule.score);
+            score.blocked |= rule.block;
+            score.matched.push(MatchedRule {
+                name: rule.name.clone(),
+                score: rule.score,
+                block: rule.block,
+            });
+        }
+        score
+    }
+
+    fn validated(self) -> Result<Self, ScoringError> {
+        for rule in &self.rules {
+            for condition in &rule.conditions {
+                if let Test::Field { field, .. } = &condition.test {
+                    if !ParsedRelease::FIELDS.contains(&field.as_str()) {
+                        return Err(ScoringError::UnknownField {
+                            rule: rule.name.clone(),
+                            field: field.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(self)
+    }
+}
+
+impl ScoringRule {
+    pub fn matches(&self, parsed: &ParsedRelease) -> bool {
+        self.conditions.iter().all(|condition| condition.holds(parsed))
+    }
+}
+
+impl Condition {
+    pub fn holds(&self, parsed: &ParsedRelease) -> bool {
+        let passed = match &self.test {
+            Test::Field { field, values } => values.iter().any(|value| parsed.field_is(field, value)),
+            Test::Release { pattern } => pattern.0.is_match(&parsed.release),
+            Test::Group { groups } => !parsed.group.is_empty() && groups.iter().any(|group| group.eq_ignore_ascii_case(&parsed.group)),
+            Test::Flag { flags } => flags.iter().any(|flag| parsed.flags.iter().any(|candidate| candidate.eq_ignore_ascii_case(flag))),
+        };
+        passed != self.negate
+    }
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        RegexBuilder::new(pattern).case_insensitive(true).build().map(Self)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Accept `"x265"` as well as `["x265", "HEVC"]`.
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+impl fmt::Display for ScoringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(reason) => write!(f, "invalid scoring rules: {}", reason),
+            Self::UnknownField { rule, field } => write!(f, "rule {:?} tests unknown field {:?}", rule, field),
+        }
+    }
+}
+
+impl Error for ScoringError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReleaseParser;
+
+    const RULES: &str = r#"
+        [[rules]]
+        name = "DV HDR10"
+        score = 100
+        conditions = [{ kind = "field", field = "hdr", values = "DV HDR10" }]
+
+        [[rules]]
+        name = "x265 at 720p"
+        score = -1000
+        conditions = [
+            { kind = "field", field = "format", values = "x265" },
+            { kind = "field", field = "resolution", values = "720p" },
+        ]
+
+        [[rules]]
+        name = "AMZN or NF"
+        score = 50
+        conditions = [{ kind = "field", field = "streaming_provider", values = ["AMZN", "NF"] }]
+
+        [[rules]]
+        name = "CAM"
+        block = true
+        conditions = [{ kind = "field", field = "source", values = "CAM" }]
+
+        [[rules]]
+        name = "Denied groups"
+        block = true
+        conditions = [{ kind = "group", groups = ["BAD"] }]
+
+        [[rules]]
+        name = "Not nuked"
+        score = 5
+        conditions = [{ kind = "flag", flags = "NUKED", negate = true }]
+
+        [[rules]]
+        name = "Atmos"
+        score = 20
+        conditions = [{ kind = "release", pattern = '\bAtmos\b' }]
+    "#;
+
+    fn score(release: &str) -> Score {
+        let rules = ScoringRules::from_toml(RULES).unwrap();
+        rules.score(&ReleaseParser::new("movie").parse(release))
+    }
+
+    fn names(score: &Score) -> Vec<&str> {
+        score.matched.iter().map(|rule| rule.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_score() {
+        let dv = score("Movie.2020.2160p.NF.WEB-DL.DDP5.1.Atmos.DV.HDR10.H.265-GRP");
+        assert_eq!(names(&dv), vec!["DV HDR10", "AMZN or NF", "Not nuked", "Atmos"]);
+        assert_eq!(dv.total, 175);
+        assert!(!dv.blocked);
+
+        // HEVC is x265 by canonical name
+        let hevc = score("Movie.2020.720p.BluRay.HEVC-GRP");
+        assert_eq!(hevc.total, -995);
+        assert_eq!(score("Movie.2020.1080p.BluRay.x265-GRP").total, 5);
+    }
+
+    #[test]
+    fn test_block() {
+        let cam = score("Movie.2020.CAM.x264-GRP");
+        assert!(cam.blocked);
+        assert_eq!(names(&cam), vec!["CAM", "Not nuked"]);
+        assert!(score("Movie.2020.1080p.BluRay.x264-bad").blocked);
+        assert_eq!(score("Movie.2020.NUKED.1080p.BluRay.x264-GRP").total, 0);
+    }
+
+    #[test]
+    fn test_json_and_errors() {
+        let rules = ScoringRules::from_json(r#"{"rules": [{"name": "Remux", "score": 10, "conditions": [{"kind": "release", "pattern": "remux"}]}]}"#).unwrap();
+        let parsed = ReleaseParser::new("movie").parse("Movie.2020.1080p.BluRay.REMUX.AVC-GRP");
+        assert_eq!(rules.score(&parsed).total, 10);
+
+        let unknown = ScoringRules::from_json(r#"{"rules": [{"name": "Bad", "conditions": [{"kind": "field", "field": "codec", "values": "x265"}]}]}"#);
+        assert_eq!(
+            unknown,
+            Err(ScoringError::UnknownField {
+                rule: "Bad".to_string(),
+                field: "codec".to_string()
+            })
+        );
+        let invalid = ScoringRules::from_toml("[[rules]]\nname = \"Bad\"\nconditions = [{ kind = \"release\", pattern = \"(\" }]");
+        assert!(matches!(invalid, Err(ScoringError::Syntax(_))));
+    }
+
+    #[test]
+    fn test_flags_field() {
+        let rules = ScoringRules::from_json(r#"{"rules": [{"name": "Proper", "score": 10, "conditions": [{"kind": "field", "field": "flags", "values": "proper"}]}]}"#).unwrap();
+        let parsed = ReleaseParser::new("movie").parse("Movie.2020.PROPER.REPACK.1080p.BluRay.x264-GRP");
+        assert_eq!(parsed.get("flags").as_deref(), Some("PROPER,REPACK"));
+        assert_eq!(rules.score(&parsed).total, 10);
+        assert_eq!(rules.score(&ReleaseParser::new("movie").parse("Movie.2020.1080p.BluRay.x264-GRP")).total, 0);
+    }
+
+    #[test]
+    fn test_total_saturates() {
+        let rules = ScoringRules::from_json(&format!(
+            r#"{{"rules": [{{"name": "Huge", "score": {max}, "conditions": []}}, {{"name": "Huger", "score": {max}, "conditions": []}}]}}"#,
+            max = i64::MAX
+        ))
+        .unwrap();
+        let parsed = ReleaseParser::new("movie").parse("Movie.2020.1080p.BluRay.x264-GRP");
+        assert_eq!(rules.score(&parsed).total, i64::MAX);
+    }
+}
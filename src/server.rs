@@ -0,0 +1,355 @@
+//! Local HTTP service answering parse requests with JSON.
+//!
+//! Built with the `server` feature. [`ParseService::serve`] listens on
+//! localhost by default and answers:
+//!
+//! - `POST /parse` with `{"name": "..."}`: the [`ParsedRelease`]
+//! - `POST /parse/batch` with `{"names": [...]}`: an array of them, with
+//!   `{"release": "...", "error": "..."}` for names that fail
+//! - `POST /parse/path` with `{"path": "..."}`: the [`PathInfo`](crate::PathInfo)
+//! - `GET /health`: `{"status": "ok", ...}`
+//!
+//! Requests may add `"type"` (a release type or `"auto"`, the default) and
+//! `"strict"`. Errors are `{"error": "..."}` with a 4xx status. Results are
+//! kept in an LRU cache, so repeated names are not parsed again.
+
+use crate::classify::RELEASE_TYPES;
+use crate::parser::ReleaseParser;
+use crate::types::ParsedRelease;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::sync::Mutex;
+
+/// Settings of a [`ParseService`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerConfig {
+    /// Address to listen on, `127.0.0.1:8080` by default
+    pub address: String,
+    /// Largest request body accepted, 64 KiB by default
+    pub max_body_bytes: usize,
+    /// Most names in one batch, 1000 by default
+    pub max_batch_size: usize,
+    /// Number of results kept in the cache, 0 to disable it
+    pub cache_size: usize,
+}
+
+/// Status and JSON body of an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+/// Answers parse requests, with or without the HTTP server around it.
+#[derive(Debug)]
+pub struct ParseService {
+    config: ServerConfig,
+    cache: Mutex<Cache>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Options {
+    #[serde(rename = "type", default)]
+    release_type: Option<String>,
+    #[serde(default)]
+    strict: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParseRequest {
+    name: String,
+    #[serde(flatten)]
+    options: Options,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchRequest {
+    names: Vec<String>,
+    #[serde(flatten)]
+    options: Options,
+}
+
+#[derive(Debug, Deserialize)]
+struct PathRequest {
+    path: String,
+    #[serde(flatten)]
+    options: Options,
+}
+
+/// Least recently used results, as JSON.
+#[derive(Debug)]
+struct Cache {
+    capacity: usize,
+    /// Result and the tick of its last use, by request
+    entries: HashMap<String, (String, u64)>,
+    tick: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:8080".to_string(),
+            max_body_bytes: 64 * 1024,
+            max_batch_size: 1000,
+            cache_size: 1024,
+        }
+    }
+}
+
+impl Response {
+    fn json(status: u16, body: String) -> Self {
+        Self { status, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, serde_json::json!({ "error": message }).to_string())
+    }
+}
+
+impl ParseService {
+    pub fn new(config: ServerConfig) -> Self {
+        let cache = Mutex::new(Cache::new(config.cache_size));
+        Self { config, cache }
+    }
+
+    pub fn config(&self) -> &ServerConfig {
+        &self.config
+    }
+
+    /// Answer a request. `path` may carry a query string, which is ignored.
+    pub fn handle(&self, method: &str, path: &str, body: &[u8]) -> Response {
+        let path = path.split('?').next().unwrap_or_default().trim_end_matches('/');
+        if body.len() > self.config.max_body_bytes {
+            return Response::error(413, &format!("request body is over {} bytes", self.config.max_body_bytes));
+        }
+        match (method, path) {
+            ("GET", "/health") => self.health(),
+            ("POST", "/parse") => self.parse(body),
+            ("POST", "/parse/batch") => self.parse_batch(body),
+            ("POST", "/parse/path") => self.parse_path(body),
+            (_, "/health" | "/parse" | "/parse/batch" | "/parse/path") => Response::error(405, &format!("{} is not allowed on {}", method, path)),
+            _ => Response::error(404, &format!("no endpoint {}", path)),
+        }
+    }
+
+    /// Listen on the configured address and answer requests until the
+    /// listener fails.
+    pub fn serve(&self) -> io::Result<()> {
+        let server = tiny_http::Server::http(&self.config.address).map_err(io::Error::other)?;
+        for mut request in server.incoming_requests() {
+            let response = if request.body_length().is_some_and(|length| length > self.config.max_body_bytes) {
+                Response::error(413, &format!("request body is over {} bytes", self.config.max_body_bytes))
+            } else {
+                // One byte more than allowed, so chunked bodies over the limit are caught too
+                let mut body = Vec::new();
+                match request.as_reader().take(self.config.max_body_bytes as u64 + 1).read_to_end(&mut body) {
+                    Ok(_) => self.handle(request.method().as_str(), request.url(), &body),
+                    Err(error) => Response::error(400, &error.to_string()),
+                }
+            };
+            let header = tiny_http::Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+            let _ = request.respond(tiny_http::Response::from_string(response.body).with_status_code(response.status).with_header(header));
+        }
+        Ok(())
+    }
+
+    fn health(&self) -> Response {
+        let cached = self.cache.lock().map(|cache| cache.entries.len()).unwrap_or(0);
+        Response::json(200, serde_json::json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION"), "cached": cached }).to_string())
+    }
+
+    fn parse(&self, body: &[u8]) -> Response {
+        let request: ParseRequest = match read(body) {
+            Ok(request) => request,
+            Err(response) => return response,
+        };
+        match self.parser(&request.options) {
+            Ok(parser) => match self.parse_name(&parser, &request.options, &request.name) {
+                Ok(json) => Response::json(200, json),
+                Err(error) => Response::error(422, &error),
+            },
+            Err(response) => response,
+        }
+    }
+
+    fn parse_batch(&self, body: &[u8]) -> Response {
+        let request: BatchRequest = match read(body) {
+            Ok(request) => request,
+            Err(response) => return response,
+        };
+        if request.names.len() > self.config.max_batch_size {
+            return Response::error(413, &format!("batch has over {} names", self.config.max_batch_size));
+        }
+        let parser = match self.parser(&request.options) {
+            Ok(parser) => parser,
+            Err(response) => return response,
+        };
+        let results: Vec<String> = request
+            .names
+            .iter()
+            .map(|name| {
+                self.parse_name(&parser, &request.options, name)
+                    .unwrap_or_else(|error| serde_json::json!({ "release": name, "error": error }).to_string())
+            })
+            .collect();
+        Response::json(200, format!("[{}]", results.join(",")))
+    }
+
+    fn parse_path(&self, body: &[u8]) -> Response {
+        let request: PathRequest = match read(body) {
+            Ok(request) => request,
+            Err(response) => return response,
+        };
+        let parser = match self.parser(&request.options) {
+            Ok(parser) => parser,
+            Err(response) => return response,
+        };
+        let result = self.cached("path", &request.options, &request.path, || {
+            let path_info = parser.try_parse_path(&request.path).map_err(|error| error.to_string())?;
+            Ok(serde_json::to_string(&path_info).expect("parse results serialize to JSON"))
+        });
+        match result {
+            Ok(json) => Response::json(200, json),
+            Err(error) => Response::error(422, &error),
+        }
+    }
+
+    fn parse_name(&self, parser: &ReleaseParser, options: &Options, name: &str) -> Result<String, String> {
+        self.cached("name", options, name, || {
+            let parsed: ParsedRelease = parser.try_parse(name).map_err(|error| error.to_string())?;
+            Ok(serde_json::to_string(&parsed).expect("parse results serialize to JSON"))
+        })
+    }
+
+    /// The cached result of a request, or `parse` run and, when it succeeds, cached.
+    fn cached(&self, kind: &str, options: &Options, input: &str, parse: impl FnOnce() -> Result<String, String>) -> Result<String, String> {
+        // The type as the parser sees it, so "TV" and "tv" share an entry
+        let release_type = options.release_type.as_deref().map_or_else(|| "auto".to_string(), str::to_lowercase);
+        let key = format!("{}\0{}\0{}\0{}", kind, release_type, options.strict, input);
+        if let Some(json) = self.cache.lock().ok().and_then(|mut cache| cache.get(&key)) {
+            return Ok(json);
+        }
+        let json = parse()?;
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(key, json.clone());
+        }
+        Ok(json)
+    }
+
+    fn parser(&self, options: &Options) -> Result<ReleaseParser, Response> {
+        let parser = match options.release_type.as_deref().map(str::to_lowercase) {
+            None => ReleaseParser::auto(),
+            Some(release_type) if release_type == "auto" => ReleaseParser::auto(),
+            Some(release_type) if RELEASE_TYPES.contains(&release_type.as_str()) => ReleaseParser::new(&release_type),
+            Some(release_type) => return Err(Response::error(400, &format!("unknown release type {:?}", release_type))),
+        };
+        Ok(parser.strict(options.strict))
+    }
+}
+
+fn read<'a, T: Deserialize<'a>>(body: &'a [u8]) -> Result<T, Response> {
+    serde_json::from_slice(body).map_err(|error| Response::error(400, &format!("invalid request: {}", error)))
+}
+
+impl Cache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<String> {
+        self.tick += 1;
+        let (value, used) = self.entries.get_mut(key)?;
+        *used = self.tick;
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: String, value: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            if let Some(oldest) = self.entries.iter().min_by_key(|(_, (_, used))| *used).map(|(key, _)| key.clone()) {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.tick += 1;
+        self.entries.insert(key, (value, self.tick));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn post(service: &ParseService, path: &str, body: &str) -> (u16, Value) {
+        let response = service.handle("POST", path, body.as_bytes());
+        (response.status, serde_json::from_str(&response.body).unwrap())
+    }
+
+    #[test]
+    fn test_parse_endpoints() {
+        let service = ParseService::new(ServerConfig::default());
+        let (status, json) = post(&service, "/parse", r#"{"name": "Show.S01E02.720p.HDTV.x264-GRP"}"#);
+        assert_eq!(status, 200);
+        assert_eq!(json["type"], "tv");
+        assert_eq!(json["episode"], 2);
+
+        let (status, json) = post(&service, "/parse/batch", r#"{"names": ["Movie.2020.1080p.BluRay.x264-GRP", "Movie.2020"], "type": "movie", "strict": true}"#);
+        assert_eq!(status, 200);
+        assert_eq!(json[0]["year"], 2020);
+        assert_eq!(json[1]["error"], "could not determine group");
+
+        let (status, json) = post(&service, "/parse/path", r#"{"path": "Show (2020)/Season 01/Show.S01E02.mkv", "type": "tv"}"#);
+        assert_eq!(status, 200);
+        assert_eq!(json["season"], 1);
+        let (status, _) = post(&service, "/parse/path", r#"{"path": "Season 01/Show.S01E02.mkv"}"#);
+        assert_eq!(status, 422);
+    }
+
+    #[test]
+    fn test_errors_and_limits() {
+        let service = ParseService::new(ServerConfig {
+            max_body_bytes: 100,
+            max_batch_size: 2,
+            ..Default::default()
+        });
+        assert_eq!(post(&service, "/parse", "{").0, 400);
+        assert_eq!(post(&service, "/parse", r#"{"name": "x", "type": "tvshow"}"#).0, 400);
+        assert_eq!(post(&service, "/parse", &format!(r#"{{"name": "{}"}}"#, "a".repeat(100))).0, 413);
+        assert_eq!(post(&service, "/parse/batch", r#"{"names": ["a", "b", "c"]}"#).0, 413);
+        assert_eq!(post(&service, "/nothing", "{}").0, 404);
+        assert_eq!(service.handle("GET", "/parse", b"").status, 405);
+
+        let health = service.handle("GET", "/health?verbose", b"");
+        assert_eq!(health.status, 200);
+        assert!(health.body.contains(r#""status":"ok""#));
+    }
+
+    #[test]
+    fn test_cache() {
+        let service = ParseService::new(ServerConfig {
+            cache_size: 2,
+            ..Default::default()
+        });
+        for name in ["A.S01E01-GRP", "B.S01E01-GRP", "A.S01E01-GRP", "C.S01E01-GRP"] {
+            post(&service, "/parse", &format!(r#"{{"name": "{}"}}"#, name));
+        }
+        let cache = service.cache.lock().unwrap();
+        assert_eq!(cache.entries.len(), 2);
+        // B was used least recently
+        assert!(!cache.entries.keys().any(|key| key.ends_with("B.S01E01-GRP")));
+        drop(cache);
+
+        let service = ParseService::new(ServerConfig::default());
+        for release_type in ["tv", "TV", "Tv"] {
+            post(&service, "/parse", &format!(r#"{{"name": "A.S01E01-GRP", "type": "{}"}}"#, release_type));
+        }
+        assert_eq!(service.cache.lock().unwrap().entries.len(), 1);
+    }
+}
@@ -0,0 +1,252 @@
+//! Splits a release name into typed tokens with byte spans.
+//!
+//! The tokenizer knows nothing about sources, codecs or groups; it only cuts
+//! the release name into words and records how each word was delimited. The
+//! parser then assigns every token to at most one field.
+
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// How a token was delimited in the release name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Separator {
+    /// `Title.2023.1080p`
+    Dot,
+    /// `Title 2023 1080p`
+    Space,
+    /// `Title_2023_1080p`
+    Underscore,
+    /// `Title - S01E01 - Episode`
+    Dash,
+    /// Inside `[...]`
+    Bracket,
+    /// Inside `(...)`
+    Parenthesis,
+    /// Inside `{...}`
+    Brace,
+    /// The trailing `-GROUP` suffix
+    Group,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Token {
+    pub text: String,
+    /// Byte span of `text` in the release name
+    pub span: Range<usize>,
+    pub separator: Separator,
+}
+
+impl Token {
+    /// Whether the token lies completely inside `span`.
+    pub fn is_within(&self, span: &Range<usize>) -> bool {
+        span.start <= self.span.start && self.span.end <= span.end
+    }
+}
+
+/// Split a release name into tokens.
+///
+/// Words are separated by dots, spaces, underscores and standalone dashes.
+/// Bracket, parenthesis and brace contents are split the same way but keep
+/// their enclosing style. A few dotted forms are kept whole: audio channels
+/// (`5.1`, `DDP2.0`) and `H.264`/`H.265`. A dash between letters and digits
+/// (`WEBDL-1080p`) splits the word, other dashes (`WEB-DL`, `S01E01-E02`) don't.
+pub fn tokenize(release_name: &str) -> Vec<Token> {
+    let bytes = release_name.as_bytes();
+    let group_start = group_suffix_start(release_name);
+    let mut tokens = Vec::new();
+    let mut context: Vec<Separator> = Vec::new();
+    let mut last_separator = None;
+    let mut word_start = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let closes_word = match c {
+            b'.' => !keeps_dot(bytes, i),
+            b' ' | b'_' | b'[' | b']' | b'(' | b')' | b'{' | b'}' => true,
+            b'-' => standalone_dash(bytes, i) || Some(i + 1) == group_start || splits_dash(bytes, i, word_start, &context),
+            _ => false,
+        };
+
+        if !closes_word {
+            if word_start.is_none() {
+                word_start = Some(i);
+            }
+            i += 1;
+            continue;
+        }
+
+        if let Some(start) = word_start.take() {
+            push_word(&mut tokens, release_name, start..i, &context, last_separator, group_start);
+            last_separator = None;
+        }
+        let separator = match c {
+            b'[' => {
+                context.push(Separator::Bracket);
+                None
+            }
+            b'(' => {
+                context.push(Separator::Parenthesis);
+                None
+            }
+            b'{' => {
+                context.push(Separator::Brace);
+                None
+            }
+            b']' | b')' | b'}' => {
+                context.pop();
+                None
+            }
+            b'.' => Some(Separator::Dot),
+            b' ' => Some(Separator::Space),
+            b'_' => Some(Separator::Underscore),
+            _ => Some(Separator::Dash),
+        };
+        // In a run like " - " the dash wins over the surrounding spaces
+        if last_separator != Some(Separator::Dash) {
+            last_separator = separator.or(last_separator);
+        }
+        i += 1;
+    }
+    if let Some(start) = word_start {
+        push_word(&mut tokens, release_name, start..bytes.len(), &context, last_separator, group_start);
+    }
+
+    tokens
+}
+
+fn push_word(
+    tokens: &mut Vec<Token>,
+    release_name: &str,
+    span: Range<usize>,
+    context: &[Separator],
+    last_separator: Option<Separator>,
+    group_start: Option<usize>,
+) {
+    let text = &release_name[span.clone()];
+    let separator = if group_start == Some(span.start) {
+        Separator::Group
+    } else if let Some(enclosing) = context.last() {
+        *enclosing
+    } else {
+        last_separator.unwrap_or_else(|| first_separator(&release_name[span.end..]))
+    };
+    tokens.push(Token {
+        text: text.to_string(),
+        span,
+        separator,
+    });
+}
+
+/// The separator style of the first token is taken from what follows it.
+fn first_separator(rest: &str) -> Separator {
+    match rest.bytes().find(|c| matches!(c, b'.' | b' ' | b'_' | b'-')) {
+        Some(b'.') => Separator::Dot,
+        Some(b'_') => Separator::Underscore,
+        Some(b'-') => Separator::Dash,
+        _ => Separator::Space,
+    }
+}
+
+/// Start of a trailing `-GROUP` suffix (e.g. `x264-GROUP`), if any.
+fn group_suffix_start(release_name: &str) -> Option<usize> {
+    let dash = release_name.rfind('-')?;
+    let group = &release_name[dash + 1..];
+    let glued = dash > 0 && !matches!(release_name.as_bytes()[dash - 1], b' ' | b'-');
+    if glued && !group.is_empty() && group.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '@') {
+        Some(dash + 1)
+    } else {
+        None
+    }
+}
+
+/// A dash with whitespace (or nothing) on both sides: `Title - S01E01`.
+fn standalone_dash(bytes: &[u8], i: usize) -> bool {
+    let open_before = i == 0 || bytes[i - 1].is_ascii_whitespace();
+    let open_after = bytes.get(i + 1).is_none_or(|c| c.is_ascii_whitespace());
+    open_before && open_after
+}
+
+/// Keep `5.1`, `DDP2.0` and `H.264` together.
+fn keeps_dot(bytes: &[u8], i: usize) -> bool {
+    let before = &bytes[..i];
+    let after = &bytes[i + 1..];
+    let digits_before = before.iter().rev().take_while(|c| c.is_ascii_digit()).count();
+    let digits_after = after.iter().take_while(|c| c.is_ascii_digit()).count();
+    let ends_after = after.get(digits_after).is_none_or(|c| !c.is_ascii_alphanumeric());
+
+    // Audio channels: a single digit on both sides
+    if digits_before == 1 && digits_after == 1 && ends_after {
+        return true;
+    }
+    // H.264 / H.265
+    let h_before = before.last().is_some_and(|c| c.eq_ignore_ascii_case(&b'h'))
+        && before.len().checked_sub(2).is_none_or(|j| !bytes[j].is_ascii_alphanumeric());
+    h_before && (after.starts_with(b"264") || after.starts_with(b"265")) && digits_after == 3
+}
+
+/// Split `WEBDL-1080p` and `Remux-2160p`, but not `WEB-DL`, `DTS-HD` or `tmdb-123`.
+fn splits_dash(bytes: &[u8], i: usize, word_start: Option<usize>, context: &[Separator]) -> bool {
+    if word_start.is_none() || context.last() == Some(&Separator::Brace) {
+        return false;
+    }
+    let letter_before = i > 0 && bytes[i - 1].is_ascii_alphabetic();
+    let digit_after = bytes.get(i + 1).is_some_and(|c| c.is_ascii_digit());
+    letter_before && digit_after
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(release_name: &str) -> Vec<String> {
+        tokenize(release_name).into_iter().map(|token| token.text).collect()
+    }
+
+    #[test]
+    fn test_dot_separated_release() {
+        let tokens = tokenize("The.Matrix.1999.1080p.BluRay.x264-GROUP");
+
+        assert_eq!(texts("The.Matrix.1999.1080p.BluRay.x264-GROUP"), vec!["The", "Matrix", "1999", "1080p", "BluRay", "x264", "GROUP"]);
+        assert_eq!(tokens[0].separator, Separator::Dot);
+        assert_eq!(tokens[6].separator, Separator::Group);
+        assert_eq!(tokens[6].span, 34..39);
+    }
+
+    #[test]
+    fn test_bracket_release() {
+        let release = "Arrow (2012) - S05E04 - Penance [Bluray-1080p Remux][DTS-HD MA 5.1][AVC]-EPSiLON";
+        let tokens = tokenize(release);
+
+        assert_eq!(texts(release), vec!["Arrow", "2012", "S05E04", "Penance", "Bluray", "1080p", "Remux", "DTS-HD", "MA", "5.1", "AVC", "EPSiLON"]);
+        assert_eq!(tokens[1].separator, Separator::Parenthesis);
+        assert_eq!(tokens[2].separator, Separator::Dash);
+        assert_eq!(tokens[4].separator, Separator::Bracket);
+        assert_eq!(tokens[11].separator, Separator::Group);
+        for token in &tokens {
+            assert_eq!(&release[token.span.clone()], token.text);
+        }
+    }
+
+    #[test]
+    fn test_braces_keep_ids_together() {
+        assert_eq!(texts("Seinfeld (1989) {tvdb-79169}"), vec!["Seinfeld", "1989", "tvdb-79169"]);
+        assert_eq!(tokenize("Seinfeld (1989) {tvdb-79169}")[2].separator, Separator::Brace);
+    }
+
+    #[test]
+    fn test_dotted_codecs_and_channels() {
+        assert_eq!(texts("Movie.2021.WEB-DL.DDP2.0.H.264-SQS"), vec!["Movie", "2021", "WEB-DL", "DDP2.0", "H.264", "SQS"]);
+        assert_eq!(texts("24.S02E02.9.00.Uhr"), vec!["24", "S02E02", "9", "00", "Uhr"]);
+    }
+
+    #[test]
+    fn test_underscores_and_spaces() {
+        let tokens = tokenize("Some_Show_S01E01_720p");
+        assert_eq!(texts("Some_Show_S01E01_720p"), vec!["Some", "Show", "S01E01", "720p"]);
+        assert!(tokens.iter().all(|token| token.separator == Separator::Underscore));
+
+        assert_eq!(tokenize("Doctor (2012) 1080p")[2].separator, Separator::Space);
+    }
+}
@@ -1,5 +1,9 @@
+use crate::date::AirDate;
+use crate::media::{AudioCodec, HdrFormat, Resolution, Source, VideoCodec};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct ParsedRelease {
@@ -9,11 +13,30 @@ pub struct ParsedRelease {
     pub episode_title: String,
     pub group: String,
     pub year: Option<u16>,
-    pub date: Option<String>,
+    /// Air date of a daily episode
+    pub date: Option<AirDate>,
     pub season: Option<u128>,
     pub episode: Option<u128>,
     pub episodes: Vec<u128>,
+    /// Seasons of a season pack, in order
+    #[serde(default)]
+    pub seasons: Vec<u128>,
+    /// Kind of season pack, `None` for anything else
+    #[serde(default)]
+    pub pack: Option<PackKind>,
+    /// Kind of special, OVA or extra, `None` for regular episodes
+    #[serde(default)]
+    pub special_kind: Option<SpecialKind>,
     pub disc: Option<u8>,
+    /// Part of a miniseries, documentary or split movie: `Part.3.of.6`, `Pt.2`, `Part.II`
+    #[serde(default)]
+    pub part: Option<u16>,
+    /// Number of parts, when given as in `Part.3.of.6`
+    #[serde(default)]
+    pub part_total: Option<u16>,
+    /// Volume of a release sold in volumes: `Vol.3`
+    #[serde(default)]
+    pub volume: Option<u16>,
     pub flags: Vec<String>,
     pub source: String,
     pub format: String,
@@ -31,10 +54,34 @@ pub struct ParsedRelease {
     pub streaming_provider: String,
     #[serde(rename = "type")]
     pub release_type: String,
+    /// Tokens no field was read from, in order
+    #[serde(default)]
+    pub unparsed_tokens: Vec<String>,
+    /// Byte spans of [`unparsed_tokens`](Self::unparsed_tokens) in the release name
+    #[serde(default)]
+    pub unparsed_spans: Vec<Range<usize>>,
 }
 
+/// What a season pack holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackKind {
+    /// One whole season: `S02`, `Season 2`
+    Season,
+    /// Several seasons: `S01-S05`, `Season 1-3`
+    SeasonRange,
+    /// Every season of the show: `Complete Series`
+    CompleteSeries,
+}
 
 impl ParsedRelease {
+    /// Names of the fields [`get`](Self::get) knows.
+    pub const FIELDS: &'static [&'static str] = &[
+        "release", "title", "title_extra", "episode_title", "group", "year", "date", "season", "episode", "episodes", "seasons", "pack", "special_kind", "disc", "part", "part_total", "volume", "source",
+        "format", "resolution", "audio", "device", "os", "version", "tmdb_id", "tvdb_id", "imdb_id", "edition", "hdr",
+        "streaming_provider", "type", "unparsed_tokens", "flags",
+    ];
+
     pub fn get(&self, field: &str) -> Option<String> {
         match field {
             "release" => Some(self.release.clone()),
@@ -43,7 +90,7 @@ impl ParsedRelease {
             "episode_title" => Some(self.episode_title.clone()),
             "group" => Some(self.group.clone()),
             "year" => self.year.map(|y| y.to_string()),
-            "date" => self.date.clone(),
+            "date" => self.date.map(|date| date.to_string()),
             "season" => self.season.map(|s| s.to_string()),
             "episode" => self.episode.map(|e| e.to_string()),
             "episodes" => if self.episodes.is_empty() {
@@ -51,7 +98,17 @@ impl ParsedRelease {
             } else {
                 Some(self.episodes.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(","))
             },
+            "seasons" => if self.seasons.is_empty() {
+                None
+            } else {
+                Some(self.seasons.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(","))
+            },
+            "pack" => self.pack.map(|pack| pack.to_string()),
+            "special_kind" => self.special_kind.map(|kind| kind.to_string()),
             "disc" => self.disc.map(|d| d.to_string()),
+            "part" => self.part.map(|part| part.to_string()),
+            "part_total" => self.part_total.map(|total| total.to_string()),
+            "volume" => self.volume.map(|volume| volume.to_string()),
             "source" => Some(self.source.clone()),
             "format" => Some(self.format.clone()),
             "resolution" => Some(self.resolution.clone()),
@@ -66,9 +123,117 @@ impl ParsedRelease {
             "hdr" => Some(self.hdr.clone()),
             "streaming_provider" => Some(self.streaming_provider.clone()),
             "type" => Some(self.release_type.clone()),
+            "unparsed_tokens" => if self.unparsed_tokens.is_empty() {
+                None
+            } else {
+                Some(self.unparsed_tokens.join(","))
+            },
+            "flags" => if self.flags.is_empty() {
+                None
+            } else {
+                Some(self.flags.join(","))
+            },
             _ => None,
         }
     }
+
+    /// [`source`](Self::source) as a [`Source`], `None` if no source was found.
+    pub fn source(&self) -> Option<Source> {
+        typed(&self.source)
+    }
+
+    /// [`format`](Self::format) as a [`VideoCodec`], `None` if no codec was found.
+    pub fn video_codec(&self) -> Option<VideoCodec> {
+        typed(&self.format)
+    }
+
+    /// [`resolution`](Self::resolution) as a [`Resolution`], `None` if no resolution was found.
+    pub fn resolution(&self) -> Option<Resolution> {
+        typed(&self.resolution)
+    }
+
+    /// [`hdr`](Self::hdr) as an [`HdrFormat`], `None` if the release is not HDR.
+    pub fn hdr_format(&self) -> Option<HdrFormat> {
+        typed(&self.hdr)
+    }
+
+    /// Codec part of [`audio`](Self::audio) as an [`AudioCodec`], `None` if no audio was found.
+    pub fn audio_codec(&self) -> Option<AudioCodec> {
+        AudioCodec::from_audio(&self.audio)
+    }
+
+    /// Whether `field` holds `value`. Technical fields compare by canonical
+    /// name, so `x265` is `HEVC`; numbers compare as numbers and other text
+    /// ignoring case. An empty `value` matches a field that was not found.
+    pub(crate) fn field_is(&self, field: &str, value: &str) -> bool {
+        match field {
+            "source" => self.source() == typed(value),
+            "format" => self.video_codec() == typed(value),
+            "resolution" => self.resolution() == typed(value),
+            "hdr" => self.hdr_format() == typed(value),
+            "audio" => self.audio_codec() == AudioCodec::from_audio(value),
+            // A release has several flags; it is enough that one matches
+            "flags" => self.flags.iter().any(|flag| flag.eq_ignore_ascii_case(value.trim())),
+            _ => {
+                let actual = self.get(field).unwrap_or_default();
+                match (actual.parse::<u128>(), value.trim().parse::<u128>()) {
+                    (Ok(actual), Ok(value)) => actual == value,
+                    _ => actual.eq_ignore_ascii_case(value.trim()),
+                }
+            }
+        }
+    }
+}
+
+/// What a TV release holds besides a regular episode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecialKind {
+    /// `Special`, `SP3`, or any episode of season 0
+    Special,
+    /// Original video animation, released straight to video
+    Ova,
+    /// Original animation DVD, sold with a manga volume
+    Oad,
+    /// Original net animation, released online
+    Ona,
+    /// Opening without credits, `NCOP`
+    CreditlessOpening,
+    /// Ending without credits, `NCED`
+    CreditlessEnding,
+    Recap,
+    Pilot,
+    Preview,
+}
+
+impl fmt::Display for PackKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Season => "season",
+            Self::SeasonRange => "season_range",
+            Self::CompleteSeries => "complete_series",
+        })
+    }
+}
+
+impl fmt::Display for SpecialKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Special => "special",
+            Self::Ova => "ova",
+            Self::Oad => "oad",
+            Self::Ona => "ona",
+            Self::CreditlessOpening => "creditless_opening",
+            Self::CreditlessEnding => "creditless_ending",
+            Self::Recap => "recap",
+            Self::Pilot => "pilot",
+            Self::Preview => "preview",
+        })
+    }
+}
+
+fn typed<T: std::str::FromStr<Err = std::convert::Infallible>>(value: &str) -> Option<T> {
+    (!value.is_empty()).then(|| value.parse().unwrap_or_else(|never| match never {}))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]